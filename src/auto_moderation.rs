@@ -0,0 +1,380 @@
+use chrono::Utc;
+use itertools::Itertools;
+use serenity::{
+    builder::CreateEmbed,
+    client::Context,
+    model::{
+        channel::{Channel, Message},
+        id::GuildId,
+        Timestamp,
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    database::{
+        storage::Storage,
+        types::{ModerationAction, ModerationRule},
+    },
+    error::KowalskiError,
+    utils::create_embed,
+};
+
+/// Gets the auto-moderation rules of a guild whose conditions a message matches,
+/// leaving out the ones already applied to it.
+pub async fn matching_rules(
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    message: &Message,
+) -> Result<Vec<ModerationRule>, KowalskiError> {
+    let rules = storage.moderation_rules(guild_id).await?;
+
+    // Check whether auto moderation is enabled
+    if rules.is_empty() {
+        return Ok(rules);
+    }
+
+    // Every rule gets applied to a message only once
+    let applied = storage
+        .applied_moderation_rules(message.channel_id, message.id)
+        .await?;
+
+    // Get score and reactions of the message
+    let score = storage
+        .message_score(guild_id, message.channel_id, message.id)
+        .await?;
+    let reactions = storage
+        .message_score_reactions(guild_id, message.channel_id, message.id)
+        .await?
+        .len() as i64;
+    let age = Utc::now().timestamp() - message.timestamp.unix_timestamp();
+
+    // Check whether scores share the same sign and the score is reached
+    let reached = |limit: i64| (score >= 0) == (limit >= 0) && score.abs() >= limit.abs();
+
+    Ok(rules
+        .into_iter()
+        .filter(|rule| !applied.contains(&rule.id))
+        .filter(|rule| {
+            rule.score.is_none_or(reached)
+                && rule.reactions.is_none_or(|limit| reactions >= limit)
+                && rule
+                    .channel
+                    .is_none_or(|channel_id| channel_id == message.channel_id)
+                && rule
+                    .max_age
+                    .is_none_or(|hours| age <= hours.saturating_mul(3600))
+        })
+        .collect())
+}
+
+/// Applies the action of an auto-moderation rule to a message.
+pub async fn apply_rule(
+    ctx: &Context,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    message: &Message,
+    rule: &ModerationRule,
+) -> Result<(), KowalskiError> {
+    match rule.action {
+        ModerationAction::Pin => {
+            if !message.pinned {
+                message.pin(&ctx.http).await?;
+            }
+        }
+        ModerationAction::Delete => message.delete(&ctx.http).await?,
+        ModerationAction::Repost => {
+            let mut embed = CreateEmbed::default();
+            if !message.content.is_empty() {
+                embed.description(&message.content);
+            }
+            embed
+                .author(|author| {
+                    author
+                        .name(&message.author.name)
+                        .icon_url(message.author.face())
+                })
+                .field(
+                    "Source",
+                    format!("[Jump to message]({})", message.link()),
+                    false,
+                )
+                .timestamp(message.timestamp);
+            // Show the first image of the message
+            if let Some(image) = message
+                .attachments
+                .iter()
+                .find(|attachment| attachment.width.is_some())
+            {
+                embed.image(&image.url);
+            }
+
+            rule.target
+                .unwrap()
+                .send_message(&ctx.http, |message| message.set_embeds(vec![embed]))
+                .await?;
+        }
+        ModerationAction::Notify => {
+            let score = storage
+                .message_score(guild_id, message.channel_id, message.id)
+                .await?;
+
+            let embed = create_embed(
+                "Auto-moderation",
+                &format!(
+                    "A [message]({}) of {} in {} matches rule #{} with a score of {}.",
+                    message.link(),
+                    message.author.mention(),
+                    message.channel_id.mention(),
+                    rule.id,
+                    score
+                ),
+            );
+
+            rule.target
+                .unwrap()
+                .send_message(&ctx.http, |message| {
+                    if let Some(role_id) = rule.role {
+                        message.content(role_id.mention());
+                    }
+
+                    message.set_embeds(vec![embed])
+                })
+                .await?;
+        }
+        ModerationAction::Lock => {
+            // Only threads can be locked
+            if let Channel::Guild(channel) = message.channel(ctx).await? {
+                if channel.thread_metadata.is_some() {
+                    channel
+                        .id
+                        .edit_thread(&ctx.http, |thread| thread.locked(true))
+                        .await?;
+                }
+            }
+        }
+        ModerationAction::Timeout => {
+            let until = Timestamp::from_unix_timestamp(
+                Utc::now().timestamp() + rule.duration.unwrap() * 60,
+            )
+            .unwrap();
+
+            let mut member = guild_id.member(ctx, message.author.id).await?;
+            member
+                .disable_communication_until_datetime(&ctx.http, until)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Describes the conditions and the action of an auto-moderation rule.
+pub fn describe_rule(rule: &ModerationRule) -> String {
+    let mut conditions = Vec::new();
+    if let Some(score) = rule.score {
+        conditions.push(format!(
+            "score {} {}",
+            if score >= 0 { ">=" } else { "<=" },
+            score
+        ));
+    }
+    if let Some(reactions) = rule.reactions {
+        conditions.push(format!("at least {} reactions", reactions));
+    }
+    if let Some(channel_id) = rule.channel {
+        conditions.push(format!("in {}", channel_id.mention()));
+    }
+    if let Some(max_age) = rule.max_age {
+        conditions.push(format!("at most {} hours old", max_age));
+    }
+
+    let action = match rule.action {
+        ModerationAction::Pin => "pin the message".to_string(),
+        ModerationAction::Delete => "delete the message".to_string(),
+        ModerationAction::Repost => format!("repost it in {}", rule.target.unwrap().mention()),
+        ModerationAction::Notify => match rule.role {
+            Some(role_id) => format!(
+                "notify {} in {}",
+                role_id.mention(),
+                rule.target.unwrap().mention()
+            ),
+            None => format!("notify in {}", rule.target.unwrap().mention()),
+        },
+        ModerationAction::Lock => "lock the thread".to_string(),
+        ModerationAction::Timeout => {
+            format!("time out the author for {} minutes", rule.duration.unwrap())
+        }
+    };
+
+    format!(
+        "**#{}**: {}: {}",
+        rule.id,
+        conditions.iter().join(", "),
+        action
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use serde_json::json;
+    use serenity::model::id::{ChannelId, MessageId, UserId};
+
+    use super::*;
+    use crate::database::memory::MemoryStorage;
+
+    const GUILD: GuildId = GuildId(1);
+    const CHANNEL: ChannelId = ChannelId(2);
+    const MESSAGE: MessageId = MessageId(3);
+    const AUTHOR: UserId = UserId(4);
+
+    /// Creates a message in the channel sent the given number of hours ago.
+    fn message(hours: i64) -> Message {
+        let timestamp = Utc::now() - Duration::hours(hours);
+
+        serde_json::from_value(json!({
+            "id": MESSAGE.0.to_string(),
+            "channel_id": CHANNEL.0.to_string(),
+            "guild_id": GUILD.0.to_string(),
+            "author": {
+                "id": AUTHOR.0.to_string(),
+                "username": "author",
+                "discriminator": "0001",
+                "avatar": null,
+            },
+            "content": "",
+            "timestamp": timestamp.to_rfc3339(),
+            "edited_timestamp": null,
+            "tts": false,
+            "mention_everyone": false,
+            "mentions": [],
+            "mention_roles": [],
+            "attachments": [],
+            "embeds": [],
+            "pinned": false,
+            "type": 0,
+        }))
+        .unwrap()
+    }
+
+    fn rule(score: Option<i64>, reactions: Option<i64>) -> ModerationRule {
+        ModerationRule {
+            id: 0,
+            score,
+            reactions,
+            channel: None,
+            max_age: None,
+            action: ModerationAction::Pin,
+            target: None,
+            role: None,
+            duration: None,
+        }
+    }
+
+    /// Gives the message votes of the given weights.
+    async fn vote(storage: &MemoryStorage, weights: &[i32]) {
+        for (index, &weight) in weights.iter().enumerate() {
+            storage
+                .vote(
+                    GUILD,
+                    UserId(100 + index as u64),
+                    AUTHOR,
+                    CHANNEL,
+                    MESSAGE,
+                    weight,
+                )
+                .await;
+        }
+    }
+
+    async fn matching_ids(storage: &MemoryStorage, message: &Message) -> Vec<i32> {
+        matching_rules(storage, GUILD, message)
+            .await
+            .unwrap()
+            .iter()
+            .map(|rule| rule.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn matches_score_with_same_sign() {
+        let storage = MemoryStorage::new();
+        let upvoted = storage
+            .add_moderation_rule(GUILD, &rule(Some(3), None))
+            .await
+            .unwrap();
+        let downvoted = storage
+            .add_moderation_rule(GUILD, &rule(Some(-2), None))
+            .await
+            .unwrap();
+        let message = message(0);
+
+        vote(&storage, &[2]).await;
+        assert!(matching_ids(&storage, &message).await.is_empty());
+
+        vote(&storage, &[1]).await;
+        assert_eq!(matching_ids(&storage, &message).await, vec![upvoted]);
+
+        vote(&storage, &[-3, -3, -3]).await;
+        assert_eq!(matching_ids(&storage, &message).await, vec![downvoted]);
+    }
+
+    #[tokio::test]
+    async fn matches_reactions_channel_and_age() {
+        let storage = MemoryStorage::new();
+        let reactions = storage
+            .add_moderation_rule(GUILD, &rule(None, Some(2)))
+            .await
+            .unwrap();
+        let other_channel = storage
+            .add_moderation_rule(
+                GUILD,
+                &ModerationRule {
+                    channel: Some(ChannelId(99)),
+                    ..rule(None, None)
+                },
+            )
+            .await
+            .unwrap();
+        let recent = storage
+            .add_moderation_rule(
+                GUILD,
+                &ModerationRule {
+                    max_age: Some(2),
+                    ..rule(None, None)
+                },
+            )
+            .await
+            .unwrap();
+
+        vote(&storage, &[1, -1]).await;
+        assert_eq!(
+            matching_ids(&storage, &message(1)).await,
+            vec![reactions, recent]
+        );
+        assert_eq!(matching_ids(&storage, &message(3)).await, vec![reactions]);
+        assert!(!matching_ids(&storage, &message(0))
+            .await
+            .contains(&other_channel));
+    }
+
+    #[tokio::test]
+    async fn skips_applied_rules() {
+        let storage = MemoryStorage::new();
+        let id = storage
+            .add_moderation_rule(GUILD, &rule(Some(1), None))
+            .await
+            .unwrap();
+        let message = message(0);
+
+        vote(&storage, &[1]).await;
+        assert_eq!(matching_ids(&storage, &message).await, vec![id]);
+
+        storage
+            .trigger_moderation_rule(GUILD, id, CHANNEL, MESSAGE)
+            .await
+            .unwrap();
+        assert!(matching_ids(&storage, &message).await.is_empty());
+    }
+}
@@ -4,10 +4,14 @@ use ics::{
     Event, ICalendar,
 };
 use rocket::{get, routes, State};
-use serenity::{client::Context, model::id::GuildId};
+use serenity::client::Context;
 use tracing::error;
 
-use crate::{data, database::client::Database, strings::ERR_CALENDAR};
+use crate::{
+    data,
+    database::{client::Database, storage::Storage},
+    strings::ERR_CALENDAR,
+};
 
 pub fn host_calendar(ctx: Context) {
     tokio::spawn(async move {
@@ -28,21 +32,7 @@ async fn events(ctx: &State<Context>, id: String) -> Option<String> {
     let database = data!(ctx, Database);
 
     // Get guild id
-    let guild_id = {
-        let row = database
-            .client
-            .query_opt(
-                "
-            SELECT guild FROM publishing
-            Where id = $1::TEXT
-        ",
-                &[&id],
-            )
-            .await
-            .unwrap_or_default();
-
-        row.map(|row| GuildId(row.get::<_, i64>(0) as u64))
-    };
+    let guild_id = database.publishing_guild(&id).await.unwrap_or_default();
 
     const FORMAT: &str = "%Y%m%dT%H%M%SZ";
 
@@ -0,0 +1,91 @@
+use plotters::prelude::*;
+
+use crate::error::KowalskiError;
+
+/// The colors of the lines of a chart and the emojis representing them in a legend.
+pub const CHART_COLORS: [((u8, u8, u8), &str); 6] = [
+    ((237, 66, 69), "🟥"),
+    ((88, 101, 242), "🟦"),
+    ((87, 242, 135), "🟩"),
+    ((254, 231, 92), "🟨"),
+    ((155, 89, 182), "🟪"),
+    ((230, 126, 34), "🟧"),
+];
+
+/// Renders a line chart of the given series as a PNG image, coloring them by CHART_COLORS.
+///
+/// Note: The chart contains no text, as fonts are not guaranteed to be available
+pub fn render_line_chart(
+    width: u32,
+    height: u32,
+    days: i64,
+    series: &[Vec<(i64, i64)>],
+) -> Result<Vec<u8>, KowalskiError> {
+    let mut buffer = vec![0; (width * height * 3) as usize];
+
+    draw_lines(&mut buffer, width, height, days, series)
+        .map_err(|why| KowalskiError::ChartError(why.to_string()))?;
+
+    // Encode the raw pixels
+    let mut image = Vec::new();
+    let mut encoder = png::Encoder::new(&mut image, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&buffer))
+        .map_err(|why| KowalskiError::ChartError(why.to_string()))?;
+
+    Ok(image)
+}
+
+fn draw_lines(
+    buffer: &mut [u8],
+    width: u32,
+    height: u32,
+    days: i64,
+    series: &[Vec<(i64, i64)>],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = BitMapBackend::with_buffer(buffer, (width, height)).into_drawing_area();
+    // Use the background color of embeds
+    root.fill(&RGBColor(47, 49, 54))?;
+
+    // Leave some space above and below the scores
+    let (min, max) = series
+        .iter()
+        .flatten()
+        .fold((0, 0), |(min, max), &(_, score)| {
+            (score.min(min), score.max(max))
+        });
+    let padding = ((max - min) / 10).max(1);
+
+    let mut chart = ChartBuilder::on(&root)
+        .margin(20)
+        .build_cartesian_2d(0..days, min - padding..max + padding)?;
+
+    chart
+        .configure_mesh()
+        .disable_axes()
+        .x_labels(8)
+        .y_labels(6)
+        .light_line_style(TRANSPARENT)
+        .bold_line_style(RGBColor(79, 84, 92))
+        .draw()?;
+
+    // Mark the zero line
+    chart.draw_series(LineSeries::new(
+        [(0, 0), (days, 0)],
+        RGBColor(185, 187, 190).stroke_width(1),
+    ))?;
+
+    for (points, ((red, green, blue), _)) in series.iter().zip(CHART_COLORS) {
+        chart.draw_series(LineSeries::new(
+            points.iter().copied(),
+            RGBColor(red, green, blue).stroke_width(3),
+        ))?;
+    }
+
+    root.present()?;
+
+    Ok(())
+}
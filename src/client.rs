@@ -54,10 +54,12 @@ impl Client {
         {
             let mut data = client.data.write().await;
 
-            // Add config to data
-            data.insert::<Config>(Arc::new(Config::new().await?));
+            let config = Config::new().await?;
+
             // Add database to data
-            data.insert::<Database>(Arc::new(Database::new().await?));
+            data.insert::<Database>(Arc::new(Database::new(&config).await?));
+            // Add config to data
+            data.insert::<Config>(Arc::new(config));
             // Add cooldowns to data
             data.insert::<Cooldowns>(Arc::new(RwLock::new(Cooldowns::new())));
             // Add credits to data
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::{Command, Config},
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    leaderboard::Period,
+    utils::{parse_arg, send_response_complex},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    let options = &command.data.options;
+
+    // Parse argument (use the last month as fallback)
+    let period = if !options.is_empty() {
+        Period::from_str(parse_arg(options, 0)?)?
+    } else {
+        Period::Month
+    };
+
+    let guild_id = command.guild_id.unwrap();
+
+    // Look for suspicious voting patterns
+    let start = period.start();
+    let reciprocal = database
+        .reciprocal_votes(
+            guild_id,
+            start,
+            config.general.abuse_min_score,
+            config.general.abuse_report_size,
+        )
+        .await?;
+    let dominant = database
+        .dominant_givers(
+            guild_id,
+            start,
+            config.general.abuse_min_score,
+            config.general.abuse_max_share,
+            config.general.abuse_report_size,
+        )
+        .await?;
+    let bursts = database
+        .new_account_bursts(
+            guild_id,
+            start,
+            config.general.abuse_account_age,
+            config.general.abuse_burst_size,
+            config.general.abuse_report_size,
+        )
+        .await?;
+
+    // Get the maximum score a single user can give another user
+    let cap = database.score_cap(guild_id).await?;
+
+    let mut content = format!(
+        "These voting patterns of {} might be worth a closer look.",
+        period
+    );
+    if let Some(cap) = cap {
+        content.push_str(&format!(
+            "\nA single user can give another user a score of at most **{}**.",
+            cap
+        ));
+    }
+
+    send_response_complex(
+        ctx,
+        command,
+        command_config,
+        "Voting abuse",
+        &content,
+        |embed| {
+            let mut reciprocal = reciprocal
+                .iter()
+                .map(|votes| {
+                    format!(
+                        "{} ⇄ {}: **{}** / **{}**",
+                        votes.users.0.mention(),
+                        votes.users.1.mention(),
+                        votes.scores.0,
+                        votes.scores.1
+                    )
+                })
+                .join("\n");
+            if reciprocal.is_empty() {
+                reciprocal = "Not available".to_string();
+            }
+
+            let mut dominant = dominant
+                .iter()
+                .map(|giver| {
+                    format!(
+                        "{} → {}: **{}** of {} ({:.1}%)",
+                        giver.user_from.mention(),
+                        giver.user_to.mention(),
+                        giver.score,
+                        giver.total,
+                        giver.score as f64 / giver.total as f64 * 100f64
+                    )
+                })
+                .join("\n");
+            if dominant.is_empty() {
+                dominant = "Not available".to_string();
+            }
+
+            let mut bursts = bursts
+                .iter()
+                .map(|burst| {
+                    format!(
+                        "{}: **{}** upvotes at <t:{}:f>",
+                        burst.user.mention(),
+                        burst.upvotes,
+                        burst.hour.timestamp()
+                    )
+                })
+                .join("\n");
+            if bursts.is_empty() {
+                bursts = "Not available".to_string();
+            }
+
+            embed.fields(vec![
+                ("Reciprocal voting", reciprocal, false),
+                ("Dominant givers", dominant, false),
+                ("Bursts from new accounts", bursts, false),
+            ])
+        },
+        Vec::new(),
+    )
+    .await
+}
@@ -0,0 +1,84 @@
+use serenity::{
+    client::Context,
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::Channel,
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::LevelUpAnnouncement},
+    error::KowalskiError,
+    utils::{parse_arg, parse_arg_resolved, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    // Parse arguments
+    let mut announcement = LevelUpAnnouncement {
+        channel: None,
+        level_up: None,
+        level_down: None,
+    };
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "channel" => match parse_arg_resolved(options, i)? {
+                Channel(channel) => announcement.channel = Some(channel.id),
+                _ => unreachable!(),
+            },
+            "level_up" => announcement.level_up = Some(parse_arg(options, i)?),
+            "level_down" => announcement.level_down = Some(parse_arg(options, i)?),
+            _ => unreachable!(),
+        }
+    }
+
+    let title = "Level-up announcements";
+
+    // Without messages there is nothing to announce
+    if announcement.level_up.is_none() && announcement.level_down.is_none() {
+        // Delete entry
+        database.set_level_up_announcement(guild_id, None).await?;
+
+        return send_response(
+            ctx,
+            command,
+            command_config,
+            title,
+            "Level-ups and level-downs are not announced anymore.",
+        )
+        .await;
+    }
+
+    // Insert or update entry
+    database
+        .set_level_up_announcement(guild_id, Some(&announcement))
+        .await?;
+
+    let announced = match (&announcement.level_up, &announcement.level_down) {
+        (Some(_), Some(_)) => "Level-ups and level-downs",
+        (Some(_), None) => "Level-ups",
+        _ => "Level-downs",
+    };
+    let content = match announcement.channel {
+        Some(channel_id) => format!(
+            "{} are now announced in {}.",
+            announced,
+            channel_id.mention()
+        ),
+        None => format!("{} are now announced through direct messages.", announced),
+    };
+
+    send_response(ctx, command, command_config, title, &content).await
+}
@@ -0,0 +1,58 @@
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::{parse_arg, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    let title = "Score cap";
+
+    if !options.is_empty() {
+        // Parse argument
+        let cap: i64 = parse_arg(options, 0)?;
+
+        // Insert or update entry
+        database.set_score_cap(guild_id, Some(cap)).await?;
+
+        send_response(
+            ctx,
+            command,
+            command_config,
+            title,
+            &format!(
+                "A single user can now give another user a score of at most **{}**.",
+                cap
+            ),
+        )
+        .await
+    } else {
+        // Delete entry
+        database.set_score_cap(guild_id, None).await?;
+
+        send_response(
+            ctx,
+            command,
+            command_config,
+            title,
+            "There is no limit anymore on the score a single user can give another user.",
+        )
+        .await
+    }
+}
@@ -0,0 +1,72 @@
+use serenity::{
+    client::Context,
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::Channel,
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::ChannelScoring},
+    error::KowalskiError,
+    utils::{parse_arg, parse_arg_resolved, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    // Parse arguments
+    let mut scoring = None;
+    let mut multiplier = None;
+    let mut excluded = false;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "channel" => match parse_arg_resolved(options, i)? {
+                Channel(channel) => scoring = Some(ChannelScoring::new(channel.id)),
+                _ => unreachable!(),
+            },
+            "multiplier" => multiplier = Some(parse_arg::<i64>(options, i)?),
+            "excluded" => excluded = parse_arg(options, i)?,
+            _ => unreachable!(),
+        }
+    }
+    let mut scoring = scoring.unwrap();
+    scoring.multiplier = multiplier.unwrap_or(1) as i32;
+    scoring.excluded = excluded;
+
+    let title = "Channel scoring";
+
+    // Insert or update entry
+    database.set_channel_scoring(guild_id, &scoring).await?;
+
+    let content = if scoring.excluded {
+        format!(
+            "Score emojis are now ignored in {}.",
+            scoring.channel.mention()
+        )
+    } else if scoring.multiplier != 1 {
+        format!(
+            "Reactions in {} now count {} times as much.",
+            scoring.channel.mention(),
+            scoring.multiplier
+        )
+    } else {
+        format!(
+            "Reactions in {} now count normally.",
+            scoring.channel.mention()
+        )
+    };
+
+    send_response(ctx, command, command_config, title, &content).await
+}
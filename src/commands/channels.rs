@@ -0,0 +1,67 @@
+use itertools::Itertools;
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::send_response,
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    // Get the channels in which reactions don't count normally
+    let scorings = database.channel_scorings(guild_id).await?;
+
+    let title = "Channels";
+
+    if scorings.is_empty() {
+        return send_response(
+            ctx,
+            command,
+            command_config,
+            title,
+            "Reactions count the same in all channels.",
+        )
+        .await;
+    }
+
+    let multiplied = scorings
+        .iter()
+        .filter(|scoring| !scoring.excluded)
+        .map(|scoring| format!("{}: ×{}", scoring.channel.mention(), scoring.multiplier))
+        .join("\n");
+    let excluded = scorings
+        .iter()
+        .filter(|scoring| scoring.excluded)
+        .map(|scoring| scoring.channel.mention().to_string())
+        .join(", ");
+
+    let mut content = String::new();
+    if !multiplied.is_empty() {
+        content.push_str(&format!(
+            "Reactions in the following channels count more:\n{}\n\n",
+            multiplied
+        ));
+    }
+    if !excluded.is_empty() {
+        content.push_str(&format!(
+            "Score emojis are ignored in the following channels: {}",
+            excluded
+        ));
+    }
+
+    send_response(ctx, command, command_config, title, content.trim_end()).await
+}
@@ -1,3 +1,8 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
 use serenity::{
     client::Context, model::interactions::application_command::ApplicationCommandInteraction,
     model::interactions::application_command::ApplicationCommandInteractionDataOptionValue::Role,
@@ -7,11 +12,51 @@ use serenity::{
 use crate::{
     config::Command,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
+    error::KowalskiError::DiscordApiError,
+    pluralize,
+    strings::ERR_CMD_ARGS_INVALID,
     utils::{parse_arg, parse_arg_resolved, send_response},
 };
 
+enum Policy {
+    Role,
+    Receiver,
+    Downvote,
+    DailyBudget,
+    Window,
+}
+
+impl Display for Policy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Policy::Role => "Role",
+            Policy::Receiver => "Receiver",
+            Policy::Downvote => "Downvote",
+            Policy::DailyBudget => "Daily budget",
+            Policy::Window => "Window",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Policy {
+    type Err = KowalskiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "role" => Ok(Policy::Role),
+            "receiver" => Ok(Policy::Receiver),
+            "downvote" => Ok(Policy::Downvote),
+            "daily budget" => Ok(Policy::DailyBudget),
+            "window" => Ok(Policy::Window),
+            _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+        }
+    }
+}
+
 pub async fn execute(
     ctx: &Context,
     command: &ApplicationCommandInteraction,
@@ -20,73 +65,112 @@ pub async fn execute(
     // Get database
     let database = data!(ctx, Database);
 
-    let options = &command.data.options;
+    let guild_id = command.guild_id.unwrap();
 
-    // Parse first argument
-    let role = match parse_arg_resolved(options, 0)? {
-        Role(role) => role,
-        _ => unreachable!(),
-    };
+    let options = &command.data.options;
 
-    // Get guild and role ids
-    let guild_db_id = database.get_guild(role.guild_id).await?;
-    let role_db_id = database.get_role(role.guild_id, role.id).await?;
+    // Parse arguments
+    let mut policy = None;
+    let mut role = None;
+    let mut cooldown = None;
+    let mut votes = None;
+    let mut minutes = 60;
+    let mut enabled = false;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "policy" => policy = Some(Policy::from_str(parse_arg(options, i)?)?),
+            "role" => match parse_arg_resolved(options, i)? {
+                Role(resolved) => role = Some(resolved),
+                _ => unreachable!(),
+            },
+            "cooldown" => cooldown = Some(parse_arg::<i64>(options, i)?),
+            "votes" => votes = Some(parse_arg::<i64>(options, i)?),
+            "minutes" => minutes = parse_arg(options, i)?,
+            "enabled" => enabled = parse_arg(options, i)?,
+            _ => unreachable!(),
+        }
+    }
+    let policy = policy.unwrap();
 
-    let title = format!("Set cooldown for {}", role.name);
+    let title = format!("{} cooldown", policy);
 
-    if options.len() > 1 {
-        // Parse second argument
-        let cooldown: i64 = parse_arg(options, 1)?;
+    // Role cooldowns are not part of the policy of the guild
+    if let Policy::Role = policy {
+        let role = role.ok_or(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()))?;
 
-        // Insert or update entry
+        // Insert, update or delete entry
         database
-            .client
-            .execute(
-                "
-        INSERT INTO score_cooldowns
-        VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT)
-        ON CONFLICT (guild, role)
-        DO UPDATE SET cooldown = $3::BIGINT
-        ",
-                &[&guild_db_id, &role_db_id, &cooldown],
-            )
+            .set_role_cooldown(guild_id, role.id, cooldown)
             .await?;
 
-        send_response(
-            &ctx,
-            &command,
-            command_config,
-            &title,
-            &format!(
+        let content = match cooldown {
+            Some(cooldown) => format!(
                 "The role {} now has a reaction-cooldown of {} seconds.",
                 role.mention(),
                 cooldown
             ),
-        )
-        .await
-    } else {
-        // Delete cooldown
-        database
-            .client
-            .execute(
-                "
-        DELETE FROM score_cooldowns
-        WHERE guild = $1::BIGINT AND role = $2::BIGINT
-        ",
-                &[&guild_db_id, &role_db_id],
-            )
-            .await?;
-
-        send_response(
-            &ctx,
-            &command,
-            command_config,
-            &title,
-            &format!(
+            None => format!(
                 "The role {} now has the default reaction-cooldown.",
                 role.mention()
             ),
-        )
-        .await
+        };
+
+        return send_response(ctx, command, command_config, &title, &content).await;
     }
+
+    let mut cooldown_policy = database.cooldown_policy(guild_id).await?;
+
+    let content = match policy {
+        Policy::Role => unreachable!(),
+        Policy::Receiver => {
+            cooldown_policy.per_receiver = enabled;
+
+            if enabled {
+                "The reaction-cooldown now only applies to voting the same user again.".to_string()
+            } else {
+                "The reaction-cooldown now applies to voting any user.".to_string()
+            }
+        }
+        Policy::Downvote => {
+            cooldown_policy.downvote_cooldown = cooldown;
+
+            match cooldown {
+                Some(cooldown) => format!(
+                    "Downvotes now have a separate reaction-cooldown of {} seconds.",
+                    cooldown
+                ),
+                None => "Downvotes now share the reaction-cooldown of upvotes.".to_string(),
+            }
+        }
+        Policy::DailyBudget => {
+            cooldown_policy.daily_budget = votes;
+
+            match votes {
+                Some(votes) => format!(
+                    "Users can now give at most {} per day.",
+                    pluralize!("vote", votes)
+                ),
+                None => "Users can now give as many votes per day as they want.".to_string(),
+            }
+        }
+        Policy::Window => {
+            cooldown_policy.window = votes.map(|votes| (votes, minutes));
+
+            match votes {
+                Some(votes) => format!(
+                    "Users can now give at most {} within {}.",
+                    pluralize!("vote", votes),
+                    pluralize!("minute", minutes)
+                ),
+                None => "Users can now give as many votes in a row as they want.".to_string(),
+            }
+        }
+    };
+
+    // Insert, update or delete entry
+    database
+        .set_cooldown_policy(guild_id, &cooldown_policy)
+        .await?;
+
+    send_response(ctx, command, command_config, &title, &content).await
 }
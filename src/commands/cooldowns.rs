@@ -1,12 +1,16 @@
 use itertools::Itertools;
 use serenity::{
-    client::Context,
-    model::{id::RoleId, interactions::application_command::ApplicationCommandInteraction},
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
     prelude::Mentionable,
 };
 
 use crate::{
-    config::Command, config::Config, data, database::client::Database, error::KowalskiError,
+    config::Command,
+    config::Config,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    pluralize,
     utils::send_response,
 };
 
@@ -20,27 +24,8 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get roles and their respective cooldowns
-    let role_cooldowns: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-                SELECT role, cooldown FROM score_cooldowns
-                WHERE guild = $1::BIGINT
-                ORDER BY cooldown
-                ",
-                &[&guild_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| (RoleId(row.get::<_, i64>(0) as u64), row.get::<_, i64>(1)))
-            .collect()
-    };
+    let role_cooldowns = database.role_cooldowns(guild_id).await?;
 
     let role_cooldowns = role_cooldowns
         .iter()
@@ -50,32 +35,50 @@ pub async fn execute(
     // Get default cooldown
     let default_cooldown = config.general.default_cooldown;
 
-    if role_cooldowns.is_empty() {
-        send_response(
-            &ctx,
-            &command,
-            &command_config,
-            "Cooldowns",
-            &format!(
-                "Everyone has a reaction cooldown of {} seconds.",
-                default_cooldown
-            ),
+    let mut content = if role_cooldowns.is_empty() {
+        format!(
+            "Everyone has a reaction cooldown of {} seconds.",
+            default_cooldown
         )
-        .await
     } else {
-        send_response(
-            &ctx,
-            &command,
-            &command_config,
-            "Cooldowns",
-            &format!(
-                "The default reaction cooldown is set to {} seconds.
+        format!(
+            "The default reaction cooldown is set to {} seconds.
 
-                The following roles have custom cooldowns defined (smallest applies):
-                {}",
-                default_cooldown, role_cooldowns
-            ),
+            The following roles have custom cooldowns defined (smallest applies):
+            {}",
+            default_cooldown, role_cooldowns
         )
-        .await
+    };
+
+    // Get the rules limiting how often users can vote
+    let policy = database.cooldown_policy(guild_id).await?;
+
+    let mut rules = Vec::new();
+    if policy.per_receiver {
+        rules.push("The cooldown only applies to voting the same user again.".to_string());
     }
+    if let Some(cooldown) = policy.downvote_cooldown {
+        rules.push(format!(
+            "Downvotes have a separate cooldown of {} seconds.",
+            cooldown
+        ));
+    }
+    if let Some(votes) = policy.daily_budget {
+        rules.push(format!(
+            "Users can give at most {} per day.",
+            pluralize!("vote", votes)
+        ));
+    }
+    if let Some((votes, minutes)) = policy.window {
+        rules.push(format!(
+            "Users can give at most {} within {}.",
+            pluralize!("vote", votes),
+            pluralize!("minute", minutes)
+        ));
+    }
+    if !rules.is_empty() {
+        content.push_str(&format!("\n\n{}", rules.join("\n")));
+    }
+
+    send_response(ctx, command, command_config, "Cooldowns", &content).await
 }
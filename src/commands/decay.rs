@@ -0,0 +1,76 @@
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::ScoreDecay},
+    error::KowalskiError,
+    pluralize,
+    utils::{parse_arg, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    // Parse arguments
+    let mut half_life = None;
+    let mut levelups = false;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "half_life" => half_life = Some(parse_arg::<i64>(options, i)?),
+            "levelups" => levelups = parse_arg(options, i)?,
+            _ => unreachable!(),
+        }
+    }
+
+    let title = "Score decay";
+
+    match half_life {
+        Some(half_life) => {
+            // Insert or update entry
+            database
+                .set_score_decay(
+                    guild_id,
+                    Some(&ScoreDecay {
+                        half_life,
+                        levelups,
+                    }),
+                )
+                .await?;
+
+            let mut content = format!(
+                "Reactions now count half as much every {}.",
+                pluralize!("day", half_life)
+            );
+            if levelups {
+                content.push_str("\nLevel-up roles are based on the decayed score.");
+            }
+
+            send_response(ctx, command, command_config, title, &content).await
+        }
+        None => {
+            // Delete entry
+            database.set_score_decay(guild_id, None).await?;
+
+            send_response(
+                ctx,
+                command,
+                command_config,
+                title,
+                "Reactions now count the same no matter how old they are.",
+            )
+            .await
+        }
+    }
+}
@@ -14,7 +14,7 @@ use serenity::{
 use crate::{
     config::Command,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage, types::DropMode},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
     strings::ERR_CMD_ARGS_INVALID,
@@ -24,6 +24,7 @@ use crate::{
 enum Action {
     Add,
     Remove,
+    Mode(DropMode),
 }
 
 impl Display for Action {
@@ -31,6 +32,7 @@ impl Display for Action {
         let name = match self {
             Action::Add => "Add",
             Action::Remove => "Remove",
+            Action::Mode(_) => "Mode",
         };
 
         write!(f, "{}", name)
@@ -44,7 +46,9 @@ impl FromStr for Action {
         match s {
             "add" => Ok(Action::Add),
             "remove" => Ok(Action::Remove),
-            _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+            _ => DropMode::from_name(s)
+                .map(Action::Mode)
+                .ok_or_else(|| DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
         }
     }
 }
@@ -60,18 +64,50 @@ pub async fn execute(
     let options = &command.data.options;
 
     // Parse arguments
-    let action = Action::from_str(parse_arg(options, 0)?).unwrap();
-    let partial_channel = match parse_arg_resolved(options, 1)? {
-        Channel(channel) => channel,
-        _ => unreachable!(),
-    };
-    let channel = partial_channel.id.to_channel(&ctx.http).await?;
+    let mut action = None;
+    let mut partial_channel = None;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "action" => action = Some(Action::from_str(parse_arg(options, i)?)?),
+            "channel" => match parse_arg_resolved(options, i)? {
+                Channel(resolved) => partial_channel = Some(resolved),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+    }
+    let action = action.unwrap();
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild and channel ids
-    let guild_db_id = database.get_guild(guild_id).await?;
-    let channel_db_id = database.get_channel(guild_id, partial_channel.id).await?;
+    // Switch how the score gets dropped
+    if let Action::Mode(mode) = action {
+        database.set_drop_mode(guild_id, mode).await?;
+
+        let content = match mode {
+            DropMode::FirstPickup => {
+                "The first user to pick up a dropped score now gets all of it."
+            }
+            DropMode::Lottery => {
+                "A dropped score now goes to a random user out of the ones picking it up."
+            }
+            DropMode::Split => {
+                "A dropped score now gets split evenly among the users picking it up."
+            }
+            DropMode::ReturnToGivers => {
+                "The upvotes of users leaving the guild now go back to the users who gave them."
+            }
+        };
+
+        return send_response(ctx, command, command_config, "Drop mode", content).await;
+    }
+
+    // Adding and removing drops requires a channel
+    let partial_channel = match partial_channel {
+        Some(partial_channel) => partial_channel,
+        None => return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+    };
+    let channel = partial_channel.id.to_channel(&ctx.http).await?;
 
     let title = format!(
         "{} drops for channel {}",
@@ -82,16 +118,7 @@ pub async fn execute(
     match action {
         Action::Add => {
             database
-                .client
-                .execute(
-                    "
-            INSERT INTO score_drops
-            VALUES($1::BIGINT, $2::BIGINT)
-            ON CONFLICT
-            DO NOTHING
-            ",
-                    &[&guild_db_id, &channel_db_id],
-                )
+                .add_drop_channel(guild_id, partial_channel.id)
                 .await?;
 
             send_response(
@@ -107,18 +134,11 @@ pub async fn execute(
             .await
         }
         Action::Remove => {
-            let modified = database
-                .client
-                .execute(
-                    "
-            DELETE FROM score_drops
-            WHERE guild = $1::BIGINT AND channel = $2::BIGINT
-            ",
-                    &[&guild_db_id, &channel_db_id],
-                )
+            let removed = database
+                .remove_drop_channel(guild_id, partial_channel.id)
                 .await?;
 
-            if modified == 0 {
+            if !removed {
                 send_response(
                     &ctx,
                     &command,
@@ -145,5 +165,6 @@ pub async fn execute(
                     .await
             }
         }
+        Action::Mode(_) => unreachable!(),
     }
 }
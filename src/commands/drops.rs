@@ -1,12 +1,15 @@
 use itertools::Itertools;
 use serenity::{
-    client::Context,
-    model::{id::ChannelId, interactions::application_command::ApplicationCommandInteraction},
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
     prelude::Mentionable,
 };
 
 use crate::{
-    config::Command, data, database::client::Database, error::KowalskiError, utils::send_response,
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::send_response,
 };
 
 pub async fn execute(
@@ -19,23 +22,8 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get all channels where the channel is activated
-    let channels: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "SELECT channel FROM score_drops WHERE guild = $1",
-                &[&guild_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| ChannelId(row.get::<_, i64>(0) as u64))
-            .collect()
-    };
+    let channels = database.drop_channels(guild_id).await?;
 
     let channels = channels
         .iter()
@@ -52,14 +40,19 @@ pub async fn execute(
         )
         .await
     } else {
+        // Get how the score gets handed out
+        let mode = database.drop_mode(guild_id).await?;
+
         send_response(
             &ctx,
             &command,
             &command_config,
             "Drops",
             &format!(
-                "Drops are currently activated for the following channels: {}",
-                channels
+                "Drops are currently activated for the following channels: {}
+                Dropped scores are handed out by mode **{}**.",
+                channels,
+                mode.name()
             ),
         )
         .await
@@ -17,7 +17,7 @@ use unicode_segmentation::UnicodeSegmentation;
 use crate::{
     config::{Command, Config},
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
     strings::ERR_CMD_ARGS_INVALID,
@@ -65,9 +65,6 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     let options = &command.data.options;
 
     // Parse arguments
@@ -103,6 +100,11 @@ pub async fn execute(
             }
         }
     };
+    let weight = if options.len() > 2 {
+        parse_arg::<i32>(options, 2)?
+    } else {
+        1
+    };
 
     let title = format!("{} emoji", action);
 
@@ -113,28 +115,24 @@ pub async fn execute(
 
             match action {
                 Action::AddUpvote | Action::AddDownvote => {
-                    let upvote = matches!(action, Action::AddUpvote);
+                    // Downvotes subtract their weight from the score
+                    let weight = match action {
+                        Action::AddUpvote => weight,
+                        _ => -weight,
+                    };
 
                     // Insert entry
-                    database
-                        .client
-                        .execute(
-                            "
-                    INSERT INTO score_emojis
-                    VALUES ($1::BIGINT, $2::INT, $3::BOOLEAN)
-                    ON CONFLICT (guild, emoji)
-                    DO UPDATE SET upvote = $3::BOOL
-                    ",
-                            &[&guild_db_id, &emoji_id, &upvote],
-                        )
-                        .await?;
+                    database.set_score_emoji(guild_id, emoji_id, weight).await?;
 
                     send_response(
                         &ctx,
                         &command,
                         command_config,
                         &title,
-                        &format!("I am now listening to the emoji {}.", emoji),
+                        &format!(
+                            "I am now listening to the emoji {}, which counts as **{:+}**.",
+                            emoji, weight
+                        ),
                     )
                     .await
                 }
@@ -156,15 +154,7 @@ pub async fn execute(
                     match response {
                         Some(InteractionResponse::Continue) => {
                             // Delete entries
-                            database
-                                .client
-                                .execute(
-                                    "
-                                    DELETE FROM score_emojis
-                                    WHERE emoji = $1::INT",
-                                    &[&emoji_id],
-                                )
-                                .await?;
+                            database.remove_score_emoji(guild_id, emoji_id).await?;
 
                             send_response(
                                 &ctx,
@@ -2,13 +2,16 @@ use itertools::Itertools;
 use serenity::{
     client::Context,
     model::{
-        channel::ReactionType, id::EmojiId,
-        interactions::application_command::ApplicationCommandInteraction,
+        channel::ReactionType, interactions::application_command::ApplicationCommandInteraction,
     },
 };
 
 use crate::{
-    config::Command, data, database::client::Database, error::KowalskiError, utils::send_response,
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::StoredEmoji},
+    error::KowalskiError,
+    utils::send_response,
 };
 
 pub async fn execute(
@@ -21,35 +24,18 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get up- and downvote emojis
     let (upvotes, downvotes) = {
-        let rows = database
-            .client
-            .query(
-                "
-                SELECT unicode, guild_emoji, upvote FROM score_emojis se
-                INNER JOIN emojis e ON se.emoji = e.id
-                WHERE se.guild = $1::BIGINT
-                ",
-                &[&guild_db_id],
-            )
-            .await?;
+        let score_emojis = database.score_emojis(guild_id).await?;
 
         let mut upvotes = Vec::new();
         let mut downvotes = Vec::new();
 
-        for row in rows {
-            let unicode: Option<String> = row.get(0);
-            let guild_emoji: Option<i64> = row.get(1);
-            let upvote: bool = row.get(2);
-
-            let emoji = match (unicode, guild_emoji) {
-                (Some(string), _) => ReactionType::Unicode(string),
-                (_, Some(id)) => {
-                    let emoji = guild_id.emoji(&ctx.http, EmojiId(id as u64)).await?;
+        for (emoji, weight) in score_emojis {
+            let emoji = match emoji {
+                StoredEmoji::Unicode(string) => ReactionType::Unicode(string),
+                StoredEmoji::Guild(id) => {
+                    let emoji = guild_id.emoji(&ctx.http, id).await?;
 
                     ReactionType::Custom {
                         animated: emoji.animated,
@@ -57,13 +43,12 @@ pub async fn execute(
                         name: Some(emoji.name),
                     }
                 }
-                _ => unreachable!(),
             };
 
-            if upvote {
-                upvotes.push(emoji);
+            if weight > 0 {
+                upvotes.push((emoji, weight));
             } else {
-                downvotes.push(emoji);
+                downvotes.push((emoji, weight));
             }
         }
 
@@ -88,14 +73,20 @@ pub async fn execute(
         if !upvotes.is_empty() {
             content.push_str(&format!(
                 "**Upvotes:** {}\n",
-                upvotes.iter().map(|emoji| emoji.to_string()).join(", ")
+                upvotes
+                    .iter()
+                    .map(|(emoji, weight)| format!("{} ({:+})", emoji, weight))
+                    .join(", ")
             ));
         }
 
         if !downvotes.is_empty() {
             content.push_str(&format!(
                 "**Downvotes:** {}\n",
-                downvotes.iter().map(|emoji| emoji.to_string()).join(", ")
+                downvotes
+                    .iter()
+                    .map(|(emoji, weight)| format!("{} ({:+})", emoji, weight))
+                    .join(", ")
             ));
         }
 
@@ -0,0 +1,150 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use serde::Serialize;
+use serenity::{
+    client::Context,
+    model::{
+        channel::AttachmentType, interactions::application_command::ApplicationCommandInteraction,
+    },
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::client::Database,
+    error::KowalskiError,
+    leaderboard::{self, Period},
+    utils::{get_members, parse_arg, send_response, send_response_attachment},
+};
+
+/// A user of the exported leaderboard.
+#[derive(Serialize)]
+struct Row {
+    rank: Option<usize>,
+    user_id: String,
+    name: String,
+    score: i64,
+    upvotes: i64,
+    downvotes: i64,
+    given_upvotes: i64,
+    given_downvotes: i64,
+    gifted: i64,
+}
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let options = &command.data.options;
+
+    // Parse argument
+    let format: String = parse_arg(options, 0)?;
+
+    // Get guild
+    let guild_id = command.guild_id.unwrap();
+
+    // Get the scores and the given reactions of all users
+    let scores = leaderboard::scores(database.as_ref(), guild_id, Period::AllTime).await?;
+    let mut givens: HashMap<_, _> = leaderboard::givens(database.as_ref(), guild_id)
+        .await?
+        .into_iter()
+        .map(|(user, upvotes, downvotes, gifted)| (user, (upvotes, downvotes, gifted)))
+        .collect();
+
+    if scores.is_empty() && givens.is_empty() {
+        return send_response(
+            ctx,
+            command,
+            command_config,
+            "Leaderboard Export",
+            "Looks like there are no scores to export :(",
+        )
+        .await;
+    }
+
+    // Get the names of the users which are still members of the guild
+    let names: HashMap<_, _> = get_members(ctx, guild_id)
+        .await?
+        .into_iter()
+        .map(|member| (member.user.id, member.user.name))
+        .collect();
+
+    // Rank the users by score, followed by the users who only gave reactions
+    let mut rows: Vec<_> = scores
+        .into_iter()
+        .enumerate()
+        .map(|(index, (user, upvotes, downvotes))| {
+            let (given_upvotes, given_downvotes, gifted) = givens.remove(&user).unwrap_or_default();
+
+            Row {
+                rank: Some(index + 1),
+                user_id: user.to_string(),
+                name: names.get(&user).cloned().unwrap_or_default(),
+                score: upvotes - downvotes,
+                upvotes,
+                downvotes,
+                given_upvotes,
+                given_downvotes,
+                gifted,
+            }
+        })
+        .collect();
+    let mut givers: Vec<_> = givens.into_iter().collect();
+    givers.sort_by_key(|&(user, _)| user);
+    rows.extend(
+        givers
+            .into_iter()
+            .map(|(user, (given_upvotes, given_downvotes, gifted))| Row {
+                rank: None,
+                user_id: user.to_string(),
+                name: names.get(&user).cloned().unwrap_or_default(),
+                score: 0,
+                upvotes: 0,
+                downvotes: 0,
+                given_upvotes,
+                given_downvotes,
+                gifted,
+            }),
+    );
+
+    let data = match format.as_str() {
+        "csv" => {
+            let mut writer = csv::Writer::from_writer(Vec::new());
+            for row in &rows {
+                writer
+                    .serialize(row)
+                    .map_err(|why| KowalskiError::ExportError(why.to_string()))?;
+            }
+
+            writer
+                .into_inner()
+                .map_err(|why| KowalskiError::ExportError(why.to_string()))?
+        }
+        "json" => serde_json::to_vec_pretty(&rows)?,
+        _ => unreachable!(),
+    };
+
+    let file = AttachmentType::Bytes {
+        data: Cow::from(data),
+        filename: format!("leaderboard.{}", format),
+    };
+
+    send_response_attachment(
+        ctx,
+        command,
+        command_config,
+        "Leaderboard Export",
+        &format!(
+            "The leaderboard of **{}** users is attached as {}.",
+            rows.len(),
+            format.to_uppercase()
+        ),
+        |embed| embed,
+        file,
+    )
+    .await
+}
@@ -1,5 +1,6 @@
 use std::{cmp::min, time::Duration};
 
+use chrono::{Datelike, NaiveTime, Utc};
 use serenity::{
     client::Context,
     model::interactions::application_command::{
@@ -11,7 +12,7 @@ use serenity::{
 use crate::{
     config::{Command, Config},
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     pluralize,
     utils::{parse_arg, parse_arg_resolved, send_confirmation, send_response, InteractionResponse},
@@ -36,36 +37,40 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild and user ids
-    let guild_db_id = database.get_guild(guild_id).await?;
-    let user_from_db_id = database.get_user(guild_id, command.user.id).await?;
-    let user_to_db_id = database.get_user(guild_id, user.id).await?;
+    // Get the score the user can still gift today and this week
+    let caps = database.gift_caps(guild_id).await?;
+    let today = Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+    let week = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let mut limits = Vec::new();
+    let mut windows = Vec::new();
+    if let Some(daily) = caps.daily {
+        let gifted = database
+            .gifted_score(guild_id, command.user.id, today)
+            .await?;
+        limits.push((daily - gifted, "today"));
+        windows.push((today, daily));
+    }
+    if let Some(weekly) = caps.weekly {
+        let gifted = database
+            .gifted_score(guild_id, command.user.id, week)
+            .await?;
+        limits.push((weekly - gifted, "this week"));
+        windows.push((week, weekly));
+    }
+    let limit = limits.into_iter().min_by_key(|&(remaining, _)| remaining);
 
     // Calculate amount to gift
     let amount = {
-        // Select all upvotes the user has received
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT COUNT(*) FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND upvote
-        ",
-                &[&guild_db_id, &user_from_db_id],
-            )
-            .await?;
-
-        let upvotes: Option<i64> = row.get(0);
+        // Select the score the user has received through upvotes
+        let upvotes = database.user_upvotes(guild_id, command.user.id).await?;
 
-        min(score, upvotes.unwrap_or_default())
+        match limit {
+            Some((remaining, _)) => min(min(score, upvotes), remaining.max(0)),
+            None => min(score, upvotes),
+        }
     };
 
-    let title = format!(
-        "Gifting {} to {}",
-        pluralize!("reaction", amount),
-        user.name
-    );
+    let title = format!("Gifting {} to {}", pluralize!("point", amount), user.name);
 
     // Prevent user from gifting to themselves
     if user.id == command.user.id {
@@ -79,14 +84,29 @@ pub async fn execute(
         .await;
     }
 
+    // Prevent user from gifting more than allowed
+    if let Some((remaining, period)) = limit {
+        if remaining <= 0 {
+            return send_response(
+                ctx,
+                command,
+                command_config,
+                &title,
+                &format!("You can't gift any more score {}.", period),
+            )
+            .await;
+        }
+    }
+
     // Check for the interaction response
     let response = send_confirmation(
         ctx,
         command,
         command_config,
         &format!(
-            "Are you really sure you want to give {} reactions to {}?
-                This cannot be reversed!",
+            "Are you really sure you want to give up to {} of your score to {}?
+                Only whole reactions are given, so it might be a bit less.
+                Only an administrator can reverse this!",
             amount,
             user.mention()
         ),
@@ -96,27 +116,9 @@ pub async fn execute(
 
     match response {
         Some(InteractionResponse::Continue) => {
-            // Move reactions to the new user
+            // Move reactions to the new user, checking the caps again in case of concurrent gifts
             let altered_rows = database
-                .client
-                .execute(
-                    "
-                WITH to_update AS (
-                    SELECT r.guild, user_from, user_to, channel, message, r.emoji
-                    FROM score_reactions r
-                    INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-                    WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND upvote
-                    ORDER BY native
-                    LIMIT $4::BIGINT
-                )
-
-                UPDATE score_reactions
-                SET user_to = $3::BIGINT, native = false
-                WHERE (guild, user_from, user_to, channel, message, emoji)
-                    IN (SELECT * FROM to_update)
-                ",
-                    &[&guild_db_id, &user_from_db_id, &user_to_db_id, &amount],
-                )
+                .gift_upvotes(guild_id, command.user.id, user.id, amount, &windows)
                 .await?;
 
             send_response(
@@ -125,8 +127,8 @@ pub async fn execute(
                 command_config,
                 &title,
                 &format!(
-                    "Successfully gifted {} reactions to {}.",
-                    altered_rows,
+                    "Successfully gifted {} to {}.",
+                    pluralize!("point", altered_rows),
                     user.mention()
                 ),
             )
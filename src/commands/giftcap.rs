@@ -0,0 +1,55 @@
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::GiftCaps},
+    error::KowalskiError,
+    utils::{parse_arg, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    // Parse arguments (remove the caps which are not given)
+    let mut caps = GiftCaps::default();
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "daily" => caps.daily = Some(parse_arg(options, i)?),
+            "weekly" => caps.weekly = Some(parse_arg(options, i)?),
+            _ => unreachable!(),
+        }
+    }
+
+    // Insert, update or delete entry
+    database.set_gift_caps(guild_id, &caps).await?;
+
+    let content = match (caps.daily, caps.weekly) {
+        (Some(daily), Some(weekly)) => format!(
+            "Users can now gift a score of at most **{}** per day and **{}** per week.",
+            daily, weekly
+        ),
+        (Some(daily), None) => format!(
+            "Users can now gift a score of at most **{}** per day.",
+            daily
+        ),
+        (None, Some(weekly)) => format!(
+            "Users can now gift a score of at most **{}** per week.",
+            weekly
+        ),
+        (None, None) => "There is no limit anymore on the score users can gift.".to_string(),
+    };
+
+    send_response(ctx, command, command_config, "Gift caps", &content).await
+}
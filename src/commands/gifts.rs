@@ -0,0 +1,100 @@
+use itertools::Itertools;
+use serenity::{
+    client::Context,
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::{Command, Config},
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::{parse_arg_resolved, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    let options = &command.data.options;
+
+    // Parse argument (use all users as fallback)
+    let user = if !options.is_empty() {
+        match parse_arg_resolved(options, 0)? {
+            User(user, ..) => Some(user),
+            _ => unreachable!(),
+        }
+    } else {
+        None
+    };
+
+    let guild_id = command.guild_id.unwrap();
+
+    // Get the latest gifts
+    let gifts = database
+        .score_gifts(
+            guild_id,
+            user.map(|user| user.id),
+            config.general.gift_list_size as i64,
+        )
+        .await?;
+
+    let title = match user {
+        Some(user) => format!("Gifts of {}", user.name),
+        None => "Gifts".to_string(),
+    };
+
+    let mut content = if gifts.is_empty() {
+        "Looks like there are no gifts to display :(".to_string()
+    } else {
+        gifts
+            .iter()
+            .map(|gift| {
+                let line = format!(
+                    "`#{}` {} → {}: **{}** at <t:{}:f>",
+                    gift.id,
+                    gift.user_from.mention(),
+                    gift.user_to.mention(),
+                    gift.amount,
+                    gift.time.timestamp()
+                );
+
+                // Strike through gifts which were undone
+                if gift.undone {
+                    format!("~~{}~~ (undone)", line)
+                } else {
+                    line
+                }
+            })
+            .join("\n")
+    };
+
+    // Get the maximum score users can gift
+    let caps = database.gift_caps(guild_id).await?;
+
+    let mut rules = Vec::new();
+    if let Some(daily) = caps.daily {
+        rules.push(format!(
+            "Users can gift a score of at most {} per day.",
+            daily
+        ));
+    }
+    if let Some(weekly) = caps.weekly {
+        rules.push(format!(
+            "Users can gift a score of at most {} per week.",
+            weekly
+        ));
+    }
+    if !rules.is_empty() {
+        content.push_str(&format!("\n\n{}", rules.join("\n")));
+    }
+
+    send_response(ctx, command, command_config, &title, &content).await
+}
@@ -1,12 +1,8 @@
 use itertools::Itertools;
 use serenity::{
     client::Context,
-    model::{
-        channel::ReactionType,
-        id::{EmojiId, UserId},
-        interactions::application_command::{
-            ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
-        },
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
     },
     prelude::Mentionable,
 };
@@ -14,8 +10,9 @@ use serenity::{
 use crate::{
     config::Command,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
+    reaction_roles::reaction_type,
     utils::{parse_arg_resolved, send_response_complex},
 };
 
@@ -41,170 +38,41 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-    let user_db_id = database.get_user(guild_id, user.id).await?;
-
     // Analyze reactions from the user
-    let (upvotes, downvotes) = {
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT SUM(CASE WHEN upvote THEN 1 END) upvotes,
-        SUM(CASE WHEN NOT upvote THEN 1 END) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        let upvotes: Option<i64> = row.get(0);
-        let downvotes: Option<i64> = row.get(1);
+    let givens = database.guild_givens(guild_id).await?;
+    let (rank, upvotes, downvotes) =
+        match givens.iter().position(|&(user_id, ..)| user_id == user.id) {
+            Some(index) => {
+                let (_, upvotes, downvotes, _) = givens[index];
 
-        (upvotes.unwrap_or_default(), downvotes.unwrap_or_default())
-    };
+                ((index + 1).to_string(), upvotes, downvotes)
+            }
+            None => (String::from("not available"), 0, 0),
+        };
     let score = upvotes - downvotes;
     let emojis = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT unicode, guild_emoji, COUNT(*) FROM score_reactions r
-        INNER JOIN emojis e ON r.emoji = e.id
-        WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT
-        GROUP BY emoji, unicode, guild_emoji
-        ORDER BY count DESC
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
         let mut emojis = Vec::new();
 
-        for row in rows {
-            let unicode: Option<String> = row.get(0);
-            let guild_emoji: Option<i64> = row.get(1);
-            let count: i64 = row.get(2);
-
-            let emoji = match (unicode, guild_emoji) {
-                (Some(string), _) => ReactionType::Unicode(string),
-                (_, Some(id)) => {
-                    let emoji = guild_id.emoji(&ctx.http, EmojiId(id as u64)).await?;
-
-                    ReactionType::Custom {
-                        animated: emoji.animated,
-                        id: emoji.id,
-                        name: Some(emoji.name),
-                    }
-                }
-                _ => unreachable!(),
-            };
-
-            emojis.push((emoji, count));
+        for (emoji, count) in database.given_emojis(Some(guild_id), user.id).await? {
+            emojis.push((reaction_type(ctx, guild_id, &emoji).await?, count));
         }
 
         emojis
     };
-    let rank = {
-        let row = database.client.query_opt("
-            WITH ranks AS (
-                SELECT user_from,
-                RANK() OVER (
-                    ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC, user_from
-                ) rank
-                FROM score_reactions r
-                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-                WHERE r.guild = $1::BIGINT
-                GROUP BY user_from
-            )
-
-            SELECT rank FROM ranks
-            WHERE user_from = $2::BIGINT
-            ", &[&guild_db_id, &user_db_id]).await?;
-
-        row.map(|row| row.get::<_, i64>(0))
-    };
-    let rank = match rank {
-        Some(rank) => rank.to_string(),
-        None => String::from("not available"),
-    };
-
-    let top_users: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT user_to, COUNT(*) FILTER (WHERE upvote) upvotes,
-        COUNT(*) FILTER (WHERE NOT upvote) downvotes,
-        SUM(CASE WHEN upvote THEN 1 ELSE -1 END) FILTER (WHERE NOT native) gifted
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT
-        GROUP BY user_to
-        HAVING COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) >= 0
-        ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC
-        LIMIT 5
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| {
-                let user: i64 = row.get(0);
-                let upvotes: Option<i64> = row.get(1);
-                let downvotes: Option<i64> = row.get(2);
-                let gifted: Option<i64> = row.get(3);
-
-                (
-                    UserId(user as u64),
-                    upvotes.unwrap_or_default(),
-                    downvotes.unwrap_or_default(),
-                    gifted.unwrap_or_default(),
-                )
-            })
-            .collect()
-    };
-
-    let bottom_users: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT user_to, COUNT(*) FILTER (WHERE upvote) upvotes,
-        COUNT(*) FILTER (WHERE NOT upvote) downvotes,
-        SUM(CASE WHEN upvote THEN 1 ELSE -1 END) FILTER (WHERE NOT native) gifted
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT
-        GROUP BY user_to
-        HAVING COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) < 0
-        ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) ASC
-        LIMIT 5
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| {
-                let user: i64 = row.get(0);
-                let upvotes: Option<i64> = row.get(1);
-                let downvotes: Option<i64> = row.get(2);
-                let gifted: Option<i64> = row.get(3);
-
-                (
-                    UserId(user as u64),
-                    upvotes.unwrap_or_default(),
-                    downvotes.unwrap_or_default(),
-                    gifted.unwrap_or_default(),
-                )
-            })
-            .collect()
-    };
+    let total: i64 = emojis.iter().map(|(_, count)| count).sum();
+
+    let recipients = database.user_recipients(guild_id, user.id).await?;
+    let top_users: Vec<_> = recipients
+        .iter()
+        .filter(|(_, upvotes, downvotes, _)| upvotes - downvotes >= 0)
+        .take(5)
+        .collect();
+    let bottom_users: Vec<_> = recipients
+        .iter()
+        .rev()
+        .filter(|(_, upvotes, downvotes, _)| upvotes - downvotes < 0)
+        .take(5)
+        .collect();
 
     send_response_complex(
         &ctx,
@@ -224,7 +92,7 @@ pub async fn execute(
                 .iter()
                 .map(|(reaction, count)| {
                     let f_count = *count as f64;
-                    let f_total = (upvotes + downvotes) as f64;
+                    let f_total = total as f64;
                     format!(
                         "**{}x{}** ({:.1}%)",
                         count,
@@ -20,6 +20,7 @@ use crate::{
     database::client::Database,
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
+    leaderboard,
     strings::ERR_CMD_ARGS_INVALID,
     utils::{send_response, send_response_complex},
 };
@@ -49,44 +50,8 @@ pub async fn execute(
     // Get config and database
     let (config, database) = data!(ctx, (Config, Database));
 
-    // Get guild id
-    let guild_db_id = database.get_guild(command.guild_id.unwrap()).await?;
-
     // Get top users
-    let top: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT user_from, COUNT(*) FILTER (WHERE upvote) upvotes,
-        COUNT(*) FILTER (WHERE NOT upvote) downvotes,
-        SUM(CASE WHEN upvote THEN 1 ELSE -1 END) FILTER (WHERE NOT native) gifted
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT
-        GROUP BY user_from
-        ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC, user_from
-        ",
-                &[&guild_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| {
-                let user: i64 = row.get(0);
-                let upvotes: Option<i64> = row.get(1);
-                let downvotes: Option<i64> = row.get(2);
-                let gifted: Option<i64> = row.get(3);
-
-                (
-                    UserId(user as u64),
-                    upvotes.unwrap_or_default(),
-                    downvotes.unwrap_or_default(),
-                    gifted.unwrap_or_default(),
-                )
-            })
-            .collect()
-    };
+    let top = leaderboard::givens(database.as_ref(), command.guild_id.unwrap()).await?;
 
     if top.is_empty() {
         send_response(
@@ -100,7 +65,7 @@ pub async fn execute(
     } else {
         let mut page_index = 0;
         let page_size = config.general.leaderboard_size;
-        let page_count = (top.len() + page_size - 1) / page_size;
+        let page_count = top.len().div_ceil(page_size);
 
         // Loop through interactions until there is a timeout
         while let Some(interaction) = show_page(
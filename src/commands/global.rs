@@ -1,12 +1,9 @@
+use chrono::DateTime;
 use itertools::Itertools;
 use serenity::{
     client::Context,
-    model::{
-        channel::ReactionType,
-        id::EmojiId,
-        interactions::application_command::{
-            ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
-        },
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
     },
     prelude::Mentionable,
 };
@@ -14,9 +11,10 @@ use serenity::{
 use crate::{
     config::Command,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     pluralize,
+    reaction_roles::reaction_type,
     utils::{parse_arg_resolved, send_response_complex},
 };
 
@@ -42,193 +40,45 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get user id
-    let user_db_id = database.get_user(guild_id, user.id).await?;
+    // Register the user, so the guild counts as shared
+    database.get_user(guild_id, user.id).await?;
 
     // Count active guilds of the user
-    let guilds = {
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT COUNT(*) guilds
-        FROM users
-        WHERE \"user\" = $1::BIGINT
-        ",
-                &[&user_db_id],
-            )
-            .await?;
-
-        row.get::<_, Option<i64>>(0).unwrap_or_default()
-    };
+    let guilds = database.user_guild_count(user.id).await?;
 
     // Analyze reactions of the user
-    let (upvotes, downvotes) = {
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT SUM(CASE WHEN upvote THEN 1 END) upvotes,
-        SUM(CASE WHEN NOT upvote THEN 1 END) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE user_to = $1::BIGINT
-        ",
-                &[&user_db_id],
-            )
-            .await?;
-
-        let upvotes: Option<i64> = row.get(0);
-        let downvotes: Option<i64> = row.get(1);
-
-        (upvotes.unwrap_or_default(), downvotes.unwrap_or_default())
-    };
+    let (upvotes, downvotes, rank) = database.global_user_score(user.id).await?;
     let score = upvotes - downvotes;
     let emojis = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT unicode, guild_emoji, COUNT(*) FROM score_reactions r
-        INNER JOIN emojis e ON r.emoji = e.id
-        WHERE user_to = $1::BIGINT
-        GROUP BY emoji, unicode, guild_emoji
-        ORDER BY count DESC
-        ",
-                &[&user_db_id],
-            )
-            .await?;
-
         let mut emojis = Vec::new();
 
-        for row in rows {
-            let unicode: Option<String> = row.get(0);
-            let guild_emoji: Option<i64> = row.get(1);
-            let count: i64 = row.get(2);
-
-            let emoji = match (unicode, guild_emoji) {
-                (Some(string), _) => ReactionType::Unicode(string),
-                (_, Some(id)) => {
-                    let emoji = guild_id.emoji(&ctx.http, EmojiId(id as u64)).await?;
-
-                    ReactionType::Custom {
-                        animated: emoji.animated,
-                        id: emoji.id,
-                        name: Some(emoji.name),
-                    }
-                }
-                _ => unreachable!(),
-            };
-
-            emojis.push((emoji, count));
+        for (emoji, count) in database
+            .received_emojis(None, user.id, DateTime::UNIX_EPOCH)
+            .await?
+        {
+            emojis.push((reaction_type(ctx, guild_id, &emoji).await?, count));
         }
 
         emojis
     };
-    // Get rank of the user
-    let rank = {
-        let row = database.client.query_opt("
-            WITH ranks AS (
-                SELECT user_to,
-                RANK() OVER (
-                    ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC, user_to
-                ) rank
-                FROM score_reactions r
-                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-                GROUP BY user_to
-            )
-
-            SELECT rank FROM ranks
-            WHERE user_to = $1::BIGINT
-            ", &[&user_db_id]).await?;
-
-        row.map(|row| row.get::<_, i64>(0))
-    };
+    let total: i64 = emojis.iter().map(|(_, count)| count).sum();
     let rank = match rank {
         Some(rank) => rank.to_string(),
         None => String::from("not available"),
     };
 
-    let (given_upvotes, given_downvotes) = {
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT SUM(CASE WHEN upvote THEN 1 END) upvotes,
-        SUM(CASE WHEN NOT upvote THEN 1 END) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE user_from = $1::BIGINT
-        ",
-                &[&user_db_id],
-            )
-            .await?;
-
-        let upvotes: Option<i64> = row.get(0);
-        let downvotes: Option<i64> = row.get(1);
-
-        (upvotes.unwrap_or_default(), downvotes.unwrap_or_default())
-    };
+    let (given_upvotes, given_downvotes, given_rank) = database.global_user_givens(user.id).await?;
     let given = given_upvotes - given_downvotes;
     let given_emojis = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT unicode, guild_emoji, COUNT(*) FROM score_reactions r
-        INNER JOIN emojis e ON r.emoji = e.id
-        WHERE user_from = $1::BIGINT
-        GROUP BY emoji, unicode, guild_emoji
-        ORDER BY count DESC
-        ",
-                &[&user_db_id],
-            )
-            .await?;
-
         let mut emojis = Vec::new();
 
-        for row in rows {
-            let unicode: Option<String> = row.get(0);
-            let guild_emoji: Option<i64> = row.get(1);
-            let count: i64 = row.get(2);
-
-            let emoji = match (unicode, guild_emoji) {
-                (Some(string), _) => ReactionType::Unicode(string),
-                (_, Some(id)) => {
-                    let emoji = guild_id.emoji(&ctx.http, EmojiId(id as u64)).await?;
-
-                    ReactionType::Custom {
-                        animated: emoji.animated,
-                        id: emoji.id,
-                        name: Some(emoji.name),
-                    }
-                }
-                _ => unreachable!(),
-            };
-
-            emojis.push((emoji, count));
+        for (emoji, count) in database.given_emojis(None, user.id).await? {
+            emojis.push((reaction_type(ctx, guild_id, &emoji).await?, count));
         }
 
         emojis
     };
-    let given_rank = {
-        let row = database.client.query_opt("
-            WITH ranks AS (
-                SELECT user_from,
-                RANK() OVER (
-                    ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC, user_from
-                ) rank
-                FROM score_reactions r
-                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-                GROUP BY user_from
-            )
-
-            SELECT rank FROM ranks
-            WHERE user_from = $1::BIGINT
-            ", &[&user_db_id]).await?;
-
-        row.map(|row| row.get::<_, i64>(0))
-    };
+    let given_total: i64 = given_emojis.iter().map(|(_, count)| count).sum();
     let given_rank = match given_rank {
         Some(given_rank) => given_rank.to_string(),
         None => String::from("not available"),
@@ -249,7 +99,7 @@ pub async fn execute(
                 .iter()
                 .map(|(reaction, count)| {
                     let f_count = *count as f64;
-                    let f_total = (upvotes + downvotes) as f64;
+                    let f_total = total as f64;
                     format!(
                         "**{}x{}** ({:.1}%)",
                         count,
@@ -266,7 +116,7 @@ pub async fn execute(
                 .iter()
                 .map(|(reaction, count)| {
                     let f_count = *count as f64;
-                    let f_total = (given_upvotes + given_downvotes) as f64;
+                    let f_total = given_total as f64;
                     format!(
                         "**{}x{}** ({:.1}%)",
                         count,
@@ -24,7 +24,7 @@ use serenity::{
 use crate::{
     config::{Command, Config},
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
     strings::ERR_CMD_ARGS_INVALID,
@@ -106,28 +106,14 @@ pub async fn execute(
     match action {
         Action::Create => {
             // Get count of owned guilds
-            let count: i64 = database
-                .client
-                .query_one("SELECT COUNT(*) FROM owned_guilds", &[])
-                .await?
-                .get(0);
+            let count = database.owned_guilds().await?.len();
 
             // Create guild
             let partial_guild =
                 create_guild(&ctx.http, &format!("Kowalski Guild #{}", count + 1), None).await?;
 
-            // Get guild id
-            let guild_db_id = database.get_guild(partial_guild.id).await?;
-
             // Add guild to database
-            database
-                .client
-                .execute(
-                    "
-                    INSERT INTO owned_guilds VALUES ($1::BIGINT)",
-                    &[&guild_db_id],
-                )
-                .await?;
+            database.add_owned_guild(partial_guild.id).await?;
 
             // Get invite
             let invite = get_invite(ctx, &partial_guild).await?;
@@ -146,13 +132,7 @@ pub async fn execute(
         }
         Action::Edit => {
             // Get list of owned guilds
-            let owned: Vec<GuildId> = database
-                .client
-                .query("SELECT guild FROM owned_guilds", &[])
-                .await?
-                .iter()
-                .map(|row| GuildId(row.get::<_, i64>(0) as u64))
-                .collect();
+            let owned = database.owned_guilds().await?;
 
             if owned.is_empty() {
                 send_response(
@@ -484,17 +464,8 @@ async fn guild_action(
                         .await?;
 
                     if partial_guild.owner_id == command.user.id {
-                        // Get guild id
-                        let guild_db_id = database.get_guild(current_guild_id.clone()).await?;
-
                         // Remove guild from database
-                        database
-                            .client
-                            .execute(
-                                "DELETE FROM owned_guilds WHERE guild = $1::BIGINT",
-                                &[&guild_db_id],
-                            )
-                            .await?;
+                        database.remove_owned_guild(*current_guild_id).await?;
 
                         send_response(
                             ctx,
@@ -518,20 +489,11 @@ async fn guild_action(
                     }
                 }
                 ComponentInteractionResponse::Delete => {
-                    // Get guild id
-                    let guild_db_id = database.get_guild(current_guild_id.clone()).await?;
-
                     // Delete guild (ignore result because of a library bug)
                     let _ = current_guild_id.delete(&ctx.http).await;
 
                     // Remove guild from database
-                    database
-                        .client
-                        .execute(
-                            "DELETE FROM owned_guilds WHERE guild = $1::BIGINT",
-                            &[&guild_db_id],
-                        )
-                        .await?;
+                    database.remove_owned_guild(*current_guild_id).await?;
 
                     send_response(
                         ctx,
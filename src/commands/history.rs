@@ -0,0 +1,155 @@
+use std::borrow::Cow;
+
+use chrono::{Duration, Utc};
+use serenity::{
+    client::Context,
+    model::{
+        channel::AttachmentType,
+        interactions::application_command::{
+            ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
+        },
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    chart::{render_line_chart, CHART_COLORS},
+    config::{Command, Config},
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    leaderboard::{self, Period},
+    utils::{parse_arg, parse_arg_resolved, send_response, send_response_attachment},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    let options = &command.data.options;
+
+    // Parse arguments (use 30 days as fallback)
+    let mut user = None;
+    let mut top = None;
+    let mut days = 30;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "user" => {
+                user = match parse_arg_resolved(options, i)? {
+                    User(user, ..) => Some(user.id),
+                    _ => unreachable!(),
+                }
+            }
+            "top" => top = Some(parse_arg::<i64>(options, i)? as usize),
+            "days" => days = parse_arg(options, i)?,
+            _ => unreachable!(),
+        }
+    }
+
+    // Get guild
+    let guild_id = command.guild_id.unwrap();
+
+    // Get the users to chart, the top users first
+    let mut users = match top {
+        Some(top) => leaderboard::scores(database.as_ref(), guild_id, Period::AllTime)
+            .await?
+            .iter()
+            .take(top)
+            .map(|&(user, ..)| user)
+            .collect(),
+        None => Vec::new(),
+    };
+    // Chart the command user if nobody else is charted
+    let user = user.or_else(|| top.is_none().then_some(command.user.id));
+    if let Some(user) = user {
+        if !users.contains(&user) {
+            users.push(user);
+        }
+    }
+
+    // Get the snapshots of the time frame, counting the days from the first one
+    let since = (Utc::now() - Duration::days(days - 1)).date_naive();
+    let snapshots = database.score_snapshots(guild_id, &users, since).await?;
+
+    let series: Vec<Vec<_>> = users
+        .iter()
+        .map(|user| {
+            snapshots
+                .iter()
+                .filter(|(snapshot_user, ..)| snapshot_user == user)
+                .map(|&(_, day, score)| ((day - since).num_days(), score))
+                .collect()
+        })
+        .collect();
+
+    let title = format!("Score History of the last {} days", days);
+
+    if series.iter().all(Vec::is_empty) {
+        return send_response(
+            ctx,
+            command,
+            command_config,
+            &title,
+            "Looks like there are no scores to display :(",
+        )
+        .await;
+    }
+
+    let image = render_line_chart(
+        config.general.chart_width,
+        config.general.chart_height,
+        days - 1,
+        &series,
+    )?;
+
+    // Describe the lines of the chart, as it contains no text
+    let legend: Vec<_> = users
+        .iter()
+        .zip(&series)
+        .zip(CHART_COLORS)
+        .map(
+            |((user, points), (_, emoji))| match (points.first(), points.last()) {
+                (Some((first_day, first)), Some((_, last))) => format!(
+                    "{} {}: **{}** ({:+} since {})",
+                    emoji,
+                    user.mention(),
+                    last,
+                    last - first,
+                    since + Duration::days(*first_day)
+                ),
+                _ => format!("{} {}: no scores recorded", emoji, user.mention()),
+            },
+        )
+        .collect();
+    let (min, max) = series
+        .iter()
+        .flatten()
+        .fold((0, 0), |(min, max), &(_, score)| {
+            (score.min(min), score.max(max))
+        });
+
+    let file = AttachmentType::Bytes {
+        data: Cow::from(image),
+        filename: "history.png".to_string(),
+    };
+
+    send_response_attachment(
+        ctx,
+        command,
+        command_config,
+        &title,
+        &legend.join("\n"),
+        |embed| {
+            embed
+                .field("Days", format!("{} until today", since), true)
+                .field("Scores", format!("{} to {}", min, max), true)
+                .image("attachment://history.png")
+        },
+        file,
+    )
+    .await
+}
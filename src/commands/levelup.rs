@@ -5,8 +5,11 @@ use std::{
 
 use serenity::{
     client::Context,
-    model::interactions::application_command::{
-        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::Role,
+    model::{
+        id::GuildId,
+        interactions::application_command::{
+            ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::Role,
+        },
     },
     prelude::Mentionable,
 };
@@ -14,16 +17,26 @@ use serenity::{
 use crate::{
     config::Command,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
+    level_ups::resync_roles,
     strings::ERR_CMD_ARGS_INVALID,
     utils::{parse_arg, parse_arg_resolved, send_response},
 };
 
+/// Tells the user that the roles of the members get updated, which takes a while on large guilds.
+const RESYNC_NOTE: &str = "The level-up roles of all members get updated in the background.";
+
+/// Tells the user that the roles of the members get updated once the running update is done.
+const RESYNC_QUEUED_NOTE: &str =
+    "The level-up roles of all members get updated again once the running update is done.";
+
 enum Action {
     Add,
     Remove,
+    HighestOnly,
+    AllReached,
 }
 
 impl Display for Action {
@@ -31,6 +44,8 @@ impl Display for Action {
         let name = match self {
             Action::Add => "Add",
             Action::Remove => "Remove",
+            Action::HighestOnly => "Highest only",
+            Action::AllReached => "All reached",
         };
 
         write!(f, "{}", name)
@@ -44,6 +59,8 @@ impl FromStr for Action {
         match s {
             "add" => Ok(Action::Add),
             "remove" => Ok(Action::Remove),
+            "highest only" => Ok(Action::HighestOnly),
+            "all reached" => Ok(Action::AllReached),
             _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
         }
     }
@@ -60,60 +77,86 @@ pub async fn execute(
     let options = &command.data.options;
 
     // Parse arguments
-    let action = Action::from_str(parse_arg(options, 0)?).unwrap();
-    let role = match parse_arg_resolved(options, 1)? {
-        Role(role) => role,
-        _ => unreachable!(),
-    };
-    let score: i64 = parse_arg(options, 2)?;
+    let mut action = None;
+    let mut role = None;
+    let mut score = None;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "action" => action = Some(Action::from_str(parse_arg(options, i)?)?),
+            "role" => match parse_arg_resolved(options, i)? {
+                Role(resolved) => role = Some(resolved),
+                _ => unreachable!(),
+            },
+            "score" => score = Some(parse_arg::<i64>(options, i)?),
+            _ => unreachable!(),
+        }
+    }
+    let action = action.unwrap();
+
+    let guild_id = command.guild_id.unwrap();
+
+    // Switch whether the level-up roles stack
+    if let Action::HighestOnly | Action::AllReached = action {
+        let stacking = matches!(action, Action::AllReached);
+
+        database.set_level_up_stacking(guild_id, stacking).await?;
+
+        let content = if stacking {
+            "Users now keep the level-up roles of all scores they have reached."
+        } else {
+            "Users now only keep the level-up roles of the highest score they have reached."
+        };
+
+        // Bring the roles of all members up to date
+        let note = resync_note(ctx, guild_id);
 
-    // Get guild and role ids
-    let guild_db_id = database.get_guild(role.guild_id).await?;
-    let role_db_id = database.get_role(role.guild_id, role.id).await?;
+        return send_response(
+            ctx,
+            command,
+            command_config,
+            "Level-up roles",
+            &format!("{}\n{}", content, note),
+        )
+        .await;
+    }
+
+    // Adding and removing level-up roles requires a role and a score
+    let (role, score) = match (role, score) {
+        (Some(role), Some(score)) => (role, score),
+        _ => return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+    };
 
     let title = format!("{} level-up role for {}", action, role.name);
 
     match action {
         Action::Add => {
             database
-                .client
-                .execute(
-                    "
-            INSERT INTO score_roles
-            VALUES($1::BIGINT, $2::BIGINT, $3::BIGINT)
-            ON CONFLICT
-            DO NOTHING
-            ",
-                    &[&guild_db_id, &role_db_id, &score],
-                )
+                .add_level_up_role(role.guild_id, role.id, score)
                 .await?;
 
+            // Bring the roles of all members up to date
+            let note = resync_note(ctx, guild_id);
+
             send_response(
                 &ctx,
                 &command,
                 command_config,
                 &title,
                 &format!(
-                    "Users reaching a score of {} will now receive the role {}.",
+                    "Users reaching a score of {} will now receive the role {}.\n{}",
                     score,
-                    role.mention()
+                    role.mention(),
+                    note
                 ),
             )
             .await
         }
         Action::Remove => {
-            let modified = database
-                .client
-                .execute(
-                    "
-            DELETE FROM score_roles
-            WHERE guild = $1::BIGINT AND role = $2::BIGINT AND score = $3::BIGINT
-            ",
-                    &[&guild_db_id, &role_db_id, &score],
-                )
+            let removed = database
+                .remove_level_up_role(role.guild_id, role.id, score)
                 .await?;
 
-            if modified == 0 {
+            if !removed {
                 send_response(
                     &ctx,
                     &command,
@@ -128,19 +171,33 @@ pub async fn execute(
                 )
                 .await
             } else {
+                // Bring the roles of all members up to date
+                let note = resync_note(ctx, guild_id);
+
                 send_response(
                     &ctx,
                     &command,
                     command_config,
                     &title,
                     &format!(
-                        "I have removed the level-up role {} on score {}.",
+                        "I have removed the level-up role {} on score {}.\n{}",
                         role.mention(),
-                        score
+                        score,
+                        note
                     ),
                 )
                 .await
             }
         }
+        Action::HighestOnly | Action::AllReached => unreachable!(),
+    }
+}
+
+/// Resynchronizes the level-up roles of all members, telling the user when that happens.
+fn resync_note(ctx: &Context, guild_id: GuildId) -> &'static str {
+    if resync_roles(ctx.clone(), guild_id, None) {
+        RESYNC_NOTE
+    } else {
+        RESYNC_QUEUED_NOTE
     }
 }
@@ -1,12 +1,15 @@
 use itertools::Itertools;
 use serenity::{
-    client::Context,
-    model::{id::RoleId, interactions::application_command::ApplicationCommandInteraction},
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
     prelude::Mentionable,
 };
 
 use crate::{
-    config::Command, data, database::client::Database, error::KowalskiError, utils::send_response,
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::send_response,
 };
 
 pub async fn execute(
@@ -19,27 +22,8 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get roles and their respective cooldowns
-    let role_cooldowns: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-                SELECT role, score FROM score_roles
-                WHERE guild = $1::BIGINT
-                ORDER BY score
-                ",
-                &[&guild_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| (RoleId(row.get::<_, i64>(0) as u64), row.get::<_, i64>(1)))
-            .collect()
-    };
+    let role_cooldowns = database.level_up_roles(guild_id).await?;
 
     let levelup_roles = role_cooldowns
         .iter()
@@ -55,6 +39,13 @@ pub async fn execute(
 
     let title = "Level-up roles";
 
+    // Get whether users keep the roles of lower scores
+    let stacking = if database.level_up_stacking(guild_id).await? {
+        "Users keep the roles of all scores they have reached."
+    } else {
+        "Users only keep the roles of the highest score they have reached."
+    };
+
     if levelup_roles.is_empty() {
         send_response(
             &ctx,
@@ -72,8 +63,10 @@ pub async fn execute(
             &title,
             &format!(
                 "The following roles will get assigned to users when they reach a certain score:
+                {}
+
                 {}",
-                levelup_roles
+                levelup_roles, stacking
             ),
         )
         .await
@@ -1,18 +1,28 @@
 pub mod about;
+pub mod abuse;
+pub mod announcement;
+pub mod cap;
+pub mod channel;
+pub mod channels;
 pub mod clean;
 pub mod clear;
 pub mod cooldown;
 pub mod cooldowns;
+pub mod decay;
 pub mod disabled;
 pub mod drop;
 pub mod drops;
 pub mod emoji;
 pub mod emojis;
+pub mod export;
 pub mod gift;
+pub mod giftcap;
+pub mod gifts;
 pub mod given;
 pub mod givens;
 pub mod global;
 pub mod guild;
+pub mod history;
 pub mod levelup;
 pub mod levelups;
 pub mod moderation;
@@ -21,6 +31,7 @@ pub mod module;
 pub mod modules;
 #[cfg(feature = "nlp-model")]
 pub mod mood;
+pub mod newseason;
 #[cfg(feature = "nlp-model")]
 pub mod oracle;
 pub mod ping;
@@ -30,9 +41,15 @@ pub mod reactionrole;
 pub mod reactionroles;
 pub mod reminder;
 pub mod reminders;
+pub mod resync;
+pub mod reward;
+pub mod rewards;
+pub mod rolemenu;
 pub mod say;
 pub mod score;
 pub mod scores;
+pub mod season;
 pub mod sql;
 #[cfg(feature = "nlp-model")]
 pub mod tldr;
+pub mod ungift;
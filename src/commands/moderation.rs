@@ -4,42 +4,51 @@ use std::{
 };
 
 use serenity::{
-    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    client::Context,
+    model::interactions::application_command::{
+        ApplicationCommandInteraction,
+        ApplicationCommandInteractionDataOptionValue::{Channel, Role},
+    },
 };
 
 use crate::{
+    auto_moderation::describe_rule,
     config::Command,
     data,
-    database::client::Database,
+    database::{
+        client::Database,
+        storage::Storage,
+        types::{ModerationAction, ModerationRule},
+    },
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
     strings::ERR_CMD_ARGS_INVALID,
-    utils::{parse_arg, send_response},
+    utils::{parse_arg, parse_arg_resolved, send_response},
 };
 
-enum Moderation {
-    Pin,
-    Delete,
+enum Action {
+    Add,
+    Remove,
 }
 
-impl Display for Moderation {
+impl Display for Action {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let name = match self {
-            Moderation::Pin => "Auto-pin",
-            Moderation::Delete => "Auto-delete",
+            Action::Add => "Add",
+            Action::Remove => "Remove",
         };
 
         write!(f, "{}", name)
     }
 }
 
-impl FromStr for Moderation {
+impl FromStr for Action {
     type Err = KowalskiError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
-            "pin" => Ok(Moderation::Pin),
-            "delete" => Ok(Moderation::Delete),
+            "add" => Ok(Action::Add),
+            "remove" => Ok(Action::Remove),
             _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
         }
     }
@@ -53,99 +62,103 @@ pub async fn execute(
     // Get database
     let database = data!(ctx, Database);
 
-    let options = &command.data.options;
-
-    // Parse first argument
-    let moderation = Moderation::from_str(parse_arg(options, 0)?).unwrap();
-
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
-    let title = format!("{} message", moderation);
-
-    if options.len() > 1 {
-        // Parse second argument
-        let score: i64 = parse_arg(options, 1)?;
-
-        // Insert or update entry
-        match moderation {
-            Moderation::Pin => {
-                database
-                    .client
-                    .execute(
-                        "
-                        INSERT INTO score_auto_pin
-                        VALUES ($1::BIGINT, $2::BIGINT)
-                        ON CONFLICT (guild) DO UPDATE SET score = $2::BIGINT
-                        ",
-                        &[&guild_db_id, &score],
-                    )
-                    .await?;
-            }
-            Moderation::Delete => {
-                database
-                    .client
-                    .execute(
-                        "
-                        INSERT INTO score_auto_delete
-                        VALUES ($1::BIGINT, $2::BIGINT)
-                        ON CONFLICT (guild) DO UPDATE SET score = $2::BIGINT
-                        ",
-                        &[&guild_db_id, &score],
-                    )
-                    .await?;
-            }
-        }
+    let options = &command.data.options;
 
-        send_response(
-            &ctx,
-            &command,
-            command_config,
-            &title,
-            &format!(
-                "Moderation tool '{}' is now enabled at a score of {}.",
-                moderation, score
-            ),
-        )
-        .await
-    } else {
-        // Delete moderation
-        match moderation {
-            Moderation::Pin => {
-                database
-                    .client
-                    .execute(
-                        "
-                        DELETE FROM score_auto_pin
-                        WHERE guild = $1::BIGINT
-                        ",
-                        &[&guild_db_id],
-                    )
-                    .await?;
-            }
-            Moderation::Delete => {
-                database
-                    .client
-                    .execute(
-                        "
-                        DELETE FROM score_auto_delete
-                        WHERE guild = $1::BIGINT
-                        ",
-                        &[&guild_db_id],
-                    )
-                    .await?;
+    // Parse arguments
+    let mut action = None;
+    let mut moderation = None;
+    let mut id = None;
+    let mut rule = ModerationRule {
+        id: 0,
+        score: None,
+        reactions: None,
+        channel: None,
+        max_age: None,
+        action: ModerationAction::Pin,
+        target: None,
+        role: None,
+        duration: None,
+    };
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "action" => action = Some(Action::from_str(parse_arg(options, i)?)?),
+            "moderation" => {
+                moderation = Some(
+                    ModerationAction::from_name(parse_arg(options, i)?)
+                        .ok_or(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()))?,
+                )
             }
+            "score" => rule.score = Some(parse_arg(options, i)?),
+            "reactions" => rule.reactions = Some(parse_arg(options, i)?),
+            "channel" => match parse_arg_resolved(options, i)? {
+                Channel(channel) => rule.channel = Some(channel.id),
+                _ => unreachable!(),
+            },
+            "age" => rule.max_age = Some(parse_arg(options, i)?),
+            "target" => match parse_arg_resolved(options, i)? {
+                Channel(channel) => rule.target = Some(channel.id),
+                _ => unreachable!(),
+            },
+            "role" => match parse_arg_resolved(options, i)? {
+                Role(role) => rule.role = Some(role.id),
+                _ => unreachable!(),
+            },
+            "minutes" => rule.duration = Some(parse_arg(options, i)?),
+            "rule" => id = Some(parse_arg(options, i)?),
+            _ => unreachable!(),
         }
+    }
+    let action = action.unwrap();
+
+    let title = format!("{} auto-moderation rule", action);
+
+    match action {
+        Action::Add => {
+            rule.action = moderation.ok_or(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()))?;
+
+            // Rules are checked whenever the votes change, so they need a score or reactions
+            let condition = rule.score.is_some() || rule.reactions.is_some();
+            let complete = match rule.action {
+                ModerationAction::Repost | ModerationAction::Notify => rule.target.is_some(),
+                ModerationAction::Timeout => rule.duration.is_some(),
+                _ => true,
+            };
+            if !condition || !complete {
+                return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()));
+            }
 
-        send_response(
-            &ctx,
-            &command,
-            command_config,
-            &title,
-            &format!("Moderation tool '{}' is now disabled.", moderation),
-        )
-        .await
+            rule.id = database.add_moderation_rule(guild_id, &rule).await?;
+
+            send_response(
+                ctx,
+                command,
+                command_config,
+                &title,
+                &format!(
+                    "I have added the following rule:\n\n{}",
+                    describe_rule(&rule)
+                ),
+            )
+            .await
+        }
+        Action::Remove => {
+            let id = id.ok_or(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()))?;
+
+            let removed = database.remove_moderation_rule(guild_id, id).await?;
+
+            let content = if removed {
+                format!("I have removed the rule #{}.", id)
+            } else {
+                format!(
+                    "There is no rule #{} on this server.
+                    I didn't remove anything.",
+                    id
+                )
+            };
+
+            send_response(ctx, command, command_config, &title, &content).await
+        }
     }
 }
@@ -1,9 +1,15 @@
+use itertools::Itertools;
 use serenity::{
     client::Context, model::interactions::application_command::ApplicationCommandInteraction,
 };
 
 use crate::{
-    config::Command, data, database::client::Database, error::KowalskiError, utils::send_response,
+    auto_moderation::describe_rule,
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::send_response,
 };
 
 pub async fn execute(
@@ -16,56 +22,17 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
+    let rules = database.moderation_rules(guild_id).await?;
 
-    let pin_score: Option<i64> = database
-        .client
-        .query_opt(
-            "
-            SELECT score FROM score_auto_pin
-            WHERE guild = $1::BIGINT",
-            &[&guild_db_id],
+    let content = if rules.is_empty() {
+        "There are currently no auto-moderation rules defined for this server.".to_string()
+    } else {
+        format!(
+            "I will automatically moderate messages matching the following rules, \
+            each one only once per message:\n\n{}",
+            rules.iter().map(describe_rule).join("\n")
         )
-        .await?
-        .map(|row| row.get(0));
-
-    let delete_score: Option<i64> = database
-        .client
-        .query_opt(
-            "
-            SELECT score FROM score_auto_delete
-            WHERE guild = $1::BIGINT",
-            &[&guild_db_id],
-        )
-        .await?
-        .map(|row| row.get(0));
-
-    let mut content = format!("The following auto-moderation tools are available:\n\n");
-
-    // Add auto pin information
-    content.push_str("**Auto Pin:** ");
-
-    match pin_score {
-        Some(pin_score) => content.push_str(&format!(
-            "I will automatically pin messages when they reach a score of **{}**.",
-            pin_score
-        )),
-        None => content.push_str("Disabled"),
-    };
-
-    content.push_str("\n");
-
-    // Add auto delete information
-    content.push_str("**Auto Delete:** ");
-
-    match delete_score {
-        Some(delete_score) => content.push_str(&format!(
-            "I will automatically delete messages when they reach a score of **{}**.",
-            delete_score
-        )),
-        None => content.push_str("Disabled"),
     };
 
-    send_response(&ctx, &command, &command_config, "Auto-moderation", &content).await
+    send_response(ctx, command, command_config, "Auto-moderation", &content).await
 }
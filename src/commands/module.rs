@@ -12,7 +12,7 @@ use tokio::sync::Mutex;
 use crate::{
     config::{Command, Config, Module},
     data,
-    database::{client::Database, types::ModuleStatus},
+    database::{client::Database, storage::Storage, types::ModuleStatus},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
     strings::ERR_CMD_ARGS_INVALID,
@@ -104,41 +104,12 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get the status, modify it and update it in the database if necessary
     let status: Option<ModuleStatus> = {
         let _mutex = LOCK.lock().await;
 
         // Get current guild status
-        let status: ModuleStatus = {
-            let row = database
-                .client
-                .query_opt(
-                    "SELECT status FROM modules WHERE guild = $1::BIGINT",
-                    &[&guild_db_id],
-                )
-                .await?;
-
-            match row {
-                Some(row) => row.get(0),
-                None => {
-                    database
-                        .client
-                        .execute(
-                            "
-                        INSERT INTO modules
-                        VALUES ($1::BIGINT, B'00000000')
-                        ",
-                            &[&guild_db_id],
-                        )
-                        .await?;
-
-                    ModuleStatus::default()
-                }
-            }
-        };
+        let status = database.module_status(guild_id).await?;
 
         // Copy status to compare it to the old status later
         let mut status_new = status.clone();
@@ -156,22 +127,7 @@ pub async fn execute(
         // Check whether the status has changed
         if status != status_new {
             // Update the object in the database so we can drop the lock
-
-            // Get guild id
-            let guild_db_id = database.get_guild(guild_id).await?;
-
-            // Update the database entry
-            database
-                .client
-                .execute(
-                    "
-            UPDATE modules
-            SET status = $1::BIT(8)
-            WHERE guild = $2::BIGINT
-            ",
-                    &[&status_new, &guild_db_id],
-                )
-                .await?;
+            database.set_module_status(guild_id, &status_new).await?;
 
             Some(status_new)
         } else {
@@ -258,53 +214,10 @@ async fn remove(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = guild_id.0 as i64;
-
     match module {
-        Module::Utility => {
-            database
-                .client
-                .execute(
-                    "
-                    DELETE FROM publishing WHERE guild = $1::BIGINT;
-
-                    DELETE FROM reminders WHERE guild = $1::BIGINT;
-                    ",
-                    &[&guild_db_id],
-                )
-                .await?;
-        }
-        Module::Score => {
-            database
-                .client
-                .execute(
-                    "
-                    DELETE FROM score_auto_delete WHERE guild = $1::BIGINT;
-
-                    DELETE FROM score_auto_pin WHERE guild = $1::BIGINT;
-
-                    DELETE FROM score_cooldowns WHERE guild = $1::BIGINT;
-
-                    DELETE FROM score_drops WHERE guild = $1::BIGINT;
-
-                    DELETE FROM score_emojis WHERE guild = $1::BIGINT;
-
-                    DELETE FROM score_roles WHERE guild = $1::BIGINT;
-                    ",
-                    &[&guild_db_id],
-                )
-                .await?;
-        }
-        Module::ReactionRoles => {
-            database
-                .client
-                .execute(
-                    "DELETE FROM reminders WHERE guild = $1::BIGINT",
-                    &[&guild_db_id],
-                )
-                .await?;
-        }
+        Module::Utility => database.remove_utility_data(guild_id).await?,
+        Module::Score => database.remove_score_data(guild_id).await?,
+        Module::ReactionRoles => database.remove_reaction_role_data(guild_id).await?,
         _ => {
             return send_response(
                 ctx,
@@ -6,7 +6,7 @@ use strum::IntoEnumIterator;
 use crate::{
     config::{Command, Module},
     data,
-    database::{client::Database, types::ModuleStatus},
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     utils::{send_failure, send_response_complex},
 };
@@ -34,22 +34,8 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get guild status
-    let status = database
-        .client
-        .query_opt(
-            "
-                SELECT status
-                FROM modules
-                WHERE guild = $1::BIGINT
-                ",
-            &[&guild_db_id],
-        )
-        .await?
-        .map_or(ModuleStatus::default(), |row| row.get(0));
+    let status = database.module_status(guild_id).await?;
 
     let mut fields = Vec::new();
 
@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use serenity::{
+    client::Context,
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::Channel,
+    },
+    prelude::Mentionable,
+};
+use tracing::error;
+
+use crate::{
+    config::{Command, Config},
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    events::reaction::update_roles,
+    leaderboard::format_ranks,
+    pluralize,
+    strings::ERR_LEVEL_UP_ROLES,
+    utils::{
+        create_embed, parse_arg_resolved, send_confirmation, send_response, InteractionResponse,
+    },
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    let options = &command.data.options;
+
+    // Parse argument (use the current channel as fallback)
+    let channel_id = if !options.is_empty() {
+        match parse_arg_resolved(options, 0)? {
+            Channel(channel) => channel.id,
+            _ => unreachable!(),
+        }
+    } else {
+        command.channel_id
+    };
+
+    let guild_id = command.guild_id.unwrap();
+
+    let title = "Start new season";
+
+    // Check for the interaction response
+    let response = send_confirmation(
+        ctx,
+        command,
+        command_config,
+        &format!(
+            "Are you really sure you want to end the current season?
+            The final leaderboard will be posted in {} and all scores will be reset.
+            This cannot be reversed!",
+            channel_id.mention()
+        ),
+        Duration::from_secs(config.general.interaction_timeout),
+    )
+    .await?;
+
+    match response {
+        Some(InteractionResponse::Continue) => {
+            // Archive the leaderboard and reset the scores
+            let season = database.end_season(guild_id).await?;
+            let ranks = database.season_ranks(guild_id, season.number).await?;
+
+            // Post the final leaderboard
+            let mut content = format_ranks(
+                &ranks[..ranks.len().min(config.general.leaderboard_size)],
+                &config.general.leaderboard_titles,
+            );
+            if content.is_empty() {
+                content = "Nobody received any reactions this season.".to_string();
+            }
+            let message = channel_id
+                .send_message(&ctx.http, |message| {
+                    message.set_embed(create_embed(
+                        &format!("Final leaderboard of season {}", season.number),
+                        &content,
+                    ))
+                })
+                .await?;
+
+            // Update the level-up roles of the users who lost their score, they might have left
+            let mut failed = 0;
+            for rank in &ranks {
+                if let Ok(mut member) = guild_id.member(ctx, rank.user).await {
+                    if let Err(why) = update_roles(ctx, database.as_ref(), &mut member).await {
+                        error!(
+                            "{} of user {} in guild {}: {}",
+                            ERR_LEVEL_UP_ROLES, rank.user, guild_id, why
+                        );
+                        failed += 1;
+                    }
+                }
+            }
+
+            let mut content = format!(
+                "Season {} has ended and all scores were reset, the final leaderboard is [here]({}).",
+                season.number,
+                message.link()
+            );
+            if failed > 0 {
+                content.push_str(&format!(
+                    "\nThe level-up roles of {} could not be updated.",
+                    pluralize!("user", failed)
+                ));
+            }
+
+            send_response(ctx, command, command_config, title, &content).await
+        }
+        Some(InteractionResponse::Abort) => {
+            send_response(ctx, command, command_config, title, "Aborted the action.").await
+        }
+        None => Ok(()),
+    }
+}
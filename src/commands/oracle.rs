@@ -1,8 +1,8 @@
+use itertools::Itertools;
 use rust_bert::pipelines::conversation::ConversationManager;
 use serenity::{
     client::Context, model::interactions::application_command::ApplicationCommandInteraction,
 };
-use itertools::Itertools;
 
 use crate::{
     config::{Command, Config},
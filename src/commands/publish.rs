@@ -11,7 +11,7 @@ use serenity::{
 use crate::{
     config::{Command, Config},
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
     strings::ERR_CMD_ARGS_INVALID,
@@ -61,27 +61,11 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     let title = format!("{} publishing of events", action);
 
     match action {
         Action::Enable => {
-            let id = {
-                let row = database
-                    .client
-                    .query_opt(
-                        "
-                SELECT id FROM publishing
-                WHERE guild = $1::BIGINT
-            ",
-                        &[&guild_db_id],
-                    )
-                    .await?;
-
-                row.map(|row| row.get::<_, String>(0))
-            };
+            let id = database.publishing_id(guild_id).await?;
 
             match id {
                 Some(id) => {
@@ -114,16 +98,7 @@ pub async fn execute(
                             .collect()
                     };
 
-                    database
-                        .client
-                        .execute(
-                            "
-                        INSERT INTO publishing
-                        VALUES($1::TEXT, $2::BIGINT)
-                        ",
-                            &[&id, &guild_db_id],
-                        )
-                        .await?;
+                    database.enable_publishing(guild_id, &id).await?;
 
                     send_response(
                         &ctx,
@@ -143,16 +118,7 @@ pub async fn execute(
             }
         }
         Action::Disable => {
-            database
-                .client
-                .execute(
-                    "
-            DELETE FROM publishing
-            WHERE guild = $1::BIGINT
-            ",
-                    &[&guild_db_id],
-                )
-                .await?;
+            database.disable_publishing(guild_id).await?;
 
             send_response(
                 &ctx,
@@ -20,9 +20,10 @@ use crate::{
     config::Command,
     config::Config,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
+    reaction_roles::format_duration,
     strings::ERR_CMD_ARGS_INVALID,
     utils::{parse_arg, parse_arg_resolved, send_response},
 };
@@ -30,6 +31,9 @@ use crate::{
 enum Action {
     Add,
     Remove,
+    Require,
+    Forbid,
+    Clear,
 }
 
 impl Display for Action {
@@ -37,6 +41,8 @@ impl Display for Action {
         let name = match self {
             Action::Add => "Add",
             Action::Remove => "Remove",
+            Action::Require | Action::Forbid => "Restrict",
+            Action::Clear => "Unrestrict",
         };
 
         write!(f, "{}", name)
@@ -50,6 +56,9 @@ impl FromStr for Action {
         match s {
             "add" => Ok(Action::Add),
             "remove" => Ok(Action::Remove),
+            "require" => Ok(Action::Require),
+            "forbid" => Ok(Action::Forbid),
+            "clear" => Ok(Action::Clear),
             _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
         }
     }
@@ -73,14 +82,56 @@ pub async fn execute(
         Role(role) => role,
         _ => unreachable!(),
     };
-    let slots = {
-        if options.len() > 2 {
-            Some(parse_arg::<i64>(options, 2)?)
-        } else {
-            None
+    let mut slots = None;
+    let mut group: Option<String> = None;
+    let mut min = None;
+    let mut max = None;
+    let mut hours: Option<i64> = None;
+    let mut days: Option<i64> = None;
+    let mut condition = None;
+    for i in 2..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "slots" => slots = Some(parse_arg::<i64>(options, i)?),
+            "group" => group = Some(parse_arg(options, i)?),
+            "min" => min = Some(parse_arg::<i32>(options, i)?),
+            "max" => max = Some(parse_arg::<i32>(options, i)?),
+            "hours" => hours = Some(parse_arg(options, i)?),
+            "days" => days = Some(parse_arg(options, i)?),
+            "condition" => {
+                condition = match parse_arg_resolved(options, i)? {
+                    Role(role) => Some(role),
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
         }
+    }
+
+    // Limits can only be set for groups
+    if group.is_none() && (min.is_some() || max.is_some()) {
+        return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()));
+    }
+
+    // Get the duration after which the role gets removed again
+    let duration = match (hours, days) {
+        (None, None) => None,
+        (hours, days) => Some(hours.unwrap_or_default() * 3600 + days.unwrap_or_default() * 86400),
     };
 
+    // Conditions need the role they depend on
+    let condition = match action {
+        Action::Require | Action::Forbid | Action::Clear => match condition {
+            Some(condition) => Some(condition),
+            None => return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+        },
+        _ => None,
+    };
+
+    // Roles can't expire right away
+    if duration == Some(0) {
+        return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()));
+    }
+
     let title = format!("{} reaction-role for {}", action, role.name);
 
     send_response(
@@ -121,45 +172,81 @@ pub async fn execute(
                     // Get the id of the emoji in the emoji table
                     let emoji = database.get_emoji(guild_id, &reaction.emoji).await?;
 
-                    // Get the guild, role, channel and message ids
-                    let guild_db_id = database.get_guild(guild_id).await?;
-                    let role_db_id = database.get_role(guild_id, role.id).await?;
-                    let channel_db_id = database.get_channel(guild_id, reaction.channel_id).await?;
-                    let message_db_id = database
-                        .get_message(guild_id, reaction.channel_id, reaction.message_id)
-                        .await?;
-
                     match action {
                         Action::Add => {
                             // Insert into the database if there is no entry yet
                             database
-                                .client
-                                .execute(
-                                    "
-                                INSERT INTO reaction_roles
-                                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::INT, $5::BIGINT,
-                                    $6::BIGINT)
-                                ON CONFLICT (guild, channel, message, emoji, role)
-                                DO UPDATE SET slots = $6::BIGINT
-                                ",
-                                    &[
-                                        &guild_db_id,
-                                        &channel_db_id,
-                                        &message_db_id,
-                                        &emoji,
-                                        &role_db_id,
-                                        &slots,
-                                    ],
+                                .add_reaction_role(
+                                    guild_id,
+                                    reaction.channel_id,
+                                    reaction.message_id,
+                                    emoji,
+                                    role.id,
+                                    slots,
+                                )
+                                .await?;
+                            database
+                                .set_reaction_role_group_of(
+                                    guild_id,
+                                    reaction.channel_id,
+                                    reaction.message_id,
+                                    role.id,
+                                    group.as_deref(),
+                                )
+                                .await?;
+                            database
+                                .set_reaction_role_duration(
+                                    guild_id,
+                                    reaction.channel_id,
+                                    reaction.message_id,
+                                    emoji,
+                                    role.id,
+                                    duration,
                                 )
                                 .await?;
 
+                            // Update the limits of the group
+                            let limits = match &group {
+                                Some(name) => {
+                                    let mut limits = database
+                                        .reaction_role_group(
+                                            guild_id,
+                                            reaction.channel_id,
+                                            reaction.message_id,
+                                            name,
+                                        )
+                                        .await?;
+
+                                    if min.is_some() || max.is_some() {
+                                        limits.min = min.unwrap_or(limits.min);
+                                        limits.max = max.unwrap_or(limits.max);
+
+                                        if limits.min > limits.max {
+                                            return send_response(
+                                                ctx,
+                                                command,
+                                                command_config,
+                                                &title,
+                                                "The minimum number of roles of a group can't be higher than the maximum.",
+                                            )
+                                            .await;
+                                        }
+
+                                        database.set_reaction_role_group(guild_id, &limits).await?;
+                                    }
+
+                                    Some(limits)
+                                }
+                                None => None,
+                            };
+
                             // React to the message
                             let message = reaction.message(&ctx.http).await?;
                             message.react(&ctx.http, reaction.emoji.clone()).await?;
                             // Remove the reaction of the user
                             reaction.delete(&ctx.http).await?;
 
-                            let content = format!(
+                            let mut content = format!(
                                 "I will assign the role {} to users which react with {} [here]({}).
                                 There are {} role-slots available.",
                                 role.mention(),
@@ -167,25 +254,29 @@ pub async fn execute(
                                 &message.link(),
                                 slots.map_or("unlimited".to_string(), |num| num.to_string())
                             );
+                            if let Some(duration) = duration {
+                                content.push_str(&format!(
+                                    "\nThe role gets removed again after {}.",
+                                    format_duration(duration)
+                                ));
+                            }
+                            if let Some(limits) = limits {
+                                content.push_str(&format!(
+                                    "\nThe role belongs to the group `{}`, of which users hold between {} and {} roles.",
+                                    limits.name, limits.min, limits.max
+                                ));
+                            }
 
                             send_response(ctx, command, command_config, &title, &content).await
                         }
                         Action::Remove => {
                             database
-                                .client
-                                .execute(
-                                    "
-                            DELETE FROM reaction_roles
-                            WHERE guild = $1::BIGINT AND channel = $2::BIGINT
-                            AND message = $3::BIGINT AND emoji = $4::INT AND role = $5::BIGINT
-                            ",
-                                    &[
-                                        &guild_db_id,
-                                        &channel_db_id,
-                                        &message_db_id,
-                                        &emoji,
-                                        &role_db_id,
-                                    ],
+                                .remove_reaction_role(
+                                    guild_id,
+                                    reaction.channel_id,
+                                    reaction.message_id,
+                                    emoji,
+                                    role.id,
                                 )
                                 .await?;
 
@@ -203,6 +294,87 @@ pub async fn execute(
                                     .link()
                             );
 
+                            send_response(ctx, command, command_config, &title, &content).await
+                        }
+                        Action::Require | Action::Forbid | Action::Clear => {
+                            let condition = condition.unwrap();
+
+                            // Get the reaction-role the condition belongs to
+                            let reaction_role = database
+                                .reaction_roles(
+                                    guild_id,
+                                    reaction.channel_id,
+                                    reaction.message_id,
+                                    emoji,
+                                )
+                                .await?
+                                .into_iter()
+                                .find(|reaction_role| reaction_role.role == role.id);
+
+                            // Remove the reaction of the user
+                            reaction.delete(&ctx.http).await?;
+                            let message = reaction.message(&ctx.http).await?;
+
+                            let reaction_role = match reaction_role {
+                                Some(reaction_role) => reaction_role,
+                                None => {
+                                    let content = format!(
+                                        "There is no reaction-role assigning the role {} when reacting with {} [here]({}).",
+                                        role.mention(),
+                                        &reaction.emoji.to_string(),
+                                        &message.link()
+                                    );
+
+                                    return send_response(
+                                        ctx,
+                                        command,
+                                        command_config,
+                                        &title,
+                                        &content,
+                                    )
+                                    .await;
+                                }
+                            };
+
+                            let required = match action {
+                                Action::Require => Some(true),
+                                Action::Forbid => Some(false),
+                                _ => None,
+                            };
+
+                            database
+                                .set_reaction_role_condition(
+                                    guild_id,
+                                    &reaction_role,
+                                    condition.id,
+                                    required,
+                                )
+                                .await?;
+
+                            let content = match required {
+                                Some(true) => format!(
+                                    "Users need the role {} to get the role {} when reacting with {} [here]({}).",
+                                    condition.mention(),
+                                    role.mention(),
+                                    &reaction.emoji.to_string(),
+                                    &message.link()
+                                ),
+                                Some(false) => format!(
+                                    "Users holding the role {} won't get the role {} when reacting with {} [here]({}).",
+                                    condition.mention(),
+                                    role.mention(),
+                                    &reaction.emoji.to_string(),
+                                    &message.link()
+                                ),
+                                None => format!(
+                                    "The role {} no longer affects who gets the role {} when reacting with {} [here]({}).",
+                                    condition.mention(),
+                                    role.mention(),
+                                    &reaction.emoji.to_string(),
+                                    &message.link()
+                                ),
+                            };
+
                             send_response(ctx, command, command_config, &title, &content).await
                         }
                     }
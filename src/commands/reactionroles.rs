@@ -1,16 +1,16 @@
 use itertools::Itertools;
 use serenity::{
-    client::Context,
-    model::{
-        channel::ReactionType,
-        id::{ChannelId, EmojiId, MessageId, RoleId},
-        interactions::application_command::ApplicationCommandInteraction,
-    },
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
     prelude::Mentionable,
 };
 
 use crate::{
-    config::Command, data, database::client::Database, error::KowalskiError, pluralize,
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::ReactionRoleGroup},
+    error::KowalskiError,
+    pluralize,
+    reaction_roles::{format_duration, reaction_type},
     utils::send_response,
 };
 
@@ -24,49 +24,37 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     // Get reaction roles
     let roles = {
-        let rows = database
-            .client
-            .query(
-                "
-                SELECT channel, message, unicode, guild_emoji, role, slots
-                FROM reaction_roles rr
-                INNER JOIN emojis e ON emoji = id
-                WHERE rr.guild = $1::BIGINT
-                ORDER BY channel, message
-                ",
-                &[&guild_db_id],
-            )
-            .await?;
+        let reaction_roles = database.guild_reaction_roles(guild_id).await?;
+        let groups = database.guild_reaction_role_groups(guild_id).await?;
 
         let mut roles = Vec::new();
 
-        for row in rows {
-            let channel_id = ChannelId(row.get::<_, i64>(0) as u64);
-            let message_id = MessageId(row.get::<_, i64>(1) as u64);
-            let unicode: Option<String> = row.get(2);
-            let guild_emoji: Option<i64> = row.get(3);
-            let emoji = match (unicode, guild_emoji) {
-                (Some(string), _) => ReactionType::Unicode(string),
-                (_, Some(id)) => {
-                    let emoji = guild_id.emoji(&ctx.http, EmojiId(id as u64)).await?;
-
-                    ReactionType::Custom {
-                        animated: emoji.animated,
-                        id: emoji.id,
-                        name: Some(emoji.name),
-                    }
-                }
-                _ => unreachable!(),
-            };
-            let role_id = RoleId(row.get::<_, i64>(4) as u64);
-            let slots: Option<i32> = row.get(5);
+        for reaction_role in reaction_roles {
+            let emoji = reaction_type(ctx, guild_id, &reaction_role.emoji).await?;
+
+            // Get the roles required or forbidden to get the role
+            let conditions = database
+                .reaction_role_conditions(guild_id, &reaction_role)
+                .await?;
+
+            // Get the limits of the group of the role
+            let group = reaction_role.group.as_ref().map(|name| {
+                groups
+                    .iter()
+                    .find(|group| {
+                        group.channel == reaction_role.channel
+                            && group.message == reaction_role.message
+                            && group.name == *name
+                    })
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        ReactionRoleGroup::new(reaction_role.channel, reaction_role.message, name)
+                    })
+            });
 
-            roles.push((channel_id, message_id, emoji, role_id, slots));
+            roles.push((reaction_role, emoji, group, conditions));
         }
 
         roles
@@ -74,22 +62,47 @@ pub async fn execute(
 
     let roles = roles
         .iter()
-        .map(|(channel_id, message_id, emoji, role_id, slots)| {
+        .map(|(reaction_role, emoji, group, conditions)| {
             let mut content = format!(
                 "{} when reacting with {} [here]({}).",
-                role_id.mention(),
-                emoji.to_string(),
-                message_id.link(*channel_id, Some(guild_id))
+                reaction_role.role.mention(),
+                emoji,
+                reaction_role
+                    .message
+                    .link(reaction_role.channel, Some(guild_id))
             );
 
-            if let Some(slots) = slots {
+            if let Some(slots) = reaction_role.slots {
                 content.push_str(&format!(
                     " (There {} currently {} available)",
-                    if *slots == 1 { "is" } else { "are" },
-                    pluralize!("slot", *slots)
+                    if slots == 1 { "is" } else { "are" },
+                    pluralize!("slot", slots)
+                ));
+            }
+
+            if let Some(duration) = reaction_role.duration {
+                content.push_str(&format!(" (Expires after {})", format_duration(duration)));
+            }
+
+            if let Some(group) = group {
+                content.push_str(&format!(
+                    " (Group `{}`, users hold {} to {} of its roles)",
+                    group.name, group.min, group.max
                 ));
             }
 
+            for (required, name) in [(true, "Requires"), (false, "Forbids")] {
+                let roles = conditions
+                    .iter()
+                    .filter(|(_, condition)| *condition == required)
+                    .map(|(role_id, _)| role_id.mention())
+                    .join(", ");
+
+                if !roles.is_empty() {
+                    content.push_str(&format!(" ({} {})", name, roles));
+                }
+            }
+
             content
         })
         .join("\n");
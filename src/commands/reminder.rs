@@ -7,7 +7,7 @@ use crate::{
     config::Command,
     config::Config,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage, types::Reminder},
     error::KowalskiError,
     history::History,
     pluralize,
@@ -68,31 +68,16 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild, channel, message and user ids
-    let guild_db_id = database.get_guild(guild_id).await?;
-    let channel_db_id = database.get_channel(guild_id, command.channel_id).await?;
-    let message_db_id = database
-        .get_message(guild_id, command.channel_id, response.id)
-        .await?;
-    let user_db_id = database.get_user(guild_id, command.user.id).await?;
-
     // Add reminder to database
     database
-        .client
-        .execute(
-            "
-    INSERT INTO reminders
-    VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::TIMESTAMPTZ, $6::TEXT)
-    ",
-            &[
-                &guild_db_id,
-                &channel_db_id,
-                &message_db_id,
-                &user_db_id,
-                &datetime,
-                &message,
-            ],
-        )
+        .add_reminder(&Reminder {
+            guild: guild_id,
+            channel: command.channel_id,
+            message: response.id,
+            user: command.user.id,
+            time: datetime,
+            content: message.clone(),
+        })
         .await?;
 
     send_response(
@@ -24,10 +24,9 @@ use crate::{
     config::Command,
     config::Config,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
-    row_id,
     strings::ERR_CMD_ARGS_INVALID,
     utils::{parse_arg_resolved, send_response, send_response_complex},
 };
@@ -59,9 +58,6 @@ pub async fn execute(
 
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild id
-    let guild_db_id = database.get_guild(guild_id).await?;
-
     let options = &command.data.options;
 
     let user = if !options.is_empty() {
@@ -76,62 +72,19 @@ pub async fn execute(
     };
 
     // Get reminders depending on the given argument
-    let reminders: Vec<_> = match user {
-        Some(user) => {
-            // Get user id
-            let user_db_id = database.get_user(guild_id, user.id).await?;
-
-            let rows = database
-                .client
-                .query(
-                    "
-            SELECT channel, time, content
-            FROM reminders
-            WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
-            ORDER BY time
-            ",
-                    &[&guild_db_id, &user_db_id],
-                )
-                .await?;
-
-            rows.iter()
-                .map(|row| {
-                    (
-                        row_id!(ChannelId, row, 0),
-                        None,
-                        row.get::<_, DateTime<Utc>>(1),
-                        row.get(2),
-                    )
-                })
-                .collect()
-        }
-        None => {
-            // Query the next reminder_list_size reminders
-            let rows = database
-                .client
-                .query(
-                    "
-            SELECT channel, \"user\", time, content
-            FROM reminders
-            WHERE guild = $1::BIGINT
-            ORDER BY time
-            ",
-                    &[&guild_db_id],
-                )
-                .await?;
-
-            rows.iter()
-                .map(|row| {
-                    (
-                        row_id!(ChannelId, row, 0),
-                        Some(row_id!(UserId, row, 1)),
-                        row.get::<_, DateTime<Utc>>(2),
-                        row.get(3),
-                    )
-                })
-                .collect()
-        }
-    };
+    let reminders: Vec<_> = database
+        .reminders(guild_id, user.map(|user| user.id))
+        .await?
+        .into_iter()
+        .map(|reminder| {
+            (
+                reminder.channel,
+                user.map_or(Some(reminder.user), |_| None),
+                reminder.time,
+                reminder.content,
+            )
+        })
+        .collect();
 
     if reminders.is_empty() {
         let title = match user {
@@ -150,7 +103,7 @@ pub async fn execute(
     } else {
         let mut page_index = 0;
         let page_size = config.general.reminder_list_size;
-        let page_count = (reminders.len() + page_size - 1) / page_size;
+        let page_count = reminders.len().div_ceil(page_size);
 
         // Loop through interactions until there is a timeout
         while let Some(interaction) = show_page(
@@ -0,0 +1,41 @@
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+};
+
+use crate::{config::Command, error::KowalskiError, level_ups::resync_roles, utils::send_response};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    let guild_id = command.guild_id.unwrap();
+
+    let title = "Resync level-up roles";
+
+    send_response(
+        ctx,
+        command,
+        command_config,
+        title,
+        "Updating the level-up roles of all members, this might take a while.",
+    )
+    .await?;
+
+    // Report the progress in the response
+    let message = command.get_interaction_response(&ctx.http).await?;
+
+    if !resync_roles(ctx.clone(), guild_id, Some(message)) {
+        send_response(
+            ctx,
+            command,
+            command_config,
+            title,
+            "The level-up roles of this server are being updated already, \
+            they get updated again once that is done.",
+        )
+        .await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use serenity::{
+    client::Context,
+    model::interactions::application_command::{
+        ApplicationCommandInteraction,
+        ApplicationCommandInteractionDataOptionValue::{Channel, Role},
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage, types::ScoreReward},
+    error::KowalskiError,
+    error::KowalskiError::DiscordApiError,
+    strings::ERR_CMD_ARGS_INVALID,
+    utils::{parse_arg, parse_arg_resolved, send_response},
+};
+
+enum Action {
+    Add,
+    Remove,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Action::Add => "Add",
+            Action::Remove => "Remove",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Action {
+    type Err = KowalskiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "add" => Ok(Action::Add),
+            "remove" => Ok(Action::Remove),
+            _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+        }
+    }
+}
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    // Parse arguments
+    let mut action = None;
+    let mut reward = ScoreReward {
+        score: 0,
+        role: None,
+        channel: None,
+        message: None,
+    };
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "action" => action = Some(Action::from_str(parse_arg(options, i)?)?),
+            "score" => reward.score = parse_arg(options, i)?,
+            "role" => match parse_arg_resolved(options, i)? {
+                Role(role) => reward.role = Some(role.id),
+                _ => unreachable!(),
+            },
+            "channel" => match parse_arg_resolved(options, i)? {
+                Channel(channel) => reward.channel = Some(channel.id),
+                _ => unreachable!(),
+            },
+            "message" => reward.message = Some(parse_arg(options, i)?),
+            _ => unreachable!(),
+        }
+    }
+    let action = action.unwrap();
+
+    let title = format!("{} score reward", action);
+
+    match action {
+        Action::Add => {
+            // A reward needs a role or a message with a channel to send it to
+            let message = reward.channel.is_some() && reward.message.is_some();
+            if reward.role.is_none() && !message {
+                return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()));
+            }
+
+            database.add_score_reward(guild_id, &reward).await?;
+
+            let mut content = format!(
+                "Users reaching a score of {} for the first time will now",
+                reward.score
+            );
+            if let Some(role_id) = reward.role {
+                content.push_str(&format!(" receive the role {} for good", role_id.mention()));
+                if message {
+                    content.push_str(" and");
+                }
+            }
+            if let (true, Some(channel_id)) = (message, reward.channel) {
+                content.push_str(&format!(" get announced in {}", channel_id.mention()));
+            }
+            content.push('.');
+
+            send_response(ctx, command, command_config, &title, &content).await
+        }
+        Action::Remove => {
+            let removed = database.remove_score_reward(guild_id, reward.score).await?;
+
+            let content = if removed {
+                format!("I have removed the reward on score {}.", reward.score)
+            } else {
+                format!(
+                    "There is no reward defined for score {}.
+                    I didn't remove anything.",
+                    reward.score
+                )
+            };
+
+            send_response(ctx, command, command_config, &title, &content).await
+        }
+    }
+}
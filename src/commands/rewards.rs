@@ -0,0 +1,82 @@
+use itertools::Itertools;
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    utils::send_response,
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    // Get the rewards and how the level changes get announced
+    let rewards = database.score_rewards(guild_id).await?;
+    let announcement = database.level_up_announcement(guild_id).await?;
+
+    let rewards = rewards
+        .iter()
+        .map(|reward| {
+            let mut parts = Vec::new();
+            if let Some(role_id) = reward.role {
+                parts.push(role_id.mention().to_string());
+            }
+            if let (Some(channel_id), Some(message)) = (reward.channel, &reward.message) {
+                parts.push(format!("\"{}\" in {}", message, channel_id.mention()));
+            }
+
+            format!(
+                "**score {} {}**: {}",
+                if reward.score >= 0 { ">=" } else { "<=" },
+                reward.score,
+                parts.join(", ")
+            )
+        })
+        .join("\n");
+
+    let mut content = if rewards.is_empty() {
+        "There are currently no rewards defined for this server.".to_string()
+    } else {
+        format!(
+            "Users receive the following rewards the first time they reach a certain score:
+            {}",
+            rewards
+        )
+    };
+
+    match announcement {
+        Some(announcement) => {
+            let place = match announcement.channel {
+                Some(channel_id) => format!("in {}", channel_id.mention()),
+                None => "through direct messages".to_string(),
+            };
+            if let Some(level_up) = announcement.level_up {
+                content.push_str(&format!(
+                    "\n\nLevel-ups are announced {}: \"{}\"",
+                    place, level_up
+                ));
+            }
+            if let Some(level_down) = announcement.level_down {
+                content.push_str(&format!(
+                    "\n\nLevel-downs are announced {}: \"{}\"",
+                    place, level_down
+                ));
+            }
+        }
+        None => content.push_str("\n\nLevel-ups and level-downs are not announced."),
+    }
+
+    send_response(ctx, command, command_config, "Rewards", &content).await
+}
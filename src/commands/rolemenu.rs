@@ -0,0 +1,175 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use serenity::{
+    builder::{CreateActionRow, CreateSelectMenuOption},
+    client::Context,
+    model::interactions::{
+        application_command::ApplicationCommandInteraction, message_component::ButtonStyle,
+    },
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    error::KowalskiError::DiscordApiError,
+    reaction_roles::{button_id, reaction_type, select_id, select_value},
+    strings::ERR_CMD_ARGS_INVALID,
+    utils::{create_embed, parse_arg, parse_message, send_response},
+};
+
+enum Style {
+    Buttons,
+    Menu,
+}
+
+impl FromStr for Style {
+    type Err = KowalskiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "buttons" => Ok(Style::Buttons),
+            "menu" => Ok(Style::Menu),
+            _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+        }
+    }
+}
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let guild_id = command.guild_id.unwrap();
+
+    let options = &command.data.options;
+
+    // Parse arguments
+    let mut style = None;
+    let mut message = None;
+    let mut menu_title = "Role menu";
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "style" => style = Some(Style::from_str(parse_arg(options, i)?)?),
+            "message" => message = Some(parse_message(parse_arg(options, i)?, command.channel_id)?),
+            "title" => menu_title = parse_arg(options, i)?,
+            _ => unreachable!(),
+        }
+    }
+    let (style, (channel_id, message_id)) = match (style, message) {
+        (Some(style), Some(message)) => (style, message),
+        _ => return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+    };
+
+    let title = "Post role menu";
+
+    // Get the reaction-roles of the message
+    let reaction_roles = database
+        .message_reaction_roles(guild_id, channel_id, message_id)
+        .await?;
+
+    // Discord allows 25 buttons per message and 25 options per select menu
+    if reaction_roles.is_empty() || reaction_roles.len() > 25 {
+        return send_response(
+            ctx,
+            command,
+            command_config,
+            title,
+            "A role menu can only offer between 1 and 25 reaction-roles.",
+        )
+        .await;
+    }
+
+    // Get the names of the roles
+    let roles = guild_id.roles(&ctx.http).await?;
+
+    // Get the emojis of the reaction-roles
+    let mut entries = Vec::new();
+    for reaction_role in &reaction_roles {
+        let emoji = reaction_type(ctx, guild_id, &reaction_role.emoji).await?;
+        let name = roles
+            .get(&reaction_role.role)
+            .map_or(reaction_role.role.to_string(), |role| role.name.clone());
+
+        entries.push((reaction_role, emoji, name));
+    }
+
+    let action_rows = match style {
+        Style::Buttons => entries
+            .chunks(5)
+            .map(|chunk| {
+                let mut row = CreateActionRow::default();
+
+                for (reaction_role, emoji, name) in chunk {
+                    row.create_button(|button| {
+                        button
+                            .label(name)
+                            .emoji(emoji.clone())
+                            .custom_id(button_id(reaction_role))
+                            .style(ButtonStyle::Secondary)
+                    });
+                }
+
+                row
+            })
+            .collect(),
+        Style::Menu => {
+            let mut row = CreateActionRow::default();
+
+            row.create_select_menu(|menu| {
+                menu.custom_id(select_id(channel_id, message_id))
+                    .placeholder("Choose your roles")
+                    .min_values(0)
+                    .max_values(entries.len() as u64)
+                    .options(|options| {
+                        options.set_options(
+                            entries
+                                .iter()
+                                .map(|(reaction_role, emoji, name)| {
+                                    let mut option = CreateSelectMenuOption::new(
+                                        name,
+                                        select_value(reaction_role),
+                                    );
+                                    option.emoji(emoji.clone());
+                                    option
+                                })
+                                .collect(),
+                        )
+                    })
+            });
+
+            vec![row]
+        }
+    };
+
+    // Describe the offered roles
+    let content = entries
+        .iter()
+        .map(|(reaction_role, emoji, _)| format!("{} {}", emoji, reaction_role.role.mention()))
+        .join("\n");
+
+    // Post the menu
+    let menu = command
+        .channel_id
+        .send_message(&ctx.http, |message| {
+            message
+                .set_embed(create_embed(menu_title, &content))
+                .components(|components| components.set_action_rows(action_rows))
+        })
+        .await?;
+
+    send_response(
+        ctx,
+        command,
+        command_config,
+        title,
+        &format!("I have posted the role menu [here]({}).", menu.link()),
+    )
+    .await
+}
@@ -1,12 +1,10 @@
+use std::str::FromStr;
+
 use itertools::Itertools;
 use serenity::{
     client::Context,
-    model::{
-        channel::ReactionType,
-        id::{EmojiId, UserId},
-        interactions::application_command::{
-            ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
-        },
+    model::interactions::application_command::{
+        ApplicationCommandInteraction, ApplicationCommandInteractionDataOptionValue::User,
     },
     prelude::Mentionable,
 };
@@ -14,9 +12,12 @@ use serenity::{
 use crate::{
     config::Command,
     data,
-    database::client::Database,
+    database::{client::Database, storage::Storage},
     error::KowalskiError,
-    utils::{parse_arg_resolved, send_response_complex},
+    leaderboard::{half_life, Period},
+    pluralize,
+    reaction_roles::reaction_type,
+    utils::{parse_arg, parse_arg_resolved, send_response_complex},
 };
 
 pub async fn execute(
@@ -29,197 +30,109 @@ pub async fn execute(
 
     let options = &command.data.options;
 
-    // Parse argument (use command user as fallback)
-    let user = if !options.is_empty() {
-        match parse_arg_resolved(options, 0)? {
-            User(user, ..) => user,
+    // Parse arguments (use command user and all time as fallback)
+    let mut user = &command.user;
+    let mut period = Period::AllTime;
+    for i in 0..options.len() {
+        match options.get(i).unwrap().name.as_str() {
+            "user" => {
+                user = match parse_arg_resolved(options, i)? {
+                    User(user, ..) => user,
+                    _ => unreachable!(),
+                }
+            }
+            "period" => period = Period::from_str(parse_arg(options, i)?)?,
             _ => unreachable!(),
         }
-    } else {
-        &command.user
-    };
+    }
 
     // Get guild
     let guild_id = command.guild_id.unwrap();
 
-    // Get guild and user ids
-    let guild_db_id = database.get_guild(guild_id).await?;
-    let user_db_id = database.get_user(guild_id, user.id).await?;
+    // Get the time frame of the reactions and how fast they decay
+    let start = period.start();
+    let decay = database.score_decay(guild_id).await?;
+    let half_life = half_life(database.as_ref(), guild_id).await?;
 
     // Analyze reactions of the user
-    let (upvotes, downvotes) = {
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT SUM(CASE WHEN upvote THEN 1 END) upvotes,
-        SUM(CASE WHEN NOT upvote THEN 1 END) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        let upvotes: Option<i64> = row.get(0);
-        let downvotes: Option<i64> = row.get(1);
+    let scores = database.guild_scores(guild_id, start, half_life).await?;
+    let (rank, upvotes, downvotes) =
+        match scores.iter().position(|&(user_id, ..)| user_id == user.id) {
+            Some(index) => {
+                let (_, upvotes, downvotes) = scores[index];
 
-        (upvotes.unwrap_or_default(), downvotes.unwrap_or_default())
-    };
+                ((index + 1).to_string(), upvotes, downvotes)
+            }
+            None => (String::from("not available"), 0, 0),
+        };
     let score = upvotes - downvotes;
     let emojis = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT unicode, guild_emoji, COUNT(*) FROM score_reactions r
-        INNER JOIN emojis e ON r.emoji = e.id
-        WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT
-        GROUP BY emoji, unicode, guild_emoji
-        ORDER BY count DESC
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
         let mut emojis = Vec::new();
 
-        for row in rows {
-            let unicode: Option<String> = row.get(0);
-            let guild_emoji: Option<i64> = row.get(1);
-            let count: i64 = row.get(2);
-
-            let emoji = match (unicode, guild_emoji) {
-                (Some(string), _) => ReactionType::Unicode(string),
-                (_, Some(id)) => {
-                    let emoji = guild_id.emoji(&ctx.http, EmojiId(id as u64)).await?;
-
-                    ReactionType::Custom {
-                        animated: emoji.animated,
-                        id: emoji.id,
-                        name: Some(emoji.name),
-                    }
-                }
-                _ => unreachable!(),
-            };
-
-            emojis.push((emoji, count));
+        for (emoji, count) in database
+            .received_emojis(Some(guild_id), user.id, start)
+            .await?
+        {
+            emojis.push((reaction_type(ctx, guild_id, &emoji).await?, count));
         }
 
         emojis
     };
-    let rank = {
-        let row = database.client.query_opt("
-            WITH ranks AS (
-                SELECT user_to,
-                RANK() OVER (
-                    ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC, user_to
-                ) rank
-                FROM score_reactions r
-                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-                WHERE r.guild = $1::BIGINT
-                GROUP BY user_to
-            )
-
-            SELECT rank FROM ranks
-            WHERE user_to = $2::BIGINT
-            ", &[&guild_db_id, &user_db_id]).await?;
-
-        row.map(|row| row.get::<_, i64>(0))
-    };
-    let rank = match rank {
-        Some(rank) => rank.to_string(),
-        None => String::from("not available"),
-    };
-
-    let top_users: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT user_from, COUNT(*) FILTER (WHERE upvote) upvotes,
-        COUNT(*) FILTER (WHERE NOT upvote) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND native = true
-        GROUP BY user_from
-        HAVING COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) >= 0
-        ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC
-        LIMIT 5
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| {
-                let user: i64 = row.get(0);
-                let upvotes: Option<i64> = row.get(1);
-                let downvotes: Option<i64> = row.get(2);
-
-                (
-                    UserId(user as u64),
-                    upvotes.unwrap_or_default(),
-                    downvotes.unwrap_or_default(),
-                )
-            })
-            .collect()
-    };
-
-    let bottom_users: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT user_from, COUNT(*) FILTER (WHERE upvote) upvotes,
-        COUNT(*) FILTER (WHERE NOT upvote) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND native = true
-        GROUP BY user_from
-        HAVING COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) < 0
-        ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) ASC
-        LIMIT 5
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| {
-                let user: i64 = row.get(0);
-                let upvotes: Option<i64> = row.get(1);
-                let downvotes: Option<i64> = row.get(2);
-
-                (
-                    UserId(user as u64),
-                    upvotes.unwrap_or_default(),
-                    downvotes.unwrap_or_default(),
-                )
-            })
-            .collect()
-    };
+    let total: i64 = emojis.iter().map(|(_, count)| count).sum();
+
+    let channels: Vec<_> = database
+        .user_channel_scores(guild_id, user.id, start, half_life)
+        .await?
+        .into_iter()
+        .take(5)
+        .collect();
+
+    let voters = database
+        .user_voters(guild_id, user.id, start, half_life)
+        .await?;
+    let top_users: Vec<_> = voters
+        .iter()
+        .filter(|(_, upvotes, downvotes)| upvotes - downvotes >= 0)
+        .take(5)
+        .collect();
+    let bottom_users: Vec<_> = voters
+        .iter()
+        .rev()
+        .filter(|(_, upvotes, downvotes)| upvotes - downvotes < 0)
+        .take(5)
+        .collect();
+
+    let mut content = format!(
+        "The user {} has a score of **{}** [+{}, -{}] (rank **{}**) {}.",
+        user.mention(),
+        score,
+        upvotes,
+        downvotes,
+        rank,
+        match period {
+            Period::AllTime => "of all time".to_string(),
+            period => period.to_string(),
+        }
+    );
+    if let Some(decay) = decay {
+        content.push_str(&format!(
+            "\nReactions count half as much every {}.",
+            pluralize!("day", decay.half_life)
+        ));
+    }
 
     send_response_complex(
         &ctx,
         &command,
         command_config,
         &format!("Score of {}", user.name),
-        &format!(
-            "The user {} currently has a score of **{}** [+{}, -{}] (rank **{}**).",
-            user.mention(),
-            score,
-            upvotes,
-            downvotes,
-            rank
-        ),
+        &content,
         |embed| {
             let mut emojis = emojis
                 .iter()
                 .map(|(reaction, count)| {
                     let f_count = *count as f64;
-                    let f_total = (upvotes + downvotes) as f64;
+                    let f_total = total as f64;
                     format!(
                         "**{}x{}** ({:.1}%)",
                         count,
@@ -232,6 +145,17 @@ pub async fn execute(
                 emojis = "Not available".to_string();
             }
 
+            let mut channels = channels
+                .iter()
+                .map(|(channel, multiplier, score)| match multiplier {
+                    1 => format!("{}: **{}**", channel.mention(), score),
+                    _ => format!("{}: **{}** (×{})", channel.mention(), score, multiplier),
+                })
+                .join("\n");
+            if channels.is_empty() {
+                channels = "Not available".to_string();
+            }
+
             let mut top_users = top_users
                 .iter()
                 .map(|(user, upvotes, downvotes)| {
@@ -266,6 +190,7 @@ pub async fn execute(
 
             embed.fields(vec![
                 ("Emojis", emojis, false),
+                ("Top 5 channels", channels, false),
                 ("Top 5 benefactors", top_users, false),
                 ("Top 5 haters", bottom_users, false),
             ])
@@ -20,8 +20,9 @@ use crate::{
     database::client::Database,
     error::KowalskiError,
     error::KowalskiError::DiscordApiError,
+    leaderboard::{self, Period},
     strings::ERR_CMD_ARGS_INVALID,
-    utils::{send_response, send_response_complex},
+    utils::{parse_arg, send_response, send_response_complex},
 };
 
 enum ComponentInteractionResponse {
@@ -49,55 +50,30 @@ pub async fn execute(
     // Get config and database
     let (config, database) = data!(ctx, (Config, Database));
 
-    // Get guild id
-    let guild_db_id = database.get_guild(command.guild_id.unwrap()).await?;
+    let options = &command.data.options;
 
-    // Get top users
-    let top: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-        SELECT user_to, COUNT(*) FILTER (WHERE upvote) upvotes,
-        COUNT(*) FILTER (WHERE NOT upvote) downvotes
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT
-        GROUP BY user_to
-        ORDER BY COUNT(*) FILTER (WHERE upvote) - COUNT(*) FILTER (WHERE NOT upvote) DESC, user_to
-        ",
-                &[&guild_db_id],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| {
-                let user: i64 = row.get(0);
-                let upvotes: Option<i64> = row.get(1);
-                let downvotes: Option<i64> = row.get(2);
-
-                (
-                    UserId(user as u64),
-                    upvotes.unwrap_or_default(),
-                    downvotes.unwrap_or_default(),
-                )
-            })
-            .collect()
+    // Parse argument (use all time as fallback)
+    let period = if !options.is_empty() {
+        Period::from_str(parse_arg(options, 0)?)?
+    } else {
+        Period::AllTime
     };
 
+    // Get top users
+    let top = leaderboard::scores(database.as_ref(), command.guild_id.unwrap(), period).await?;
+
     if top.is_empty() {
         send_response(
             ctx,
             command,
             command_config,
-            "Top Scores",
+            &format!("Top Scores of {}", period),
             "Looks like there are no scores to display :(",
         )
         .await
     } else {
         let mut page_index = 0;
         let page_size = config.general.leaderboard_size;
-        let page_count = (top.len() + page_size - 1) / page_size;
 
         // Loop through interactions until there is a timeout
         while let Some(interaction) = show_page(
@@ -105,8 +81,8 @@ pub async fn execute(
             command,
             command_config,
             &top,
+            period,
             page_index,
-            page_count,
             page_size,
             &config.general.leaderboard_titles,
             Duration::from_secs(config.general.interaction_timeout),
@@ -135,12 +111,14 @@ async fn show_page(
     command: &ApplicationCommandInteraction,
     command_config: &Command,
     top: &Vec<(UserId, i64, i64)>,
+    period: Period,
     index: usize,
-    count: usize,
     size: usize,
     rank_titles: &Vec<String>,
     timeout: Duration,
 ) -> Result<Option<ComponentInteractionResponse>, KowalskiError> {
+    let count = top.len().div_ceil(size);
+
     let mut row = CreateActionRow::default();
     row.create_button(|button| {
         button
@@ -162,7 +140,7 @@ async fn show_page(
         ctx,
         command,
         command_config,
-        &format!("Top Scores (Page {}/{})", index + 1, count),
+        &format!("Top Scores of {} (Page {}/{})", period, index + 1, count),
         "",
         |embed| {
             // Get start index
@@ -0,0 +1,106 @@
+use itertools::Itertools;
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::{Command, Config},
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    leaderboard::format_ranks,
+    utils::{parse_arg, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    let options = &command.data.options;
+
+    let guild_id = command.guild_id.unwrap();
+
+    let seasons = database.seasons(guild_id).await?;
+
+    if options.is_empty() {
+        let title = "Past seasons";
+
+        if seasons.is_empty() {
+            return send_response(
+                ctx,
+                command,
+                command_config,
+                title,
+                "There are no past seasons on this guild yet.",
+            )
+            .await;
+        }
+
+        // Get the winners of the latest seasons
+        let mut content = String::new();
+        for season in seasons.iter().take(config.general.leaderboard_size) {
+            let winners: Vec<_> = database
+                .season_ranks(guild_id, season.number)
+                .await?
+                .into_iter()
+                .filter(|rank| rank.rank == 1)
+                .collect();
+
+            // The first season has no start
+            let period = match season.started {
+                Some(started) => format!(
+                    "{} - {}",
+                    started.format("%d.%m.%y"),
+                    season.ended.format("%d.%m.%y")
+                ),
+                None => format!("until {}", season.ended.format("%d.%m.%y")),
+            };
+
+            content.push_str(&format!("**Season {}** ({}): ", season.number, period));
+
+            match winners.first() {
+                Some(winner) => content.push_str(&format!(
+                    "{} with a score of **{}**\n",
+                    winners.iter().map(|rank| rank.user.mention()).join(", "),
+                    winner.score
+                )),
+                None => content.push_str("No winner\n"),
+            }
+        }
+
+        send_response(ctx, command, command_config, title, &content).await
+    } else {
+        // Parse argument
+        let number: i32 = parse_arg(options, 0)?;
+
+        let title = format!("Final leaderboard of season {}", number);
+
+        if !seasons.iter().any(|season| season.number == number) {
+            return send_response(
+                ctx,
+                command,
+                command_config,
+                &title,
+                "There is no such season on this guild.",
+            )
+            .await;
+        }
+
+        let ranks = database.season_ranks(guild_id, number).await?;
+
+        let mut content = format_ranks(
+            &ranks[..ranks.len().min(config.general.leaderboard_size)],
+            &config.general.leaderboard_titles,
+        );
+        if content.is_empty() {
+            content = "Nobody received any reactions this season.".to_string();
+        }
+
+        send_response(ctx, command, command_config, &title, &content).await
+    }
+}
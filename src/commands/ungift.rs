@@ -0,0 +1,63 @@
+use serenity::{
+    client::Context, model::interactions::application_command::ApplicationCommandInteraction,
+    prelude::Mentionable,
+};
+
+use crate::{
+    config::Command,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    pluralize,
+    utils::{parse_arg, send_response},
+};
+
+pub async fn execute(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+) -> Result<(), KowalskiError> {
+    // Get database
+    let database = data!(ctx, Database);
+
+    let options = &command.data.options;
+
+    // Parse argument
+    let gift: i64 = parse_arg(options, 0)?;
+
+    let guild_id = command.guild_id.unwrap();
+
+    let title = format!("Undo gift #{}", gift);
+
+    // Move the upvotes back to the user who gifted them
+    match database.undo_gift(guild_id, gift as i32).await? {
+        Some((gift, restored)) => {
+            let mut content = format!(
+                "Moved {} back from {} to {}.",
+                pluralize!("point", restored),
+                gift.user_to.mention(),
+                gift.user_from.mention()
+            );
+
+            // Some reactions might have been removed since the gift
+            if restored < gift.amount as u64 {
+                content.push_str(&format!(
+                    "\n\nThe remaining {} of the gift do not exist anymore.",
+                    pluralize!("point", gift.amount - restored as i64)
+                ));
+            }
+
+            send_response(ctx, command, command_config, &title, &content).await
+        }
+        None => {
+            send_response(
+                ctx,
+                command,
+                command_config,
+                &title,
+                "There is no such gift or it was undone already.",
+            )
+            .await
+        }
+    }
+}
@@ -17,6 +17,7 @@ use crate::strings::{ERR_CONFIG_PARSE, ERR_CONFIG_READ};
 #[derive(Deserialize)]
 pub struct Config {
     pub general: General,
+    pub database: DatabaseConfig,
     pub commands: HashMap<String, Command>,
 }
 
@@ -31,15 +32,52 @@ pub struct General {
     pub leaderboard_titles: Vec<String>,
     pub credits_margin: i64,
     pub pickup_timeout: u64,
+    pub notification_timeout: u64,
+    pub reconciliation_messages: u64,
+    pub reconciliation_days: i64,
+    pub reconciliation_delay: u64,
+    pub resync_batch_size: usize,
+    pub resync_delay: u64,
+    pub abuse_min_score: i64,
+    pub abuse_max_share: i64,
+    pub abuse_account_age: i32,
+    pub abuse_burst_size: i64,
+    pub abuse_report_size: i64,
+    pub chart_width: u32,
+    pub chart_height: u32,
     pub nlp_max_message_length: usize,
     pub nlp_max_messages: u64,
     pub nlp_group_size: usize,
+    pub gift_list_size: usize,
     pub reminder_list_size: usize,
     pub reminder_list_max_message_length: usize,
     pub publishing_link: String,
     pub publishing_length: usize,
 }
 
+#[derive(Clone, Deserialize)]
+pub struct DatabaseConfig {
+    pub pool_size: usize,
+    pub connection_timeout: u64,
+    pub reconnect_attempts: u32,
+    pub reconnect_backoff: u64,
+    pub reconnect_backoff_max: u64,
+    pub health_check_interval: u64,
+    pub ssl_mode: Option<SslMode>,
+    pub ca_certificate: Option<String>,
+    pub client_certificate: Option<String>,
+    pub client_key: Option<String>,
+}
+
+/// TLS modes of the database connection parsed by the config.
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SslMode {
+    Disable,
+    Prefer,
+    Require,
+}
+
 #[derive(Deserialize)]
 pub struct Command {
     pub command_type: CommandType,
@@ -66,24 +104,41 @@ pub enum CommandType {
     Publish,
     Reminder,
     Reminders,
+    Abuse,
+    Announcement,
+    Cap,
+    Channel,
+    Channels,
     Cooldown,
     Cooldowns,
+    Decay,
     Drop,
     Drops,
     Emoji,
     Emojis,
+    Export,
     Gift,
+    GiftCap,
+    Gifts,
     Given,
     Givens,
     Global,
+    History,
     LevelUp,
     LevelUps,
     Moderation,
     Moderations,
+    NewSeason,
+    Resync,
+    Reward,
+    Rewards,
     Score,
     Scores,
+    Season,
+    Ungift,
     ReactionRole,
     ReactionRoles,
+    RoleMenu,
     Mood,
     Oracle,
     Tldr,
@@ -190,6 +245,16 @@ impl Into<ApplicationCommandOptionType> for OptionType {
     }
 }
 
+impl From<SslMode> for tokio_postgres::config::SslMode {
+    fn from(mode: SslMode) -> Self {
+        match mode {
+            SslMode::Disable => tokio_postgres::config::SslMode::Disable,
+            SslMode::Prefer => tokio_postgres::config::SslMode::Prefer,
+            SslMode::Require => tokio_postgres::config::SslMode::Require,
+        }
+    }
+}
+
 impl Into<ChannelType> for Channel {
     fn into(self) -> ChannelType {
         match self {
@@ -1,22 +1,53 @@
-use std::{cmp::min, collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, NaiveTime, Utc};
 use serenity::{
+    client::Context,
     model::id::{GuildId, RoleId, UserId},
     prelude::TypeMapKey,
 };
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, time::interval};
+use tracing::error;
+
+use crate::{
+    config::Config,
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+    strings::ERR_COOLDOWN_EVICTION,
+};
 
-use crate::{config::Config, database::client::Database, error::KowalskiError};
+/// Whether the eviction loop was started already, the ready event is fired again on reconnects.
+static STARTED: AtomicBool = AtomicBool::new(false);
 
 /// Cooldown struct containing a map, mapping guild ids to the cooldowns of the guild.
+///
+/// Note: The cooldowns are persisted in the storage, this only caches the ones known to be active
 pub struct Cooldowns {
     guilds: HashMap<GuildId, GuildCooldowns>,
 }
 
-/// GuildCooldowns struct containing a map, mapping user ids to the cooldowns of the reaction.
+/// GuildCooldowns struct containing a map, mapping the cooldown keys to the cooldowns of the reaction.
 struct GuildCooldowns {
-    cooldowns: HashMap<UserId, DateTime<Utc>>,
+    cooldowns: HashMap<CooldownKey, DateTime<Utc>>,
+}
+
+/// The giver, the receiver if the cooldown only applies to them and whether the cooldown only
+/// applies to downvotes.
+type CooldownKey = (UserId, Option<UserId>, bool);
+
+/// A vote a user is about to give.
+pub struct Vote {
+    pub guild: GuildId,
+    pub user_from: UserId,
+    pub user_to: UserId,
+    pub upvote: bool,
 }
 
 impl Cooldowns {
@@ -26,66 +57,128 @@ impl Cooldowns {
         }
     }
 
-    /// Check whether the user currently has a cooldown active.
+    /// Check whether the user currently has a cooldown active or has used up the votes allowed.
     ///
     /// Note: This will start a new cooldown, if no cooldown is currently active.
     pub async fn check_cooldown(
         &mut self,
         config: &Config,
-        database: &Database,
-        guild_id: GuildId,
-        user_id: UserId,
+        storage: &dyn Storage,
+        vote: &Vote,
         roles: &[RoleId],
     ) -> Result<bool, KowalskiError> {
+        let policy = storage.cooldown_policy(vote.guild).await?;
+
+        // Get the key of the cooldown which applies to the vote
+        let split = !vote.upvote && policy.downvote_cooldown.is_some();
+        let key = (
+            vote.user_from,
+            policy.per_receiver.then_some(vote.user_to),
+            split,
+        );
+
         // Get or create guild cooldowns
-        let guild_cooldowns = self.guilds.entry(guild_id).or_insert(GuildCooldowns {
+        let guild_cooldowns = self.guilds.entry(vote.guild).or_insert(GuildCooldowns {
             cooldowns: HashMap::new(),
         });
 
-        let active = match guild_cooldowns.cooldowns.get(&user_id) {
-            Some(&date) => date > Utc::now(),
-            None => false,
+        // Skip the storage if the cooldown is known to be active
+        if let Some(&date) = guild_cooldowns.cooldowns.get(&key) {
+            if date > Utc::now() {
+                return Ok(true);
+            }
+        }
+
+        // Check whether the user has given as many votes as allowed today
+        if let Some(budget) = policy.daily_budget {
+            let today = Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+
+            if storage
+                .given_votes(vote.guild, vote.user_from, today)
+                .await?
+                >= budget
+            {
+                return Ok(true);
+            }
+        }
+
+        // Check whether the user has given as many votes as allowed within the window
+        if let Some((votes, minutes)) = policy.window {
+            let since = Utc::now() - Duration::minutes(minutes);
+
+            if storage
+                .given_votes(vote.guild, vote.user_from, since)
+                .await?
+                >= votes
+            {
+                return Ok(true);
+            }
+        }
+
+        // Get the cooldown of the user (the smallest one of the roles applies)
+        let cooldown = match policy.downvote_cooldown {
+            Some(cooldown) if split => cooldown,
+            _ => storage
+                .role_cooldown(vote.guild, roles)
+                .await?
+                .map_or(config.general.default_cooldown, |cooldown| {
+                    cooldown.min(config.general.default_cooldown)
+                }),
         };
+        let cooldown_end = Utc::now() + Duration::seconds(cooldown);
+
+        // Start a new cooldown, another instance might have started one already
+        let active = storage
+            .start_cooldown(vote.guild, key.0, key.1, key.2, cooldown_end)
+            .await?;
 
-        // Get guild and role ids
-        let guild_db_id = database.get_guild(guild_id).await?;
-
-        // Add new cooldown if none is active
-        if !active {
-            let cooldown_end = {
-                let mut cooldown = config.general.default_cooldown;
-
-                for &role_id in roles {
-                    let role_db_id = database.get_role(guild_id, role_id).await?;
-
-                    let row = database
-                        .client
-                        .query_opt(
-                            "
-                        SELECT cooldown
-                        FROM score_cooldowns
-                        WHERE guild = $1::BIGINT AND role = $2::BIGINT
-                        ",
-                            &[&guild_db_id, &role_db_id],
-                        )
-                        .await?;
-
-                    if let Some(row) = row {
-                        let role_cooldown = row.get(0);
-                        cooldown = min(cooldown, role_cooldown);
-                    }
-                }
-
-                Utc::now() + Duration::seconds(cooldown)
-            };
-
-            guild_cooldowns.cooldowns.insert(user_id, cooldown_end);
+        guild_cooldowns
+            .cooldowns
+            .insert(key, active.unwrap_or(cooldown_end));
+
+        Ok(active.is_some())
+    }
+
+    /// Removes the cooldowns which are over from the cache.
+    fn evict(&mut self) {
+        let now = Utc::now();
+
+        for guild_cooldowns in self.guilds.values_mut() {
+            guild_cooldowns.cooldowns.retain(|_, date| *date > now);
         }
 
-        Ok(active)
+        self.guilds
+            .retain(|_, guild_cooldowns| !guild_cooldowns.cooldowns.is_empty());
     }
 }
 
 impl TypeMapKey for Cooldowns {
     type Value = Arc<RwLock<Cooldowns>>;
 }
+
+/// Periodically removes the cooldowns which are over.
+pub fn evict_cooldowns(ctx: Context, period: std::time::Duration) {
+    // Only run one eviction loop
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        // Get database and cooldowns
+        let (database, cooldowns_lock) = data!(ctx, (Database, Cooldowns));
+
+        // Create the interval at which we will remove the cooldowns
+        let mut interval = interval(period);
+
+        loop {
+            // Wait for the next tick
+            interval.tick().await;
+
+            cooldowns_lock.write().await.evict();
+
+            if let Err(why) = database.remove_expired_cooldowns().await {
+                error!("{}: {}", ERR_COOLDOWN_EVICTION, why);
+            }
+        }
+    });
+}
@@ -0,0 +1,3070 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    sync::{Mutex, MutexGuard},
+};
+
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use serenity::{
+    async_trait,
+    model::{
+        channel::ReactionType,
+        id::{ChannelId, EmojiId, GuildId, MessageId, RoleId, UserId},
+    },
+};
+
+use crate::{
+    database::{
+        storage::Storage,
+        types::{
+            ChannelScoring, CooldownPolicy, DominantGiver, DropMode, GiftCaps, LevelUpAnnouncement,
+            ModerationRule, ModuleStatus, NewAccountBurst, PendingDrop, ReactionRole,
+            ReactionRoleExpiration, ReactionRoleGroup, ReciprocalVotes, Reminder, ScoreDecay,
+            ScoreGift, ScoreReaction, ScoreReward, Season, SeasonRank, StoredEmoji,
+        },
+    },
+    error::KowalskiError,
+};
+
+/// A storage keeping everything in memory, used to run the bot logic without a database.
+///
+/// Note: Nothing is persisted, all data is gone once the storage gets dropped
+#[derive(Default)]
+pub struct MemoryStorage {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    guilds: HashSet<GuildId>,
+    users: HashSet<(GuildId, UserId)>,
+    roles: HashSet<(GuildId, RoleId)>,
+    channels: HashSet<(GuildId, ChannelId)>,
+    messages: HashSet<(GuildId, ChannelId, MessageId)>,
+    emojis: Vec<StoredEmoji>,
+    emoji_guilds: HashMap<EmojiId, GuildId>,
+    modules: HashMap<GuildId, ModuleStatus>,
+    score_emojis: HashMap<(GuildId, i32), i32>,
+    score_reactions: Vec<ScoreReaction>,
+    score_removed_votes: HashSet<(GuildId, UserId, ChannelId, MessageId, i32)>,
+    score_returns: Vec<(GuildId, UserId, DateTime<Utc>, i64)>,
+    score_roles: Vec<(GuildId, RoleId, i64)>,
+    score_role_stacking: HashSet<GuildId>,
+    score_cooldowns: HashMap<(GuildId, RoleId), i64>,
+    score_user_cooldowns: HashMap<(GuildId, UserId, Option<UserId>, bool), DateTime<Utc>>,
+    score_cooldown_policies: HashMap<GuildId, CooldownPolicy>,
+    score_announcements: HashMap<GuildId, LevelUpAnnouncement>,
+    score_rewards: Vec<(GuildId, ScoreReward)>,
+    score_reward_claims: HashSet<(GuildId, i64, UserId)>,
+    score_moderation_rules: Vec<(GuildId, ModerationRule)>,
+    score_moderation_triggers: HashSet<(i32, ChannelId, MessageId)>,
+    moderation_rule_count: i32,
+    score_decay: HashMap<GuildId, ScoreDecay>,
+    score_caps: HashMap<GuildId, i64>,
+    score_drop_modes: HashMap<GuildId, DropMode>,
+    score_drops: HashSet<(GuildId, ChannelId)>,
+    score_pending_drops: HashMap<(GuildId, UserId), (PendingDrop, Vec<UserId>)>,
+    score_gifts: Vec<(GuildId, ScoreGift, Vec<ScoreReaction>)>,
+    score_gift_caps: HashMap<GuildId, GiftCaps>,
+    score_channels: HashMap<(GuildId, ChannelId), ChannelScoring>,
+    score_seasons: Vec<(GuildId, Season)>,
+    score_season_starts: HashMap<GuildId, DateTime<Utc>>,
+    score_season_ranks: Vec<(GuildId, i32, SeasonRank)>,
+    score_snapshots: HashMap<(GuildId, UserId, NaiveDate), i64>,
+    reaction_roles: Vec<(GuildId, i32, ReactionRole)>,
+    reaction_role_groups: HashMap<(GuildId, ChannelId, MessageId, String), ReactionRoleGroup>,
+    reaction_role_expirations: Vec<ReactionRoleExpiration>,
+    reaction_role_conditions: HashMap<(GuildId, ChannelId, MessageId, i32, RoleId, RoleId), bool>,
+    reminders: Vec<Reminder>,
+    publishing: HashMap<GuildId, String>,
+    owned_guilds: HashSet<GuildId>,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        MemoryStorage::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // A panic while holding the lock can't leave the state half-updated, so ignore poisoning
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl State {
+    fn emoji_id(&self, emoji: &StoredEmoji) -> Option<i32> {
+        // Emoji ids start at 1, just like a SERIAL column
+        self.emojis
+            .iter()
+            .position(|stored| stored == emoji)
+            .map(|index| index as i32 + 1)
+    }
+
+    /// Gets the weight of a reaction, multiplied by the multiplier of its channel.
+    ///
+    /// Note: Returns None if the reaction does not count towards the score
+    fn weight(&self, reaction: &ScoreReaction) -> Option<i64> {
+        // Votes from before the current season don't count
+        if let Some(started) = self.score_season_starts.get(&reaction.guild) {
+            if reaction.time < *started {
+                return None;
+            }
+        }
+
+        let weight = self.score_emojis.get(&(reaction.guild, reaction.emoji))?;
+
+        match self.score_channels.get(&(reaction.guild, reaction.channel)) {
+            Some(scoring) if scoring.excluded => None,
+            Some(scoring) => Some(*weight as i64 * scoring.multiplier as i64),
+            None => Some(*weight as i64),
+        }
+    }
+
+    /// Gets the points counting towards the scores, which are the weights of the reactions and of
+    /// the upvotes returned to their givers, with their guild, the user receiving them and their
+    /// time.
+    fn points(&self) -> impl Iterator<Item = (GuildId, UserId, DateTime<Utc>, i64)> + '_ {
+        let reactions = self.score_reactions.iter().filter_map(|reaction| {
+            Some((
+                reaction.guild,
+                reaction.user_to,
+                reaction.time,
+                self.weight(reaction)?,
+            ))
+        });
+
+        // Upvotes returned before the current season don't count either
+        let returns = self
+            .score_returns
+            .iter()
+            .copied()
+            .filter(|&(guild, _, time, _)| {
+                self.score_season_starts
+                    .get(&guild)
+                    .is_none_or(|started| time >= *started)
+            });
+
+        reactions.chain(returns)
+    }
+
+    /// Gets the score every user has given every other user through native upvotes since a
+    /// certain time.
+    fn native_upvotes(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+    ) -> HashMap<(UserId, UserId), i64> {
+        let mut pairs = HashMap::new();
+        for reaction in self.score_reactions.iter().filter(|reaction| {
+            reaction.guild == guild_id && reaction.native && reaction.time >= start
+        }) {
+            if let Some(weight) = self.weight(reaction).filter(|&weight| weight > 0) {
+                *pairs
+                    .entry((reaction.user_from, reaction.user_to))
+                    .or_default() += weight;
+            }
+        }
+
+        pairs
+    }
+
+    /// Gets the weight of a reaction like `weight`, halving it every `half_life` days.
+    fn decayed_weight(
+        &self,
+        reaction: &ScoreReaction,
+        half_life: f64,
+        now: DateTime<Utc>,
+    ) -> Option<f64> {
+        self.weight(reaction).map(|weight| {
+            let days = (now - reaction.time).num_seconds() as f64 / 86400.0;
+
+            weight as f64 * 0.5f64.powf(days / half_life)
+        })
+    }
+
+    /// Removes the messages of a guild matching a condition, including everything bound to them.
+    fn remove_messages(
+        &mut self,
+        guild_id: GuildId,
+        matches: impl Fn(ChannelId, MessageId) -> bool,
+    ) {
+        self.messages
+            .retain(|&(guild, channel, message)| !(guild == guild_id && matches(channel, message)));
+        self.remove_reaction_roles(guild_id, |role| matches(role.channel, role.message));
+        self.reaction_role_groups
+            .retain(|&(guild, channel, message, _), _| {
+                !(guild == guild_id && matches(channel, message))
+            });
+        self.reminders.retain(|reminder| {
+            !(reminder.guild == guild_id && matches(reminder.channel, reminder.message))
+        });
+        self.score_moderation_triggers
+            .retain(|&(_, channel, message)| !matches(channel, message));
+        self.score_removed_votes
+            .retain(|&(guild, _, channel, message, _)| {
+                !(guild == guild_id && matches(channel, message))
+            });
+    }
+
+    /// Removes the reaction-roles of a guild matching a condition, including their conditions and
+    /// expirations.
+    fn remove_reaction_roles(
+        &mut self,
+        guild_id: GuildId,
+        matches: impl Fn(&ReactionRole) -> bool,
+    ) {
+        let removed: Vec<_> = self
+            .reaction_roles
+            .iter()
+            .filter(|(guild, _, role)| *guild == guild_id && matches(role))
+            .map(|(_, emoji, role)| (role.channel, role.message, *emoji, role.role))
+            .collect();
+
+        self.reaction_roles
+            .retain(|(guild, _, role)| !(*guild == guild_id && matches(role)));
+        self.reaction_role_conditions
+            .retain(|&(guild, channel, message, emoji, role, _), _| {
+                !(guild == guild_id && removed.contains(&(channel, message, emoji, role)))
+            });
+        self.reaction_role_expirations.retain(|expiration| {
+            !(expiration.guild == guild_id
+                && removed.contains(&(
+                    expiration.channel,
+                    expiration.message,
+                    expiration.emoji,
+                    expiration.role,
+                )))
+        });
+    }
+
+    /// Removes the moderation rules of a guild matching a condition, including their triggers.
+    fn remove_moderation_rules(
+        &mut self,
+        guild_id: GuildId,
+        matches: impl Fn(&ModerationRule) -> bool,
+    ) {
+        let removed: Vec<_> = self
+            .score_moderation_rules
+            .iter()
+            .filter(|(guild, rule)| *guild == guild_id && matches(rule))
+            .map(|(_, rule)| rule.id)
+            .collect();
+
+        self.score_moderation_rules
+            .retain(|(guild, rule)| !(*guild == guild_id && matches(rule)));
+        self.score_moderation_triggers
+            .retain(|(rule, ..)| !removed.contains(rule));
+    }
+
+    /// Removes the score rewards of a guild matching a condition, including their claims.
+    fn remove_score_rewards(&mut self, guild_id: GuildId, matches: impl Fn(&ScoreReward) -> bool) {
+        let removed: Vec<_> = self
+            .score_rewards
+            .iter()
+            .filter(|(guild, reward)| *guild == guild_id && matches(reward))
+            .map(|(_, reward)| reward.score)
+            .collect();
+
+        self.score_rewards
+            .retain(|(guild, reward)| !(*guild == guild_id && matches(reward)));
+        self.score_reward_claims
+            .retain(|&(guild, score, _)| !(guild == guild_id && removed.contains(&score)));
+    }
+
+    fn score<'a>(&self, reactions: impl Iterator<Item = &'a ScoreReaction>) -> i64 {
+        reactions.filter_map(|reaction| self.weight(reaction)).sum()
+    }
+}
+
+/// Gets the up- and downvotes of a user from the votes of all users, with the rank of the user.
+///
+/// Note: Users with the same score are ranked by their id, just like in the database
+fn global_rank(
+    votes: impl Iterator<Item = (UserId, i64)>,
+    user_id: UserId,
+) -> (i64, i64, Option<i64>) {
+    let mut users: HashMap<UserId, (i64, i64)> = HashMap::new();
+    for (user, weight) in votes {
+        let (upvotes, downvotes) = users.entry(user).or_default();
+
+        if weight > 0 {
+            *upvotes += weight;
+        } else {
+            *downvotes -= weight;
+        }
+    }
+
+    users
+        .into_iter()
+        .sorted_by_key(|&(user, (upvotes, downvotes))| (-(upvotes - downvotes), user))
+        .enumerate()
+        .find(|&(_, (user, _))| user == user_id)
+        .map_or((0, 0, None), |(index, (_, (upvotes, downvotes)))| {
+            (upvotes, downvotes, Some(index as i64 + 1))
+        })
+}
+
+fn stored_emoji(emoji: &ReactionType) -> StoredEmoji {
+    match emoji {
+        ReactionType::Custom { id, .. } => StoredEmoji::Guild(*id),
+        ReactionType::Unicode(string) => StoredEmoji::Unicode(string.clone()),
+        _ => unreachable!(),
+    }
+}
+
+#[async_trait]
+impl Storage for MemoryStorage {
+    async fn get_guild(&self, guild_id: GuildId) -> Result<i64, KowalskiError> {
+        self.state().guilds.insert(guild_id);
+
+        Ok(guild_id.0 as i64)
+    }
+
+    async fn get_user(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.users.insert((guild_id, user_id));
+
+        Ok(user_id.0 as i64)
+    }
+
+    async fn get_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<i64, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.roles.insert((guild_id, role_id));
+
+        Ok(role_id.0 as i64)
+    }
+
+    async fn get_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<i64, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.channels.insert((guild_id, channel_id));
+
+        Ok(channel_id.0 as i64)
+    }
+
+    async fn get_message(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<i64, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.channels.insert((guild_id, channel_id));
+        state.messages.insert((guild_id, channel_id, message_id));
+
+        Ok(message_id.0 as i64)
+    }
+
+    async fn get_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: &ReactionType,
+    ) -> Result<i32, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+
+        let emoji = stored_emoji(emoji);
+        if let StoredEmoji::Guild(emoji_id) = emoji {
+            state.emoji_guilds.insert(emoji_id, guild_id);
+        }
+
+        match state.emoji_id(&emoji) {
+            Some(id) => Ok(id),
+            None => {
+                state.emojis.push(emoji);
+
+                Ok(state.emojis.len() as i32)
+            }
+        }
+    }
+
+    async fn find_emoji(&self, emoji: &ReactionType) -> Result<Option<i32>, KowalskiError> {
+        Ok(self.state().emoji_id(&stored_emoji(emoji)))
+    }
+
+    async fn remove_user(&self, guild_id: GuildId, user_id: UserId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.users.remove(&(guild_id, user_id));
+
+        // Remember the removed votes, they are still on the messages
+        let removed: Vec<_> = state
+            .score_reactions
+            .iter()
+            .filter(|reaction| reaction.guild == guild_id && reaction.user_to == user_id)
+            .map(|reaction| {
+                (
+                    guild_id,
+                    reaction.user_from,
+                    reaction.channel,
+                    reaction.message,
+                    reaction.emoji,
+                )
+            })
+            .collect();
+        state.score_removed_votes.extend(removed);
+        state
+            .score_reactions
+            .retain(|reaction| !(reaction.guild == guild_id && reaction.user_to == user_id));
+        state
+            .score_returns
+            .retain(|&(guild, user, ..)| !(guild == guild_id && user == user_id));
+        state
+            .reminders
+            .retain(|reminder| !(reminder.guild == guild_id && reminder.user == user_id));
+        state
+            .reaction_role_expirations
+            .retain(|expiration| !(expiration.guild == guild_id && expiration.user == user_id));
+        state.score_pending_drops.remove(&(guild_id, user_id));
+
+        Ok(())
+    }
+
+    async fn user_guild_count(&self, user_id: UserId) -> Result<i64, KowalskiError> {
+        Ok(self
+            .state()
+            .users
+            .iter()
+            .filter(|&&(_, user)| user == user_id)
+            .count() as i64)
+    }
+
+    async fn guilds(&self) -> Result<Vec<GuildId>, KowalskiError> {
+        Ok(self.state().guilds.iter().copied().collect())
+    }
+
+    async fn remove_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.remove(&guild_id);
+        state.users.retain(|&(guild, _)| guild != guild_id);
+        state.roles.retain(|&(guild, _)| guild != guild_id);
+        state.channels.retain(|&(guild, _)| guild != guild_id);
+        state.remove_messages(guild_id, |_, _| true);
+        state.emoji_guilds.retain(|_, guild| *guild != guild_id);
+        state.modules.remove(&guild_id);
+        state
+            .score_emojis
+            .retain(|&(guild, _), _| guild != guild_id);
+        state
+            .score_reactions
+            .retain(|reaction| reaction.guild != guild_id);
+        state.score_returns.retain(|(guild, ..)| *guild != guild_id);
+        state.score_roles.retain(|&(guild, ..)| guild != guild_id);
+        state.score_role_stacking.remove(&guild_id);
+        state
+            .score_cooldowns
+            .retain(|&(guild, _), _| guild != guild_id);
+        state
+            .score_user_cooldowns
+            .retain(|&(guild, ..), _| guild != guild_id);
+        state.score_cooldown_policies.remove(&guild_id);
+        state.score_announcements.remove(&guild_id);
+        state.remove_score_rewards(guild_id, |_| true);
+        state.remove_moderation_rules(guild_id, |_| true);
+        state.score_decay.remove(&guild_id);
+        state.score_caps.remove(&guild_id);
+        state.score_drop_modes.remove(&guild_id);
+        state.score_drops.retain(|&(guild, _)| guild != guild_id);
+        state
+            .score_pending_drops
+            .retain(|&(guild, _), _| guild != guild_id);
+        state.score_gifts.retain(|(guild, ..)| *guild != guild_id);
+        state.score_gift_caps.remove(&guild_id);
+        state
+            .score_channels
+            .retain(|&(guild, _), _| guild != guild_id);
+        state.score_seasons.retain(|(guild, _)| *guild != guild_id);
+        state.score_season_starts.remove(&guild_id);
+        state
+            .score_season_ranks
+            .retain(|(guild, ..)| *guild != guild_id);
+        state
+            .score_snapshots
+            .retain(|&(guild, ..), _| guild != guild_id);
+        state.publishing.remove(&guild_id);
+        state.owned_guilds.remove(&guild_id);
+
+        Ok(())
+    }
+
+    async fn guild_users(&self, guild_id: GuildId) -> Result<Vec<UserId>, KowalskiError> {
+        Ok(self
+            .state()
+            .users
+            .iter()
+            .filter(|&&(guild, _)| guild == guild_id)
+            .map(|&(_, user)| user)
+            .collect())
+    }
+
+    async fn guild_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError> {
+        Ok(self
+            .state()
+            .channels
+            .iter()
+            .filter(|&&(guild, _)| guild == guild_id)
+            .map(|&(_, channel)| channel)
+            .collect())
+    }
+
+    async fn remove_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.channels.remove(&(guild_id, channel_id));
+        state.remove_messages(guild_id, |channel, _| channel == channel_id);
+        state.score_channels.remove(&(guild_id, channel_id));
+        state.score_drops.remove(&(guild_id, channel_id));
+        if state
+            .score_announcements
+            .get(&guild_id)
+            .is_some_and(|announcement| announcement.channel == Some(channel_id))
+        {
+            state.score_announcements.remove(&guild_id);
+        }
+        state.remove_score_rewards(guild_id, |reward| reward.channel == Some(channel_id));
+        state.remove_moderation_rules(guild_id, |rule| {
+            rule.channel == Some(channel_id) || rule.target == Some(channel_id)
+        });
+
+        Ok(())
+    }
+
+    async fn guild_roles(&self, guild_id: GuildId) -> Result<Vec<RoleId>, KowalskiError> {
+        Ok(self
+            .state()
+            .roles
+            .iter()
+            .filter(|&&(guild, _)| guild == guild_id)
+            .map(|&(_, role)| role)
+            .collect())
+    }
+
+    async fn remove_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.roles.remove(&(guild_id, role_id));
+        state.score_cooldowns.remove(&(guild_id, role_id));
+        state
+            .score_roles
+            .retain(|&(guild, role, _)| !(guild == guild_id && role == role_id));
+        state.remove_reaction_roles(guild_id, |role| role.role == role_id);
+        state
+            .reaction_role_conditions
+            .retain(|&(guild, .., required), _| !(guild == guild_id && required == role_id));
+        state.remove_score_rewards(guild_id, |reward| reward.role == Some(role_id));
+        state.remove_moderation_rules(guild_id, |rule| rule.role == Some(role_id));
+
+        Ok(())
+    }
+
+    async fn remove_messages(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_ids: &[MessageId],
+    ) -> Result<(), KowalskiError> {
+        self.state().remove_messages(guild_id, |channel, message| {
+            channel == channel_id && message_ids.contains(&message)
+        });
+
+        Ok(())
+    }
+
+    async fn guild_emojis(&self, guild_id: GuildId) -> Result<Vec<EmojiId>, KowalskiError> {
+        Ok(self
+            .state()
+            .emoji_guilds
+            .iter()
+            .filter(|&(_, &guild)| guild == guild_id)
+            .map(|(&emoji, _)| emoji)
+            .collect())
+    }
+
+    async fn remove_guild_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji_id: EmojiId,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        if state.emoji_guilds.get(&emoji_id) != Some(&guild_id) {
+            return Ok(());
+        }
+        state.emoji_guilds.remove(&emoji_id);
+
+        // The emoji keeps its id, as the ids of the other emojis depend on its position
+        let emoji = match state.emoji_id(&StoredEmoji::Guild(emoji_id)) {
+            Some(emoji) => emoji,
+            None => return Ok(()),
+        };
+        state
+            .score_emojis
+            .retain(|&(guild, id), _| !(guild == guild_id && id == emoji));
+        state
+            .score_reactions
+            .retain(|reaction| !(reaction.guild == guild_id && reaction.emoji == emoji));
+        state
+            .score_removed_votes
+            .retain(|&(guild, .., id)| !(guild == guild_id && id == emoji));
+        state.remove_reaction_roles(guild_id, |role| role.emoji_id == emoji);
+
+        Ok(())
+    }
+
+    async fn module_status(&self, guild_id: GuildId) -> Result<ModuleStatus, KowalskiError> {
+        Ok(self
+            .state()
+            .modules
+            .get(&guild_id)
+            .cloned()
+            .unwrap_or_else(ModuleStatus::default))
+    }
+
+    async fn module_statuses(&self) -> Result<Vec<(GuildId, ModuleStatus)>, KowalskiError> {
+        Ok(self
+            .state()
+            .modules
+            .iter()
+            .map(|(&guild_id, status)| (guild_id, status.clone()))
+            .collect())
+    }
+
+    async fn set_module_status(
+        &self,
+        guild_id: GuildId,
+        status: &ModuleStatus,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.modules.insert(guild_id, status.clone());
+
+        Ok(())
+    }
+
+    async fn remove_utility_data(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.publishing.remove(&guild_id);
+        state
+            .reminders
+            .retain(|reminder| reminder.guild != guild_id);
+
+        Ok(())
+    }
+
+    async fn remove_score_data(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state
+            .score_cooldowns
+            .retain(|&(guild, _), _| guild != guild_id);
+        state.score_drops.retain(|&(guild, _)| guild != guild_id);
+        state
+            .score_emojis
+            .retain(|&(guild, _), _| guild != guild_id);
+        state
+            .score_reactions
+            .retain(|reaction| reaction.guild != guild_id);
+        state
+            .score_removed_votes
+            .retain(|&(guild, ..)| guild != guild_id);
+        state.remove_moderation_rules(guild_id, |_| true);
+        state.score_roles.retain(|&(guild, ..)| guild != guild_id);
+
+        Ok(())
+    }
+
+    async fn remove_reaction_role_data(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state
+            .reaction_role_groups
+            .retain(|&(guild, ..), _| guild != guild_id);
+        state.remove_reaction_roles(guild_id, |_| true);
+
+        Ok(())
+    }
+
+    async fn score_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: i32,
+    ) -> Result<Option<i32>, KowalskiError> {
+        Ok(self.state().score_emojis.get(&(guild_id, emoji)).copied())
+    }
+
+    async fn score_emojis(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(StoredEmoji, i32)>, KowalskiError> {
+        let state = self.state();
+
+        Ok(state
+            .score_emojis
+            .iter()
+            .filter(|((guild, _), _)| *guild == guild_id)
+            .map(|(&(_, emoji), &weight)| (state.emojis[emoji as usize - 1].clone(), weight))
+            .collect())
+    }
+
+    async fn set_score_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: i32,
+        weight: i32,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.score_emojis.insert((guild_id, emoji), weight);
+
+        Ok(())
+    }
+
+    async fn remove_score_emoji(&self, guild_id: GuildId, emoji: i32) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.score_emojis.remove(&(guild_id, emoji));
+        state
+            .score_reactions
+            .retain(|reaction| !(reaction.guild == guild_id && reaction.emoji == emoji));
+
+        Ok(())
+    }
+
+    async fn add_score_reaction(&self, reaction: &ScoreReaction) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(reaction.guild);
+        state.users.insert((reaction.guild, reaction.user_from));
+        state.users.insert((reaction.guild, reaction.user_to));
+        state.channels.insert((reaction.guild, reaction.channel));
+        state
+            .messages
+            .insert((reaction.guild, reaction.channel, reaction.message));
+
+        // Reactions are unique apart from whether they were given natively
+        let exists = state.score_reactions.iter().any(|stored| {
+            ScoreReaction {
+                native: reaction.native,
+                time: reaction.time,
+                ..stored.clone()
+            } == *reaction
+        });
+
+        if !exists {
+            state.score_reactions.push(reaction.clone());
+        }
+
+        state.score_removed_votes.remove(&(
+            reaction.guild,
+            reaction.user_from,
+            reaction.channel,
+            reaction.message,
+            reaction.emoji,
+        ));
+
+        Ok(())
+    }
+
+    async fn remove_score_reaction(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+    ) -> Result<Option<UserId>, KowalskiError> {
+        let mut state = self.state();
+        let mut user_to = None;
+
+        state.score_reactions.retain(|reaction| {
+            let matches = reaction.guild == guild_id
+                && reaction.user_from == user_from
+                && reaction.channel == channel_id
+                && reaction.message == message_id
+                && reaction.emoji == emoji;
+
+            if matches {
+                user_to = Some(reaction.user_to);
+            }
+
+            !matches
+        });
+
+        Ok(user_to)
+    }
+
+    async fn message_score_reactions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<ScoreReaction>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_reactions
+            .iter()
+            .filter(|reaction| {
+                reaction.guild == guild_id
+                    && reaction.channel == channel_id
+                    && reaction.message == message_id
+            })
+            .cloned()
+            .collect())
+    }
+
+    async fn removed_votes(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<(UserId, i32)>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_removed_votes
+            .iter()
+            .filter(|(guild, _, channel, message, _)| {
+                *guild == guild_id && *channel == channel_id && *message == message_id
+            })
+            .map(|&(_, user_from, _, _, emoji)| (user_from, emoji))
+            .collect())
+    }
+
+    async fn tracked_messages(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(ChannelId, MessageId)>, KowalskiError> {
+        let mut messages: Vec<_> = self
+            .state()
+            .messages
+            .iter()
+            .filter(|(guild, ..)| *guild == guild_id)
+            .map(|&(_, channel, message)| (channel, message))
+            .collect();
+        messages.sort_by_key(|&(_, message)| std::cmp::Reverse(message));
+
+        Ok(messages)
+    }
+
+    async fn score_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_reactions
+            .iter()
+            .filter(|reaction| reaction.guild == guild_id)
+            .map(|reaction| reaction.channel)
+            .unique()
+            .collect())
+    }
+
+    async fn remove_message_score_reactions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), KowalskiError> {
+        self.state().score_reactions.retain(|reaction| {
+            !(reaction.guild == guild_id
+                && reaction.channel == channel_id
+                && reaction.message == message_id)
+        });
+
+        Ok(())
+    }
+
+    async fn user_score(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError> {
+        Ok(self
+            .state()
+            .points()
+            .filter(|&(guild, user, ..)| guild == guild_id && user == user_id)
+            .map(|(.., weight)| weight)
+            .sum())
+    }
+
+    async fn user_decayed_score(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        half_life: i64,
+    ) -> Result<i64, KowalskiError> {
+        let state = self.state();
+        let now = Utc::now();
+
+        let score: f64 = state
+            .points()
+            .filter(|&(guild, user, ..)| guild == guild_id && user == user_id)
+            .map(|(_, _, time, weight)| {
+                let days = (now - time).num_seconds() as f64 / 86400.0;
+
+                weight as f64 * 0.5f64.powf(days / half_life as f64)
+            })
+            .sum();
+
+        Ok(score.round() as i64)
+    }
+
+    async fn guild_scores(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(UserId, i64, i64)>, KowalskiError> {
+        let state = self.state();
+        let now = Utc::now();
+
+        // Sum up the decayed up- and downvotes of every user
+        let mut votes: HashMap<UserId, (f64, f64)> = HashMap::new();
+        for (_, user, time, weight) in state
+            .points()
+            .filter(|&(guild, _, time, _)| guild == guild_id && time >= start)
+        {
+            let days = (now - time).num_seconds() as f64 / 86400.0;
+            let decayed = weight as f64 * 0.5f64.powf(days / half_life);
+            let (upvotes, downvotes) = votes.entry(user).or_default();
+
+            if weight > 0 {
+                *upvotes += decayed;
+            } else {
+                *downvotes -= decayed;
+            }
+        }
+
+        Ok(votes
+            .into_iter()
+            .sorted_by(|(user_a, (up_a, down_a)), (user_b, (up_b, down_b))| {
+                (up_b - down_b)
+                    .total_cmp(&(up_a - down_a))
+                    .then(user_a.cmp(user_b))
+            })
+            .map(|(user, (upvotes, downvotes))| {
+                (user, upvotes.round() as i64, downvotes.round() as i64)
+            })
+            .collect())
+    }
+
+    async fn guild_givens(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError> {
+        let state = self.state();
+
+        // Sum up the up- and downvotes given by every user
+        let mut votes: HashMap<UserId, (i64, i64, i64)> = HashMap::new();
+        for reaction in state
+            .score_reactions
+            .iter()
+            .filter(|reaction| reaction.guild == guild_id)
+        {
+            if let Some(weight) = state.weight(reaction) {
+                let (upvotes, downvotes, gifted) = votes.entry(reaction.user_from).or_default();
+
+                if weight > 0 {
+                    *upvotes += weight;
+                } else {
+                    *downvotes -= weight;
+                }
+                if !reaction.native {
+                    *gifted += weight;
+                }
+            }
+        }
+
+        Ok(votes
+            .into_iter()
+            .map(|(user, (upvotes, downvotes, gifted))| (user, upvotes, downvotes, gifted))
+            .sorted_by_key(|&(user, upvotes, downvotes, _)| (-(upvotes - downvotes), user))
+            .collect())
+    }
+
+    async fn received_emojis(
+        &self,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+        start: DateTime<Utc>,
+    ) -> Result<Vec<(StoredEmoji, i64)>, KowalskiError> {
+        let state = self.state();
+
+        Ok(state
+            .score_reactions
+            .iter()
+            .filter(|reaction| {
+                guild_id.is_none_or(|guild_id| reaction.guild == guild_id)
+                    && reaction.user_to == user_id
+                    && reaction.time >= start
+                    && state.weight(reaction).is_some()
+            })
+            .counts_by(|reaction| reaction.emoji)
+            .into_iter()
+            .sorted_by_key(|&(emoji, count)| (-(count as i64), emoji))
+            .map(|(emoji, count)| (state.emojis[emoji as usize - 1].clone(), count as i64))
+            .collect())
+    }
+
+    async fn given_emojis(
+        &self,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+    ) -> Result<Vec<(StoredEmoji, i64)>, KowalskiError> {
+        let state = self.state();
+
+        Ok(state
+            .score_reactions
+            .iter()
+            .filter(|reaction| {
+                guild_id.is_none_or(|guild_id| reaction.guild == guild_id)
+                    && reaction.user_from == user_id
+                    && state.weight(reaction).is_some()
+            })
+            .counts_by(|reaction| reaction.emoji)
+            .into_iter()
+            .sorted_by_key(|&(emoji, count)| (-(count as i64), emoji))
+            .map(|(emoji, count)| (state.emojis[emoji as usize - 1].clone(), count as i64))
+            .collect())
+    }
+
+    async fn user_channel_scores(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(ChannelId, i32, i64)>, KowalskiError> {
+        let state = self.state();
+        let now = Utc::now();
+
+        let mut scores: HashMap<ChannelId, f64> = HashMap::new();
+        for reaction in state.score_reactions.iter().filter(|reaction| {
+            reaction.guild == guild_id && reaction.user_to == user_id && reaction.time >= start
+        }) {
+            if let Some(weight) = state.decayed_weight(reaction, half_life, now) {
+                *scores.entry(reaction.channel).or_default() += weight;
+            }
+        }
+
+        Ok(scores
+            .into_iter()
+            .map(|(channel, score)| {
+                let multiplier = state
+                    .score_channels
+                    .get(&(guild_id, channel))
+                    .map_or(1, |scoring| scoring.multiplier);
+
+                (channel, multiplier, score.round() as i64)
+            })
+            .sorted_by_key(|&(channel, _, score)| (-score, channel))
+            .collect())
+    }
+
+    async fn user_voters(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(UserId, i64, i64)>, KowalskiError> {
+        let state = self.state();
+        let now = Utc::now();
+
+        // Sum up the decayed up- and downvotes given by every user
+        let mut votes: HashMap<UserId, (f64, f64)> = HashMap::new();
+        for reaction in state.score_reactions.iter().filter(|reaction| {
+            reaction.guild == guild_id
+                && reaction.user_to == user_id
+                && reaction.native
+                && reaction.time >= start
+        }) {
+            if let Some(weight) = state.decayed_weight(reaction, half_life, now) {
+                let (upvotes, downvotes) = votes.entry(reaction.user_from).or_default();
+
+                if weight > 0.0 {
+                    *upvotes += weight;
+                } else {
+                    *downvotes -= weight;
+                }
+            }
+        }
+
+        Ok(votes
+            .into_iter()
+            .sorted_by(|(user_a, (up_a, down_a)), (user_b, (up_b, down_b))| {
+                (up_b - down_b)
+                    .total_cmp(&(up_a - down_a))
+                    .then(user_a.cmp(user_b))
+            })
+            .map(|(user, (upvotes, downvotes))| {
+                (user, upvotes.round() as i64, downvotes.round() as i64)
+            })
+            .collect())
+    }
+
+    async fn user_recipients(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError> {
+        let state = self.state();
+
+        // Sum up the up- and downvotes given to every user
+        let mut votes: HashMap<UserId, (i64, i64, i64)> = HashMap::new();
+        for reaction in state
+            .score_reactions
+            .iter()
+            .filter(|reaction| reaction.guild == guild_id && reaction.user_from == user_id)
+        {
+            if let Some(weight) = state.weight(reaction) {
+                let (upvotes, downvotes, gifted) = votes.entry(reaction.user_to).or_default();
+
+                if weight > 0 {
+                    *upvotes += weight;
+                } else {
+                    *downvotes -= weight;
+                }
+                if !reaction.native {
+                    *gifted += weight;
+                }
+            }
+        }
+
+        Ok(votes
+            .into_iter()
+            .map(|(user, (upvotes, downvotes, gifted))| (user, upvotes, downvotes, gifted))
+            .sorted_by_key(|&(user, upvotes, downvotes, _)| (-(upvotes - downvotes), user))
+            .collect())
+    }
+
+    async fn global_user_score(
+        &self,
+        user_id: UserId,
+    ) -> Result<(i64, i64, Option<i64>), KowalskiError> {
+        let state = self.state();
+
+        Ok(global_rank(
+            state.points().map(|(_, user, _, weight)| (user, weight)),
+            user_id,
+        ))
+    }
+
+    async fn global_user_givens(
+        &self,
+        user_id: UserId,
+    ) -> Result<(i64, i64, Option<i64>), KowalskiError> {
+        let state = self.state();
+
+        Ok(global_rank(
+            state
+                .score_reactions
+                .iter()
+                .filter_map(|reaction| Some((reaction.user_from, state.weight(reaction)?))),
+            user_id,
+        ))
+    }
+
+    async fn message_score(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<i64, KowalskiError> {
+        let state = self.state();
+
+        Ok(state.score(state.score_reactions.iter().filter(|reaction| {
+            reaction.guild == guild_id
+                && reaction.channel == channel_id
+                && reaction.message == message_id
+        })))
+    }
+
+    async fn user_upvotes(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError> {
+        let state = self.state();
+
+        Ok(state
+            .score_reactions
+            .iter()
+            .filter(|reaction| reaction.guild == guild_id && reaction.user_to == user_id)
+            .filter_map(|reaction| state.weight(reaction))
+            .filter(|&weight| weight > 0)
+            .sum())
+    }
+
+    async fn pair_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+    ) -> Result<i64, KowalskiError> {
+        let state = self.state();
+
+        Ok(state
+            .score_reactions
+            .iter()
+            .filter(|reaction| {
+                reaction.guild == guild_id
+                    && reaction.user_from == user_from
+                    && reaction.user_to == user_to
+                    && reaction.native
+            })
+            .filter_map(|reaction| state.weight(reaction))
+            .filter(|&weight| weight > 0)
+            .sum())
+    }
+
+    async fn reciprocal_votes(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        min_score: i64,
+        limit: i64,
+    ) -> Result<Vec<ReciprocalVotes>, KowalskiError> {
+        let pairs = self.state().native_upvotes(guild_id, start);
+
+        let mut reciprocal: Vec<_> = pairs
+            .iter()
+            .filter(|(&(user_a, user_b), _)| user_a < user_b)
+            .filter_map(|(&(user_a, user_b), &score_a)| {
+                let score_b = *pairs.get(&(user_b, user_a))?;
+
+                (score_a >= min_score && score_b >= min_score).then_some(ReciprocalVotes {
+                    users: (user_a, user_b),
+                    scores: (score_a, score_b),
+                })
+            })
+            .collect();
+        reciprocal.sort_by_key(|votes| {
+            (
+                -votes.scores.0.min(votes.scores.1),
+                votes.users.0,
+                votes.users.1,
+            )
+        });
+        reciprocal.truncate(limit.max(0) as usize);
+
+        Ok(reciprocal)
+    }
+
+    async fn dominant_givers(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        min_score: i64,
+        min_share: i64,
+        limit: i64,
+    ) -> Result<Vec<DominantGiver>, KowalskiError> {
+        let pairs = self.state().native_upvotes(guild_id, start);
+
+        let mut totals: HashMap<UserId, i64> = HashMap::new();
+        for (&(_, user_to), score) in &pairs {
+            *totals.entry(user_to).or_default() += score;
+        }
+
+        let mut givers: Vec<_> = pairs
+            .into_iter()
+            .map(|((user_from, user_to), score)| DominantGiver {
+                user_from,
+                user_to,
+                score,
+                total: totals[&user_to],
+            })
+            .filter(|giver| {
+                giver.score >= min_score && giver.score * 100 >= giver.total * min_share
+            })
+            .collect();
+        givers.sort_by(|a, b| {
+            (b.score as f64 / b.total as f64)
+                .total_cmp(&(a.score as f64 / a.total as f64))
+                .then(b.score.cmp(&a.score))
+                .then((a.user_from, a.user_to).cmp(&(b.user_from, b.user_to)))
+        });
+        givers.truncate(limit.max(0) as usize);
+
+        Ok(givers)
+    }
+
+    async fn new_account_bursts(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        account_age: i32,
+        min_upvotes: i64,
+        limit: i64,
+    ) -> Result<Vec<NewAccountBurst>, KowalskiError> {
+        let state = self.state();
+
+        let mut hours: HashMap<(UserId, DateTime<Utc>), i64> = HashMap::new();
+        for reaction in state.score_reactions.iter().filter(|reaction| {
+            reaction.guild == guild_id && reaction.native && reaction.time >= start
+        }) {
+            if state
+                .weight(reaction)
+                .filter(|&weight| weight > 0)
+                .is_none()
+            {
+                continue;
+            }
+
+            // Only count the upvotes given while the account was new
+            let created =
+                DateTime::from_timestamp(reaction.user_from.created_at().unix_timestamp(), 0)
+                    .unwrap_or_default();
+            if reaction.time >= created + Duration::days(account_age as i64) {
+                continue;
+            }
+
+            let hour = reaction.time.duration_trunc(Duration::hours(1)).unwrap();
+            *hours.entry((reaction.user_from, hour)).or_default() += 1;
+        }
+
+        let mut bursts: Vec<_> = hours
+            .into_iter()
+            .filter(|&(_, upvotes)| upvotes >= min_upvotes)
+            .map(|((user, hour), upvotes)| NewAccountBurst {
+                user,
+                hour,
+                upvotes,
+            })
+            .collect();
+        bursts.sort_by_key(|burst| (-burst.upvotes, Reverse(burst.hour), burst.user));
+        bursts.truncate(limit.max(0) as usize);
+
+        Ok(bursts)
+    }
+
+    async fn gift_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+        amount: i64,
+        limits: &[(DateTime<Utc>, i64)],
+    ) -> Result<u64, KowalskiError> {
+        let mut state = self.state();
+        state.users.insert((guild_id, user_to));
+
+        // Get the score which can still be gifted
+        let limit = limits
+            .iter()
+            .map(|&(since, cap)| {
+                let gifted: i64 = state
+                    .score_gifts
+                    .iter()
+                    .filter(|(guild, gift, _)| {
+                        *guild == guild_id
+                            && gift.user_from == user_from
+                            && gift.time >= since
+                            && !gift.undone
+                    })
+                    .map(|(_, gift, _)| gift.amount)
+                    .sum();
+
+                cap - gifted
+            })
+            .min()
+            .unwrap_or(i64::MAX);
+
+        // Get the upvotes to move, the ones which were not given natively and small ones first
+        let mut upvotes: Vec<_> = state
+            .score_reactions
+            .iter()
+            .enumerate()
+            .filter(|(_, reaction)| reaction.guild == guild_id && reaction.user_to == user_from)
+            .filter_map(|(index, reaction)| {
+                state
+                    .weight(reaction)
+                    .filter(|&weight| weight > 0)
+                    .map(|weight| (reaction.native, weight, index))
+            })
+            .collect();
+        upvotes.sort();
+
+        // Move upvotes as long as they exceed neither the score nor the limit
+        let limit = limit.min(amount);
+        let mut moved = 0;
+        let mut gifted = Vec::new();
+        for (_, weight, index) in upvotes {
+            if moved + weight > limit {
+                break;
+            }
+            moved += weight;
+
+            let reaction = &mut state.score_reactions[index];
+            gifted.push(reaction.clone());
+            reaction.user_to = user_to;
+            reaction.native = false;
+        }
+
+        // Record the gift, so it can be undone
+        if !gifted.is_empty() {
+            let gift = ScoreGift {
+                id: state.score_gifts.len() as i32 + 1,
+                user_from,
+                user_to,
+                amount: moved,
+                time: Utc::now(),
+                undone: false,
+            };
+            state.score_gifts.push((guild_id, gift, gifted));
+        }
+
+        Ok(moved as u64)
+    }
+
+    async fn gifted_score(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, KowalskiError> {
+        Ok(self
+            .state()
+            .score_gifts
+            .iter()
+            .filter(|(guild, gift, _)| {
+                *guild == guild_id && gift.user_from == user_id && gift.time >= since
+            })
+            .filter(|(_, gift, _)| !gift.undone)
+            .map(|(_, gift, _)| gift.amount)
+            .sum())
+    }
+
+    async fn score_gifts(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+        limit: i64,
+    ) -> Result<Vec<ScoreGift>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_gifts
+            .iter()
+            .rev()
+            .filter(|(guild, gift, _)| {
+                *guild == guild_id
+                    && user_id.is_none_or(|user| gift.user_from == user || gift.user_to == user)
+            })
+            .take(limit as usize)
+            .map(|(_, gift, _)| gift.clone())
+            .collect())
+    }
+
+    async fn undo_gift(
+        &self,
+        guild_id: GuildId,
+        gift: i32,
+    ) -> Result<Option<(ScoreGift, u64)>, KowalskiError> {
+        let mut state = self.state();
+        let state = &mut *state;
+
+        let (gift, gifted) =
+            match state.score_gifts.iter_mut().find(|(guild, stored, _)| {
+                *guild == guild_id && stored.id == gift && !stored.undone
+            }) {
+                Some((_, gift, gifted)) => {
+                    gift.undone = true;
+                    (gift.clone(), gifted.clone())
+                }
+                None => return Ok(None),
+            };
+
+        // Reactions which were given again to the original user in the meantime can't be moved back
+        let mut restored = 0;
+        for original in gifted {
+            let is_gifted = |reaction: &ScoreReaction| {
+                reaction.guild == guild_id
+                    && reaction.user_from == original.user_from
+                    && reaction.channel == original.channel
+                    && reaction.message == original.message
+                    && reaction.emoji == original.emoji
+            };
+
+            if state
+                .score_reactions
+                .iter()
+                .any(|reaction| is_gifted(reaction) && reaction.user_to == gift.user_from)
+            {
+                continue;
+            }
+
+            let index = state
+                .score_reactions
+                .iter()
+                .position(|reaction| is_gifted(reaction) && reaction.user_to == gift.user_to);
+
+            if let Some(index) = index {
+                restored += state.weight(&original).unwrap_or_default();
+
+                let reaction = &mut state.score_reactions[index];
+                reaction.user_to = gift.user_from;
+                reaction.native = original.native;
+            }
+        }
+
+        Ok(Some((gift, restored as u64)))
+    }
+
+    async fn gift_caps(&self, guild_id: GuildId) -> Result<GiftCaps, KowalskiError> {
+        Ok(self
+            .state()
+            .score_gift_caps
+            .get(&guild_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn set_gift_caps(&self, guild_id: GuildId, caps: &GiftCaps) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        // Gifts without caps do not need to be stored
+        if *caps == GiftCaps::default() {
+            state.score_gift_caps.remove(&guild_id);
+        } else {
+            state.guilds.insert(guild_id);
+            state.score_gift_caps.insert(guild_id, caps.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn transfer_score(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+    ) -> Result<u64, KowalskiError> {
+        let mut state = self.state();
+        state.users.insert((guild_id, user_to));
+
+        let mut moved = 0;
+
+        for reaction in state.score_reactions.iter_mut() {
+            if reaction.guild == guild_id && reaction.user_to == user_from {
+                reaction.user_to = user_to;
+                reaction.native = false;
+                moved += 1;
+            }
+        }
+
+        Ok(moved)
+    }
+
+    async fn end_season(&self, guild_id: GuildId) -> Result<Season, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+
+        // Continue where the previous season ended
+        let previous = state
+            .score_seasons
+            .iter()
+            .filter(|(guild, _)| *guild == guild_id)
+            .map(|(_, season)| season)
+            .max_by_key(|season| season.number);
+        let season = Season {
+            number: previous.map_or(1, |season| season.number + 1),
+            started: previous.map(|season| season.ended),
+            ended: Utc::now(),
+        };
+
+        // Sum up the up- and downvotes of every user
+        let mut votes: HashMap<UserId, (i64, i64)> = HashMap::new();
+        for (_, user, _, weight) in state.points().filter(|&(guild, ..)| guild == guild_id) {
+            let (upvotes, downvotes) = votes.entry(user).or_default();
+
+            if weight > 0 {
+                *upvotes += weight;
+            } else {
+                *downvotes -= weight;
+            }
+        }
+
+        // Rank the users, users with the same score share their rank
+        let mut ranks: Vec<_> = votes
+            .into_iter()
+            .map(|(user, (upvotes, downvotes))| SeasonRank {
+                user,
+                rank: 0,
+                score: upvotes - downvotes,
+                upvotes,
+                downvotes,
+            })
+            .collect();
+        ranks.sort_by_key(|rank| (-rank.score, rank.user));
+        for index in 0..ranks.len() {
+            ranks[index].rank = if index > 0 && ranks[index].score == ranks[index - 1].score {
+                ranks[index - 1].rank
+            } else {
+                index as i64 + 1
+            };
+        }
+
+        state.score_season_starts.insert(guild_id, season.ended);
+        state.score_seasons.push((guild_id, season.clone()));
+        state.score_season_ranks.extend(
+            ranks
+                .into_iter()
+                .map(|rank| (guild_id, season.number, rank)),
+        );
+        Ok(season)
+    }
+
+    async fn split_score(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        users: &[UserId],
+    ) -> Result<u64, KowalskiError> {
+        if users.is_empty() {
+            return Ok(0);
+        }
+
+        let mut state = self.state();
+        for &user in users {
+            state.users.insert((guild_id, user));
+        }
+
+        // Deal the reactions out one by one, the heaviest first
+        let mut reactions: Vec<_> = state
+            .score_reactions
+            .iter()
+            .enumerate()
+            .filter(|(_, reaction)| reaction.guild == guild_id && reaction.user_to == user_from)
+            .filter_map(|(index, reaction)| {
+                state
+                    .weight(reaction)
+                    .map(|weight| (-weight, reaction.channel, reaction.message, index))
+            })
+            .collect();
+        reactions.sort();
+
+        for (number, &(.., index)) in reactions.iter().enumerate() {
+            let reaction = &mut state.score_reactions[index];
+            reaction.user_to = users[number % users.len()];
+            reaction.native = false;
+        }
+
+        Ok(reactions.len() as u64)
+    }
+
+    async fn return_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<i64, KowalskiError> {
+        let mut state = self.state();
+
+        // Credit the givers still in the guild
+        let returns: Vec<_> = state
+            .score_reactions
+            .iter()
+            .filter(|reaction| {
+                reaction.guild == guild_id
+                    && reaction.user_to == user_id
+                    && state.users.contains(&(guild_id, reaction.user_from))
+            })
+            .filter_map(|reaction| {
+                state
+                    .weight(reaction)
+                    .filter(|&weight| weight > 0)
+                    .map(|weight| (guild_id, reaction.user_from, reaction.time, weight))
+            })
+            .collect();
+
+        let returned = returns.iter().map(|&(.., weight)| weight).sum();
+        state.score_returns.extend(returns);
+
+        Ok(returned)
+    }
+
+    async fn drop_mode(&self, guild_id: GuildId) -> Result<DropMode, KowalskiError> {
+        Ok(self
+            .state()
+            .score_drop_modes
+            .get(&guild_id)
+            .copied()
+            .unwrap_or_default())
+    }
+
+    async fn set_drop_mode(&self, guild_id: GuildId, mode: DropMode) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        // The default mode does not need to be stored
+        if mode == DropMode::default() {
+            state.score_drop_modes.remove(&guild_id);
+        } else {
+            state.guilds.insert(guild_id);
+            state.score_drop_modes.insert(guild_id, mode);
+        }
+
+        Ok(())
+    }
+
+    async fn drop_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_drops
+            .iter()
+            .filter(|&&(guild, _)| guild == guild_id)
+            .map(|&(_, channel)| channel)
+            .collect())
+    }
+
+    async fn random_drop_channel(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<ChannelId>, KowalskiError> {
+        let channels = self.drop_channels(guild_id).await?;
+
+        Ok(channels.choose(&mut rand::thread_rng()).copied())
+    }
+
+    async fn add_drop_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.channels.insert((guild_id, channel_id));
+        state.score_drops.insert((guild_id, channel_id));
+
+        Ok(())
+    }
+
+    async fn remove_drop_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<bool, KowalskiError> {
+        Ok(self.state().score_drops.remove(&(guild_id, channel_id)))
+    }
+
+    async fn add_pending_drop(&self, drop: &PendingDrop) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(drop.guild);
+        state.users.insert((drop.guild, drop.user));
+
+        state
+            .score_pending_drops
+            .insert((drop.guild, drop.user), (drop.clone(), Vec::new()));
+
+        Ok(())
+    }
+
+    async fn pending_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<PendingDrop>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_pending_drops
+            .get(&(guild_id, user_id))
+            .map(|(drop, _)| drop.clone()))
+    }
+
+    async fn due_drops(&self) -> Result<Vec<PendingDrop>, KowalskiError> {
+        let now = Utc::now();
+
+        Ok(self
+            .state()
+            .score_pending_drops
+            .values()
+            .filter(|(drop, _)| drop.ends <= now)
+            .map(|(drop, _)| drop.clone())
+            .collect())
+    }
+
+    async fn join_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        participant: UserId,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+
+        match state.score_pending_drops.get_mut(&(guild_id, user_id)) {
+            Some((_, participants)) if !participants.contains(&participant) => {
+                participants.push(participant);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn take_pending_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<(PendingDrop, Vec<UserId>)>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_pending_drops
+            .remove(&(guild_id, user_id)))
+    }
+
+    async fn season_start(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<DateTime<Utc>>, KowalskiError> {
+        Ok(self.state().score_season_starts.get(&guild_id).copied())
+    }
+
+    async fn seasons(&self, guild_id: GuildId) -> Result<Vec<Season>, KowalskiError> {
+        let mut seasons: Vec<_> = self
+            .state()
+            .score_seasons
+            .iter()
+            .filter(|(guild, _)| *guild == guild_id)
+            .map(|(_, season)| season.clone())
+            .collect();
+        seasons.sort_by_key(|season| -season.number);
+
+        Ok(seasons)
+    }
+
+    async fn season_ranks(
+        &self,
+        guild_id: GuildId,
+        season: i32,
+    ) -> Result<Vec<SeasonRank>, KowalskiError> {
+        let mut ranks: Vec<_> = self
+            .state()
+            .score_season_ranks
+            .iter()
+            .filter(|(guild, number, _)| *guild == guild_id && *number == season)
+            .map(|(.., rank)| rank.clone())
+            .collect();
+        ranks.sort_by_key(|rank| (rank.rank, rank.user));
+
+        Ok(ranks)
+    }
+
+    async fn snapshot_scores(&self) -> Result<u64, KowalskiError> {
+        let now = Utc::now();
+        let mut state = self.state();
+
+        let scores = state
+            .points()
+            .map(|(guild, user, time, weight)| {
+                let half_life = state
+                    .score_decay
+                    .get(&guild)
+                    .map_or(f64::INFINITY, |decay| decay.half_life as f64);
+                let days = (now - time).num_seconds() as f64 / 86400.0;
+
+                ((guild, user), weight as f64 * 0.5f64.powf(days / half_life))
+            })
+            .into_grouping_map()
+            .sum();
+
+        let snapshots = scores.len() as u64;
+        for ((guild, user), score) in scores {
+            state
+                .score_snapshots
+                .insert((guild, user, now.date_naive()), score.round() as i64);
+        }
+
+        Ok(snapshots)
+    }
+
+    async fn score_snapshots(
+        &self,
+        guild_id: GuildId,
+        users: &[UserId],
+        since: NaiveDate,
+    ) -> Result<Vec<(UserId, NaiveDate, i64)>, KowalskiError> {
+        let mut snapshots: Vec<_> = self
+            .state()
+            .score_snapshots
+            .iter()
+            .filter(|((guild, user, day), _)| {
+                *guild == guild_id && users.contains(user) && *day >= since
+            })
+            .map(|(&(_, user, day), &score)| (user, day, score))
+            .collect();
+        snapshots.sort_by_key(|&(user, day, _)| (day, user));
+
+        Ok(snapshots)
+    }
+
+    async fn level_up_roles(&self, guild_id: GuildId) -> Result<Vec<(RoleId, i64)>, KowalskiError> {
+        let mut roles: Vec<_> = self
+            .state()
+            .score_roles
+            .iter()
+            .filter(|(guild, ..)| *guild == guild_id)
+            .map(|&(_, role, score)| (role, score))
+            .collect();
+        roles.sort_by_key(|&(_, score)| score);
+
+        Ok(roles)
+    }
+
+    async fn level_up_stacking(&self, guild_id: GuildId) -> Result<bool, KowalskiError> {
+        Ok(self.state().score_role_stacking.contains(&guild_id))
+    }
+
+    async fn set_level_up_stacking(
+        &self,
+        guild_id: GuildId,
+        stacking: bool,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        if stacking {
+            state.guilds.insert(guild_id);
+            state.score_role_stacking.insert(guild_id);
+        } else {
+            state.score_role_stacking.remove(&guild_id);
+        }
+
+        Ok(())
+    }
+
+    async fn add_level_up_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        score: i64,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.roles.insert((guild_id, role_id));
+
+        if !state.score_roles.contains(&(guild_id, role_id, score)) {
+            state.score_roles.push((guild_id, role_id, score));
+        }
+
+        Ok(())
+    }
+
+    async fn remove_level_up_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        score: i64,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+        let count = state.score_roles.len();
+        state
+            .score_roles
+            .retain(|&entry| entry != (guild_id, role_id, score));
+
+        Ok(state.score_roles.len() != count)
+    }
+
+    async fn level_up_announcement(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<LevelUpAnnouncement>, KowalskiError> {
+        Ok(self.state().score_announcements.get(&guild_id).cloned())
+    }
+
+    async fn set_level_up_announcement(
+        &self,
+        guild_id: GuildId,
+        announcement: Option<&LevelUpAnnouncement>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        match announcement {
+            Some(announcement) => {
+                state.guilds.insert(guild_id);
+                if let Some(channel_id) = announcement.channel {
+                    state.channels.insert((guild_id, channel_id));
+                }
+                state
+                    .score_announcements
+                    .insert(guild_id, announcement.clone());
+            }
+            None => {
+                state.score_announcements.remove(&guild_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn score_rewards(&self, guild_id: GuildId) -> Result<Vec<ScoreReward>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_rewards
+            .iter()
+            .filter(|(guild, _)| *guild == guild_id)
+            .map(|(_, reward)| reward.clone())
+            .sorted_by_key(|reward| reward.score)
+            .collect())
+    }
+
+    async fn add_score_reward(
+        &self,
+        guild_id: GuildId,
+        reward: &ScoreReward,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        if let Some(role_id) = reward.role {
+            state.roles.insert((guild_id, role_id));
+        }
+        if let Some(channel_id) = reward.channel {
+            state.channels.insert((guild_id, channel_id));
+        }
+
+        state
+            .score_rewards
+            .retain(|(guild, existing)| *guild != guild_id || existing.score != reward.score);
+        state.score_rewards.push((guild_id, reward.clone()));
+
+        Ok(())
+    }
+
+    async fn remove_score_reward(
+        &self,
+        guild_id: GuildId,
+        score: i64,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+        let count = state.score_rewards.len();
+        state
+            .score_rewards
+            .retain(|(guild, reward)| *guild != guild_id || reward.score != score);
+
+        // Claims of the reward are removed along with it
+        state
+            .score_reward_claims
+            .retain(|&(guild, claimed, _)| guild != guild_id || claimed != score);
+
+        Ok(state.score_rewards.len() != count)
+    }
+
+    async fn claim_score_reward(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        score: i64,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+        state.users.insert((guild_id, user_id));
+
+        Ok(state.score_reward_claims.insert((guild_id, score, user_id)))
+    }
+
+    async fn unclaim_score_reward(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        score: i64,
+    ) -> Result<(), KowalskiError> {
+        self.state()
+            .score_reward_claims
+            .remove(&(guild_id, score, user_id));
+
+        Ok(())
+    }
+
+    async fn role_cooldown(
+        &self,
+        guild_id: GuildId,
+        role_ids: &[RoleId],
+    ) -> Result<Option<i64>, KowalskiError> {
+        let state = self.state();
+
+        Ok(role_ids
+            .iter()
+            .filter_map(|&role_id| state.score_cooldowns.get(&(guild_id, role_id)))
+            .min()
+            .copied())
+    }
+
+    async fn role_cooldowns(&self, guild_id: GuildId) -> Result<Vec<(RoleId, i64)>, KowalskiError> {
+        let mut cooldowns: Vec<_> = self
+            .state()
+            .score_cooldowns
+            .iter()
+            .filter(|((guild, _), _)| *guild == guild_id)
+            .map(|(&(_, role), &cooldown)| (role, cooldown))
+            .collect();
+        cooldowns.sort_by_key(|&(_, cooldown)| cooldown);
+
+        Ok(cooldowns)
+    }
+
+    async fn set_role_cooldown(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        cooldown: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        match cooldown {
+            Some(cooldown) => {
+                state.guilds.insert(guild_id);
+                state.roles.insert((guild_id, role_id));
+                state.score_cooldowns.insert((guild_id, role_id), cooldown);
+            }
+            None => {
+                state.score_cooldowns.remove(&(guild_id, role_id));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn cooldown_policy(&self, guild_id: GuildId) -> Result<CooldownPolicy, KowalskiError> {
+        Ok(self
+            .state()
+            .score_cooldown_policies
+            .get(&guild_id)
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn set_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        policy: &CooldownPolicy,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        // The default policy does not need to be stored
+        if *policy == CooldownPolicy::default() {
+            state.score_cooldown_policies.remove(&guild_id);
+        } else {
+            state.guilds.insert(guild_id);
+            state
+                .score_cooldown_policies
+                .insert(guild_id, policy.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn given_votes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, KowalskiError> {
+        let state = self.state();
+
+        Ok(state
+            .score_reactions
+            .iter()
+            .filter(|reaction| {
+                reaction.guild == guild_id
+                    && reaction.user_from == user_id
+                    && reaction.native
+                    && reaction.time >= since
+            })
+            .filter(|reaction| state.weight(reaction).is_some())
+            .count() as i64)
+    }
+
+    async fn start_cooldown(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        target: Option<UserId>,
+        downvote: bool,
+        ends: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, KowalskiError> {
+        let mut state = self.state();
+        state.users.insert((guild_id, user_id));
+
+        let key = (guild_id, user_id, target, downvote);
+        match state.score_user_cooldowns.get(&key) {
+            Some(&active) if active > Utc::now() => Ok(Some(active)),
+            _ => {
+                state.score_user_cooldowns.insert(key, ends);
+
+                Ok(None)
+            }
+        }
+    }
+
+    async fn remove_expired_cooldowns(&self) -> Result<u64, KowalskiError> {
+        let now = Utc::now();
+        let mut state = self.state();
+
+        let before = state.score_user_cooldowns.len();
+        state.score_user_cooldowns.retain(|_, ends| *ends > now);
+
+        Ok((before - state.score_user_cooldowns.len()) as u64)
+    }
+
+    async fn moderation_rules(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ModerationRule>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_moderation_rules
+            .iter()
+            .filter(|(guild, _)| *guild == guild_id)
+            .map(|(_, rule)| rule.clone())
+            .collect())
+    }
+
+    async fn add_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: &ModerationRule,
+    ) -> Result<i32, KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        for channel_id in [rule.channel, rule.target].into_iter().flatten() {
+            state.channels.insert((guild_id, channel_id));
+        }
+        if let Some(role_id) = rule.role {
+            state.roles.insert((guild_id, role_id));
+        }
+
+        // Rule ids start at 1 and are never reused, just like a SERIAL column
+        state.moderation_rule_count += 1;
+        let id = state.moderation_rule_count;
+        state
+            .score_moderation_rules
+            .push((guild_id, ModerationRule { id, ..rule.clone() }));
+
+        Ok(id)
+    }
+
+    async fn remove_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: i32,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+
+        let count = state.score_moderation_rules.len();
+        state
+            .score_moderation_rules
+            .retain(|(guild, existing)| *guild != guild_id || existing.id != rule);
+        state
+            .score_moderation_triggers
+            .retain(|&(triggered, ..)| triggered != rule);
+
+        Ok(state.score_moderation_rules.len() < count)
+    }
+
+    async fn applied_moderation_rules(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<i32>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_moderation_triggers
+            .iter()
+            .filter(|&&(_, channel, message)| channel == channel_id && message == message_id)
+            .map(|&(rule, ..)| rule)
+            .collect())
+    }
+
+    async fn trigger_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: i32,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+        state.messages.insert((guild_id, channel_id, message_id));
+
+        Ok(state
+            .score_moderation_triggers
+            .insert((rule, channel_id, message_id)))
+    }
+
+    async fn score_decay(&self, guild_id: GuildId) -> Result<Option<ScoreDecay>, KowalskiError> {
+        Ok(self.state().score_decay.get(&guild_id).cloned())
+    }
+
+    async fn set_score_decay(
+        &self,
+        guild_id: GuildId,
+        decay: Option<&ScoreDecay>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        match decay {
+            Some(decay) => state.score_decay.insert(guild_id, decay.clone()),
+            None => state.score_decay.remove(&guild_id),
+        };
+
+        Ok(())
+    }
+
+    async fn score_cap(&self, guild_id: GuildId) -> Result<Option<i64>, KowalskiError> {
+        Ok(self.state().score_caps.get(&guild_id).copied())
+    }
+
+    async fn set_score_cap(
+        &self,
+        guild_id: GuildId,
+        cap: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        match cap {
+            Some(cap) => state.score_caps.insert(guild_id, cap),
+            None => state.score_caps.remove(&guild_id),
+        };
+
+        Ok(())
+    }
+
+    async fn channel_scoring(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<ChannelScoring, KowalskiError> {
+        Ok(self
+            .state()
+            .score_channels
+            .get(&(guild_id, channel_id))
+            .cloned()
+            .unwrap_or_else(|| ChannelScoring::new(channel_id)))
+    }
+
+    async fn channel_scorings(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ChannelScoring>, KowalskiError> {
+        Ok(self
+            .state()
+            .score_channels
+            .iter()
+            .filter(|((guild, _), scoring)| {
+                *guild == guild_id && (scoring.multiplier != 1 || scoring.excluded)
+            })
+            .map(|(_, scoring)| scoring.clone())
+            .sorted_by_key(|scoring| scoring.channel)
+            .collect())
+    }
+
+    async fn set_channel_scoring(
+        &self,
+        guild_id: GuildId,
+        scoring: &ChannelScoring,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.channels.insert((guild_id, scoring.channel));
+        state
+            .score_channels
+            .insert((guild_id, scoring.channel), scoring.clone());
+
+        Ok(())
+    }
+
+    async fn reaction_roles(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+    ) -> Result<Vec<ReactionRole>, KowalskiError> {
+        Ok(self
+            .state()
+            .reaction_roles
+            .iter()
+            .filter(|(guild, emoji_id, role)| {
+                *guild == guild_id
+                    && *emoji_id == emoji
+                    && role.channel == channel_id
+                    && role.message == message_id
+            })
+            .map(|(.., role)| role.clone())
+            .collect())
+    }
+
+    async fn message_reaction_roles(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<ReactionRole>, KowalskiError> {
+        Ok(self
+            .state()
+            .reaction_roles
+            .iter()
+            .filter(|(guild, _, role)| {
+                *guild == guild_id && role.channel == channel_id && role.message == message_id
+            })
+            .map(|(.., role)| role.clone())
+            .collect())
+    }
+
+    async fn guild_reaction_roles(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ReactionRole>, KowalskiError> {
+        let mut roles: Vec<_> = self
+            .state()
+            .reaction_roles
+            .iter()
+            .filter(|(guild, ..)| *guild == guild_id)
+            .map(|(.., role)| role.clone())
+            .collect();
+        roles.sort_by_key(|role| (role.channel, role.message));
+
+        Ok(roles)
+    }
+
+    async fn add_reaction_role(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+        slots: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.roles.insert((guild_id, role_id));
+        state.channels.insert((guild_id, channel_id));
+        state.messages.insert((guild_id, channel_id, message_id));
+
+        // Keep the group and duration if the reaction-role exists already
+        let (group, duration) = state
+            .reaction_roles
+            .iter()
+            .find(|(guild, emoji_id, role)| {
+                *guild == guild_id
+                    && *emoji_id == emoji
+                    && role.channel == channel_id
+                    && role.message == message_id
+                    && role.role == role_id
+            })
+            .map_or((None, None), |(.., role)| {
+                (role.group.clone(), role.duration)
+            });
+
+        let reaction_role = ReactionRole {
+            channel: channel_id,
+            message: message_id,
+            emoji_id: emoji,
+            emoji: state.emojis[emoji as usize - 1].clone(),
+            role: role_id,
+            slots: slots.map(|slots| slots as i32),
+            group,
+            duration,
+        };
+
+        // Update the slots if the reaction-role exists already
+        state.reaction_roles.retain(|(guild, emoji_id, role)| {
+            !(*guild == guild_id
+                && *emoji_id == emoji
+                && role.channel == channel_id
+                && role.message == message_id
+                && role.role == role_id)
+        });
+        state.reaction_roles.push((guild_id, emoji, reaction_role));
+
+        Ok(())
+    }
+
+    async fn reaction_role_conditions(
+        &self,
+        guild_id: GuildId,
+        reaction_role: &ReactionRole,
+    ) -> Result<Vec<(RoleId, bool)>, KowalskiError> {
+        Ok(self
+            .state()
+            .reaction_role_conditions
+            .iter()
+            .filter(|((guild, channel, message, emoji, role, _), _)| {
+                *guild == guild_id
+                    && *channel == reaction_role.channel
+                    && *message == reaction_role.message
+                    && *emoji == reaction_role.emoji_id
+                    && *role == reaction_role.role
+            })
+            .map(|((.., condition_role), &required)| (*condition_role, required))
+            .collect())
+    }
+
+    async fn set_reaction_role_condition(
+        &self,
+        guild_id: GuildId,
+        reaction_role: &ReactionRole,
+        role_id: RoleId,
+        required: Option<bool>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.roles.insert((guild_id, role_id));
+
+        let key = (
+            guild_id,
+            reaction_role.channel,
+            reaction_role.message,
+            reaction_role.emoji_id,
+            reaction_role.role,
+            role_id,
+        );
+
+        match required {
+            Some(required) => state.reaction_role_conditions.insert(key, required),
+            None => state.reaction_role_conditions.remove(&key),
+        };
+
+        Ok(())
+    }
+
+    async fn remove_reaction_role(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        state.reaction_roles.retain(|(guild, emoji_id, role)| {
+            !(*guild == guild_id
+                && *emoji_id == emoji
+                && role.channel == channel_id
+                && role.message == message_id
+                && role.role == role_id)
+        });
+        state
+            .reaction_role_conditions
+            .retain(|(guild, channel, message, emoji_id, role, _), _| {
+                !(*guild == guild_id
+                    && *emoji_id == emoji
+                    && *channel == channel_id
+                    && *message == message_id
+                    && *role == role_id)
+            });
+        state.reaction_role_expirations.retain(|expiration| {
+            !(expiration.guild == guild_id
+                && expiration.emoji == emoji
+                && expiration.channel == channel_id
+                && expiration.message == message_id
+                && expiration.role == role_id)
+        });
+
+        Ok(())
+    }
+
+    async fn set_reaction_role_duration(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+        duration: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        for (guild, emoji_id, role) in self.state().reaction_roles.iter_mut() {
+            if *guild == guild_id
+                && *emoji_id == emoji
+                && role.channel == channel_id
+                && role.message == message_id
+                && role.role == role_id
+            {
+                role.duration = duration;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn set_reaction_role_group_of(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        role_id: RoleId,
+        group: Option<&str>,
+    ) -> Result<(), KowalskiError> {
+        for (guild, _, role) in self.state().reaction_roles.iter_mut() {
+            if *guild == guild_id
+                && role.channel == channel_id
+                && role.message == message_id
+                && role.role == role_id
+            {
+                role.group = group.map(String::from);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reaction_role_group(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        name: &str,
+    ) -> Result<ReactionRoleGroup, KowalskiError> {
+        Ok(self
+            .state()
+            .reaction_role_groups
+            .get(&(guild_id, channel_id, message_id, name.to_string()))
+            .cloned()
+            .unwrap_or_else(|| ReactionRoleGroup::new(channel_id, message_id, name)))
+    }
+
+    async fn guild_reaction_role_groups(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ReactionRoleGroup>, KowalskiError> {
+        let mut groups: Vec<_> = self
+            .state()
+            .reaction_role_groups
+            .iter()
+            .filter(|((guild, ..), _)| *guild == guild_id)
+            .map(|(_, group)| group.clone())
+            .collect();
+        groups
+            .sort_by(|a, b| (a.channel, a.message, &a.name).cmp(&(b.channel, b.message, &b.name)));
+
+        Ok(groups)
+    }
+
+    async fn set_reaction_role_group(
+        &self,
+        guild_id: GuildId,
+        group: &ReactionRoleGroup,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.channels.insert((guild_id, group.channel));
+        state
+            .messages
+            .insert((guild_id, group.channel, group.message));
+
+        state.reaction_role_groups.insert(
+            (guild_id, group.channel, group.message, group.name.clone()),
+            group.clone(),
+        );
+
+        Ok(())
+    }
+
+    async fn reserve_reaction_role_slot(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+
+        let reaction_role = state
+            .reaction_roles
+            .iter_mut()
+            .find(|(guild, emoji_id, role)| {
+                *guild == guild_id
+                    && *emoji_id == emoji
+                    && role.channel == channel_id
+                    && role.message == message_id
+                    && role.role == role_id
+            })
+            .map(|(.., role)| role);
+
+        match reaction_role {
+            Some(ReactionRole { slots: None, .. }) => Ok(true),
+            Some(ReactionRole {
+                slots: Some(slots), ..
+            }) if *slots > 0 => {
+                *slots -= 1;
+
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn release_reaction_role_slot(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+
+        for (guild, emoji_id, role) in state.reaction_roles.iter_mut() {
+            if *guild == guild_id
+                && *emoji_id == emoji
+                && role.channel == channel_id
+                && role.message == message_id
+                && role.role == role_id
+            {
+                if let Some(slots) = role.slots.as_mut() {
+                    *slots += 1;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn add_reaction_role_expiration(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reaction_role: &ReactionRole,
+        expires: DateTime<Utc>,
+    ) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.users.insert((guild_id, user_id));
+
+        let expiration = ReactionRoleExpiration {
+            guild: guild_id,
+            user: user_id,
+            channel: reaction_role.channel,
+            message: reaction_role.message,
+            emoji: reaction_role.emoji_id,
+            role: reaction_role.role,
+            expires,
+        };
+
+        // Renew the expiration if it exists already
+        state.reaction_role_expirations.retain(|other| {
+            !(other.guild == expiration.guild
+                && other.user == expiration.user
+                && other.channel == expiration.channel
+                && other.message == expiration.message
+                && other.emoji == expiration.emoji
+                && other.role == expiration.role)
+        });
+        state.reaction_role_expirations.push(expiration);
+
+        Ok(())
+    }
+
+    async fn remove_reaction_role_expiration(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reaction_role: &ReactionRole,
+    ) -> Result<(), KowalskiError> {
+        self.state().reaction_role_expirations.retain(|expiration| {
+            !(expiration.guild == guild_id
+                && expiration.user == user_id
+                && expiration.channel == reaction_role.channel
+                && expiration.message == reaction_role.message
+                && expiration.emoji == reaction_role.emoji_id
+                && expiration.role == reaction_role.role)
+        });
+
+        Ok(())
+    }
+
+    async fn due_reaction_role_expirations(
+        &self,
+    ) -> Result<Vec<ReactionRoleExpiration>, KowalskiError> {
+        let now = Utc::now();
+
+        Ok(self
+            .state()
+            .reaction_role_expirations
+            .iter()
+            .filter(|expiration| expiration.expires <= now)
+            .cloned()
+            .collect())
+    }
+
+    async fn complete_reaction_role_expiration(
+        &self,
+        expiration: &ReactionRoleExpiration,
+    ) -> Result<bool, KowalskiError> {
+        let mut state = self.state();
+
+        let count = state.reaction_role_expirations.len();
+        state
+            .reaction_role_expirations
+            .retain(|existing| existing != expiration);
+
+        Ok(state.reaction_role_expirations.len() < count)
+    }
+
+    async fn add_reminder(&self, reminder: &Reminder) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(reminder.guild);
+        state.channels.insert((reminder.guild, reminder.channel));
+        state
+            .messages
+            .insert((reminder.guild, reminder.channel, reminder.message));
+        state.users.insert((reminder.guild, reminder.user));
+        state.reminders.push(reminder.clone());
+
+        Ok(())
+    }
+
+    async fn take_due_reminders(&self) -> Result<Vec<Reminder>, KowalskiError> {
+        let now = Utc::now();
+        let mut state = self.state();
+        let (due, pending) = std::mem::take(&mut state.reminders)
+            .into_iter()
+            .partition(|reminder| reminder.time <= now);
+
+        state.reminders = pending;
+
+        Ok(due)
+    }
+
+    async fn reminders(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+    ) -> Result<Vec<Reminder>, KowalskiError> {
+        let mut reminders: Vec<_> = self
+            .state()
+            .reminders
+            .iter()
+            .filter(|reminder| reminder.guild == guild_id)
+            .filter(|reminder| user_id.is_none_or(|user_id| reminder.user == user_id))
+            .cloned()
+            .collect();
+        reminders.sort_by_key(|reminder| reminder.time);
+
+        Ok(reminders)
+    }
+
+    async fn publishing_id(&self, guild_id: GuildId) -> Result<Option<String>, KowalskiError> {
+        Ok(self.state().publishing.get(&guild_id).cloned())
+    }
+
+    async fn publishing_guild(&self, id: &str) -> Result<Option<GuildId>, KowalskiError> {
+        Ok(self
+            .state()
+            .publishing
+            .iter()
+            .find(|(_, publishing_id)| publishing_id.as_str() == id)
+            .map(|(&guild_id, _)| guild_id))
+    }
+
+    async fn enable_publishing(&self, guild_id: GuildId, id: &str) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.publishing.insert(guild_id, id.to_string());
+
+        Ok(())
+    }
+
+    async fn disable_publishing(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.state().publishing.remove(&guild_id);
+
+        Ok(())
+    }
+
+    async fn owned_guilds(&self) -> Result<Vec<GuildId>, KowalskiError> {
+        Ok(self.state().owned_guilds.iter().copied().collect())
+    }
+
+    async fn add_owned_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        let mut state = self.state();
+        state.guilds.insert(guild_id);
+        state.owned_guilds.insert(guild_id);
+
+        Ok(())
+    }
+
+    async fn remove_owned_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.state().owned_guilds.remove(&guild_id);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl MemoryStorage {
+    /// Adds a native vote of the given weight, using an emoji with that weight.
+    pub async fn vote(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        weight: i32,
+    ) {
+        let emoji = self
+            .get_emoji(guild_id, &ReactionType::Unicode(weight.to_string()))
+            .await
+            .unwrap();
+        self.set_score_emoji(guild_id, emoji, weight).await.unwrap();
+        self.add_score_reaction(&ScoreReaction {
+            guild: guild_id,
+            user_from,
+            user_to,
+            channel: channel_id,
+            message: message_id,
+            emoji,
+            native: true,
+            time: Utc::now(),
+        })
+        .await
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUILD: GuildId = GuildId(1);
+    const GIVER: UserId = UserId(2);
+    const RECEIVER: UserId = UserId(3);
+
+    /// Sets up a guild in which the giver received votes of the given weights.
+    async fn setup(weights: &[i32]) -> MemoryStorage {
+        let storage = MemoryStorage::new();
+
+        for (index, &weight) in weights.iter().enumerate() {
+            storage
+                .vote(
+                    GUILD,
+                    UserId(100 + index as u64),
+                    GIVER,
+                    ChannelId(4),
+                    MessageId(5),
+                    weight,
+                )
+                .await;
+        }
+
+        storage
+    }
+
+    async fn scores(storage: &MemoryStorage) -> (i64, i64) {
+        (
+            storage.user_score(GUILD, GIVER).await.unwrap(),
+            storage.user_score(GUILD, RECEIVER).await.unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn gifts_small_upvotes_first() {
+        let storage = setup(&[5, 2, 2, -1]).await;
+
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 4, &[])
+            .await
+            .unwrap();
+
+        assert_eq!(moved, 4);
+        assert_eq!(scores(&storage).await, (4, 4));
+    }
+
+    #[tokio::test]
+    async fn never_gifts_more_than_amount() {
+        let storage = setup(&[5, 2, 2, -1]).await;
+
+        // The second small upvote would exceed the amount
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 3, &[])
+            .await
+            .unwrap();
+        assert_eq!(moved, 2);
+        assert_eq!(scores(&storage).await, (6, 2));
+
+        // No upvote fits into the amount
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 1, &[])
+            .await
+            .unwrap();
+        assert_eq!(moved, 0);
+        assert_eq!(scores(&storage).await, (6, 2));
+    }
+
+    #[tokio::test]
+    async fn gifts_nothing_without_upvotes() {
+        let storage = setup(&[-1, -2]).await;
+
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 3, &[])
+            .await
+            .unwrap();
+
+        assert_eq!(moved, 0);
+        assert_eq!(scores(&storage).await, (-3, 0));
+    }
+
+    #[tokio::test]
+    async fn keeps_gifts_within_caps() {
+        let storage = setup(&[2, 2, 2, 2]).await;
+        let since = Utc::now() - Duration::days(1);
+
+        // The second upvote would exceed the cap
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 4, &[(since, 3)])
+            .await
+            .unwrap();
+        assert_eq!(moved, 2);
+
+        // Earlier gifts count towards the cap
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 2, &[(since, 3)])
+            .await
+            .unwrap();
+        assert_eq!(moved, 0);
+        assert_eq!(storage.gifted_score(GUILD, GIVER, since).await.unwrap(), 2);
+
+        // Older gifts don't count towards the cap
+        let moved = storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 2, &[(Utc::now(), 3)])
+            .await
+            .unwrap();
+        assert_eq!(moved, 2);
+        assert_eq!(scores(&storage).await, (4, 4));
+    }
+
+    #[tokio::test]
+    async fn returns_upvotes_without_self_votes() {
+        let storage = setup(&[2, 3, -1]).await;
+
+        let returned = storage.return_upvotes(GUILD, GIVER).await.unwrap();
+        assert_eq!(returned, 5);
+
+        // The givers keep their score once the user is gone
+        storage.remove_user(GUILD, GIVER).await.unwrap();
+        assert_eq!(storage.user_score(GUILD, UserId(100)).await.unwrap(), 2);
+        assert_eq!(storage.user_score(GUILD, UserId(101)).await.unwrap(), 3);
+        assert_eq!(storage.user_score(GUILD, UserId(102)).await.unwrap(), 0);
+
+        // No reaction was turned into a vote of the givers for themselves
+        assert!(storage
+            .message_score_reactions(GUILD, ChannelId(4), MessageId(5))
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn gifts_gifted_upvotes_first() {
+        let storage = setup(&[1, 3]).await;
+        storage
+            .gift_upvotes(GUILD, GIVER, RECEIVER, 1, &[])
+            .await
+            .unwrap();
+
+        // The receiver passes on the gifted upvote before the native ones
+        storage
+            .vote(GUILD, UserId(200), RECEIVER, ChannelId(4), MessageId(6), 1)
+            .await;
+        storage
+            .gift_upvotes(GUILD, RECEIVER, GIVER, 1, &[])
+            .await
+            .unwrap();
+
+        let reactions = storage
+            .message_score_reactions(GUILD, ChannelId(4), MessageId(5))
+            .await
+            .unwrap();
+        assert!(reactions.iter().all(|reaction| reaction.user_to == GIVER));
+    }
+}
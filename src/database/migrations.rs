@@ -0,0 +1,925 @@
+use tokio_postgres::Client;
+use tracing::info;
+
+use crate::{
+    error::KowalskiError,
+    strings::{ERR_DB_MIGRATIONS_ORDER, ERR_DB_VERSION_NEWER, INFO_DB_MIGRATED},
+};
+
+/// A versioned change of the database schema.
+pub struct Migration {
+    pub version: i32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// All migrations of the database schema, ordered by their version.
+///
+/// Note: Never edit a migration which was already released, always append a new one instead
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "Create initial tables",
+        sql: "
+            CREATE TABLE IF NOT EXISTS guilds (
+                guild           BIGINT PRIMARY KEY
+            );
+
+            CREATE TABLE IF NOT EXISTS users (
+                guild           BIGINT,
+                \"user\"        BIGINT,
+                PRIMARY KEY (guild, \"user\"),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS channels (
+                guild           BIGINT,
+                channel         BIGINT,
+                PRIMARY KEY (guild, channel),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS roles (
+                guild           BIGINT,
+                role            BIGINT,
+                PRIMARY KEY (guild, role),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS messages (
+                guild           BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                PRIMARY KEY (guild, channel, message),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS emojis (
+                id              SERIAL PRIMARY KEY,
+                unicode         TEXT,
+                guild           BIGINT,
+                guild_emoji     BIGINT,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT unicode_or_guild
+                    CHECK ((guild IS NULL) = (guild_emoji IS NULL)
+                    AND (unicode IS NULL) != (guild_emoji IS NULL))
+            );
+
+            CREATE TABLE IF NOT EXISTS modules (
+                guild           BIGINT PRIMARY KEY,
+                status          BIT(8) NOT NULL,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS publishing (
+                id              TEXT PRIMARY KEY,
+                guild           BIGINT UNIQUE,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_auto_delete (
+                guild           BIGINT PRIMARY KEY,
+                score           BIGINT NOT NULL,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_auto_pin (
+                guild           BIGINT PRIMARY KEY,
+                score           BIGINT NOT NULL,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_cooldowns (
+                guild           BIGINT,
+                role            BIGINT,
+                cooldown        BIGINT NOT NULL,
+                PRIMARY KEY (guild, role),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_roles
+                    FOREIGN KEY (guild, role)
+                    REFERENCES roles(guild, role)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_drops (
+                guild           BIGINT,
+                channel         BIGINT,
+                PRIMARY KEY (guild, channel),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_emojis (
+                guild           BIGINT,
+                emoji           INT,
+                upvote          BOOLEAN NOT NULL,
+                PRIMARY KEY (guild, emoji),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_emojis
+                    FOREIGN KEY (emoji)
+                    REFERENCES emojis(id)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_reactions (
+                guild           BIGINT,
+                user_from       BIGINT,
+                user_to         BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                emoji           INT,
+                PRIMARY KEY (guild, user_from, user_to, channel, message, emoji),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, user_to)
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_score_emojis
+                    FOREIGN KEY (guild, emoji)
+                    REFERENCES score_emojis(guild, emoji)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_roles (
+                guild           BIGINT,
+                role            BIGINT,
+                score           BIGINT,
+                PRIMARY KEY (guild, role, score),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_roles
+                    FOREIGN KEY (guild, role)
+                    REFERENCES roles(guild, role)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS reaction_roles (
+                guild           BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                emoji           INT,
+                role            BIGINT,
+                slots           INT,
+                PRIMARY KEY (guild, channel, message, emoji, role),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_messages
+                    FOREIGN KEY (guild, channel, message)
+                    REFERENCES messages(guild, channel, message)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_emojis
+                    FOREIGN KEY (emoji)
+                    REFERENCES emojis(id)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_roles
+                    FOREIGN KEY (guild, role)
+                    REFERENCES roles(guild, role)
+                    ON DELETE CASCADE,
+                CONSTRAINT unsigned_slots
+                    CHECK (slots >= 0)
+            );
+
+            CREATE TABLE IF NOT EXISTS reminders (
+                guild           BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                \"user\"        BIGINT,
+                time            TIMESTAMP WITH TIME ZONE,
+                content         TEXT NOT NULL,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_messages
+                    FOREIGN KEY (guild, channel, message)
+                    REFERENCES messages(guild, channel, message)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE,
+                PRIMARY KEY (guild, channel, \"user\", time)
+            );
+
+            CREATE TABLE IF NOT EXISTS owned_guilds (
+                guild           BIGINT PRIMARY KEY,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 2,
+        description: "Track whether score reactions were given natively",
+        sql: "
+            ALTER TABLE score_reactions
+            ADD COLUMN IF NOT EXISTS native BOOLEAN NOT NULL DEFAULT true;
+        ",
+    },
+    Migration {
+        version: 3,
+        description: "Add exclusive reaction-role groups",
+        sql: "
+            ALTER TABLE reaction_roles
+            ADD COLUMN IF NOT EXISTS role_group TEXT;
+
+            CREATE TABLE IF NOT EXISTS reaction_role_groups (
+                guild           BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                role_group      TEXT,
+                min_roles       INT NOT NULL DEFAULT 0,
+                max_roles       INT NOT NULL DEFAULT 1,
+                PRIMARY KEY (guild, channel, message, role_group),
+                CONSTRAINT fk_messages
+                    FOREIGN KEY (guild, channel, message)
+                    REFERENCES messages(guild, channel, message)
+                    ON DELETE CASCADE,
+                CONSTRAINT valid_limits
+                    CHECK (min_roles >= 0 AND max_roles >= 1 AND min_roles <= max_roles)
+            );
+        ",
+    },
+    Migration {
+        version: 4,
+        description: "Add timed reaction-roles",
+        sql: "
+            ALTER TABLE reaction_roles
+            ADD COLUMN IF NOT EXISTS duration BIGINT;
+
+            CREATE TABLE IF NOT EXISTS reaction_role_expirations (
+                guild           BIGINT,
+                \"user\"        BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                emoji           INT,
+                role            BIGINT,
+                expires         TIMESTAMP WITH TIME ZONE NOT NULL,
+                PRIMARY KEY (guild, \"user\", channel, message, emoji, role),
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_reaction_roles
+                    FOREIGN KEY (guild, channel, message, emoji, role)
+                    REFERENCES reaction_roles(guild, channel, message, emoji, role)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 5,
+        description: "Add role conditions of reaction-roles",
+        sql: "
+            CREATE TABLE IF NOT EXISTS reaction_role_conditions (
+                guild           BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                emoji           INT,
+                role            BIGINT,
+                condition_role  BIGINT,
+                required        BOOLEAN NOT NULL,
+                PRIMARY KEY (guild, channel, message, emoji, role, condition_role),
+                CONSTRAINT fk_reaction_roles
+                    FOREIGN KEY (guild, channel, message, emoji, role)
+                    REFERENCES reaction_roles(guild, channel, message, emoji, role)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_roles
+                    FOREIGN KEY (guild, condition_role)
+                    REFERENCES roles(guild, role)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 6,
+        description: "Replace the upvote flag of score emojis with weights",
+        sql: "
+            ALTER TABLE score_emojis ADD COLUMN IF NOT EXISTS weight INT;
+            UPDATE score_emojis SET weight = CASE WHEN upvote THEN 1 ELSE -1 END;
+            ALTER TABLE score_emojis ALTER COLUMN weight SET NOT NULL;
+            ALTER TABLE score_emojis ADD CONSTRAINT check_weight CHECK (weight <> 0);
+            ALTER TABLE score_emojis DROP COLUMN upvote;
+        ",
+    },
+    Migration {
+        version: 7,
+        description: "Add timestamps to score reactions and score decay",
+        sql: "
+            ALTER TABLE score_reactions
+            ADD COLUMN IF NOT EXISTS time TIMESTAMP WITH TIME ZONE;
+            UPDATE score_reactions
+            SET time = TO_TIMESTAMP(((message >> 22) + 1420070400000) / 1000.0)
+            WHERE time IS NULL;
+            ALTER TABLE score_reactions
+            ALTER COLUMN time SET DEFAULT NOW(),
+            ALTER COLUMN time SET NOT NULL;
+            CREATE INDEX IF NOT EXISTS score_reactions_time ON score_reactions (guild, time);
+
+            CREATE OR REPLACE FUNCTION decayed_weight(
+                reaction_weight INT,
+                reaction_time   TIMESTAMP WITH TIME ZONE,
+                half_life       FLOAT8
+            ) RETURNS FLOAT8 AS $$
+                SELECT reaction_weight
+                    * POWER(0.5, EXTRACT(EPOCH FROM NOW() - reaction_time)::FLOAT8 / 86400 / half_life)
+            $$ LANGUAGE SQL STABLE;
+
+            CREATE TABLE IF NOT EXISTS score_decay (
+                guild           BIGINT PRIMARY KEY,
+                half_life       BIGINT NOT NULL,
+                levelups        BOOLEAN NOT NULL,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 8,
+        description: "Add score seasons",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_seasons (
+                guild           BIGINT,
+                season          INT,
+                started         TIMESTAMP WITH TIME ZONE,
+                ended           TIMESTAMP WITH TIME ZONE NOT NULL,
+                PRIMARY KEY (guild, season),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_season_ranks (
+                guild           BIGINT,
+                season          INT,
+                \"user\"        BIGINT,
+                rank            BIGINT NOT NULL,
+                score           BIGINT NOT NULL,
+                upvotes         BIGINT NOT NULL,
+                downvotes       BIGINT NOT NULL,
+                PRIMARY KEY (guild, season, \"user\"),
+                CONSTRAINT fk_score_seasons
+                    FOREIGN KEY (guild, season)
+                    REFERENCES score_seasons(guild, season)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 9,
+        description: "Add score multipliers and exclusions of channels",
+        sql: "
+            ALTER TABLE channels
+            ADD COLUMN IF NOT EXISTS score_multiplier INT NOT NULL DEFAULT 1
+                CHECK (score_multiplier > 0),
+            ADD COLUMN IF NOT EXISTS score_excluded BOOLEAN NOT NULL DEFAULT false;
+
+            CREATE OR REPLACE VIEW score_votes AS
+                SELECT r.guild, r.user_from, r.user_to, r.channel, r.message, r.emoji, r.native,
+                r.time, se.weight * c.score_multiplier weight, c.score_multiplier multiplier
+                FROM score_reactions r
+                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
+                INNER JOIN channels c ON r.guild = c.guild AND r.channel = c.channel
+                WHERE NOT c.score_excluded;
+        ",
+    },
+    Migration {
+        version: 10,
+        description: "Add score caps",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_caps (
+                guild           BIGINT PRIMARY KEY,
+                cap             BIGINT NOT NULL CHECK (cap > 0),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 11,
+        description: "Persist the reaction cooldowns of users",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_user_cooldowns (
+                guild           BIGINT,
+                \"user\"        BIGINT,
+                ends            TIMESTAMP WITH TIME ZONE NOT NULL,
+                PRIMARY KEY (guild, \"user\"),
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+
+            CREATE INDEX IF NOT EXISTS score_user_cooldowns_ends
+                ON score_user_cooldowns (ends);
+        ",
+    },
+    Migration {
+        version: 12,
+        description: "Add cooldown policies",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_cooldown_policies (
+                guild               BIGINT PRIMARY KEY,
+                per_receiver        BOOLEAN NOT NULL,
+                downvote_cooldown   BIGINT,
+                daily_budget        BIGINT,
+                window_votes        BIGINT,
+                window_minutes      BIGINT,
+                CHECK ((window_votes IS NULL) = (window_minutes IS NULL)),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            ALTER TABLE score_user_cooldowns
+            ADD COLUMN IF NOT EXISTS target BIGINT NOT NULL DEFAULT 0,
+            ADD COLUMN IF NOT EXISTS downvote BOOLEAN NOT NULL DEFAULT false,
+            DROP CONSTRAINT IF EXISTS score_user_cooldowns_pkey,
+            ADD PRIMARY KEY (guild, \"user\", target, downvote);
+        ",
+    },
+    Migration {
+        version: 13,
+        description: "Add level-up announcements and score rewards",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_announcements (
+                guild           BIGINT PRIMARY KEY,
+                channel         BIGINT,
+                level_up        TEXT,
+                level_down      TEXT,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_rewards (
+                guild           BIGINT,
+                score           BIGINT,
+                role            BIGINT,
+                channel         BIGINT,
+                message         TEXT,
+                PRIMARY KEY (guild, score),
+                CHECK (role IS NOT NULL OR (channel IS NOT NULL AND message IS NOT NULL)),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_roles
+                    FOREIGN KEY (guild, role)
+                    REFERENCES roles(guild, role)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_reward_claims (
+                guild           BIGINT,
+                score           BIGINT,
+                \"user\"        BIGINT,
+                PRIMARY KEY (guild, score, \"user\"),
+                CONSTRAINT fk_score_rewards
+                    FOREIGN KEY (guild, score)
+                    REFERENCES score_rewards(guild, score)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 14,
+        description: "Add stacking level-up roles",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_role_stacking (
+                guild           BIGINT PRIMARY KEY,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 15,
+        description: "Add daily score snapshots",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_snapshots (
+                guild           BIGINT,
+                \"user\"        BIGINT,
+                day             DATE,
+                score           BIGINT NOT NULL,
+                PRIMARY KEY (guild, \"user\", day),
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 16,
+        description: "Add the gift ledger and gift caps",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_gifts (
+                id              SERIAL PRIMARY KEY,
+                guild           BIGINT NOT NULL,
+                user_from       BIGINT NOT NULL,
+                user_to         BIGINT NOT NULL,
+                amount          BIGINT NOT NULL,
+                time            TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+                undone          BOOLEAN NOT NULL DEFAULT false,
+                CONSTRAINT fk_users_from
+                    FOREIGN KEY (guild, user_from)
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_users_to
+                    FOREIGN KEY (guild, user_to)
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_gift_reactions (
+                gift            INT,
+                user_from       BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                emoji           INT,
+                native          BOOLEAN NOT NULL,
+                PRIMARY KEY (gift, user_from, channel, message, emoji),
+                CONSTRAINT fk_score_gifts
+                    FOREIGN KEY (gift)
+                    REFERENCES score_gifts(id)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_gift_caps (
+                guild           BIGINT PRIMARY KEY,
+                daily           BIGINT CHECK (daily > 0),
+                weekly          BIGINT CHECK (weekly > 0),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 17,
+        description: "Add drop modes and persist pending drops",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_drop_modes (
+                guild           BIGINT PRIMARY KEY,
+                mode            TEXT NOT NULL,
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_pending_drops (
+                guild           BIGINT,
+                \"user\"        BIGINT,
+                channel         BIGINT NOT NULL,
+                message         BIGINT NOT NULL,
+                mode            TEXT NOT NULL,
+                ends            TIMESTAMP WITH TIME ZONE NOT NULL,
+                PRIMARY KEY (guild, \"user\"),
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_drop_participants (
+                guild           BIGINT,
+                \"user\"        BIGINT,
+                participant     BIGINT,
+                PRIMARY KEY (guild, \"user\", participant),
+                CONSTRAINT fk_score_pending_drops
+                    FOREIGN KEY (guild, \"user\")
+                    REFERENCES score_pending_drops(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 18,
+        description: "Replace auto-pin and auto-delete with auto-moderation rules",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_moderation_rules (
+                id              SERIAL PRIMARY KEY,
+                guild           BIGINT NOT NULL,
+                score           BIGINT,
+                reactions       BIGINT CHECK (reactions > 0),
+                channel         BIGINT,
+                max_age         BIGINT CHECK (max_age > 0),
+                action          TEXT NOT NULL,
+                target          BIGINT,
+                role            BIGINT,
+                duration        BIGINT CHECK (duration > 0),
+                CONSTRAINT fk_guilds
+                    FOREIGN KEY (guild)
+                    REFERENCES guilds(guild)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_channels
+                    FOREIGN KEY (guild, channel)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_targets
+                    FOREIGN KEY (guild, target)
+                    REFERENCES channels(guild, channel)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_roles
+                    FOREIGN KEY (guild, role)
+                    REFERENCES roles(guild, role)
+                    ON DELETE CASCADE
+            );
+
+            CREATE TABLE IF NOT EXISTS score_moderation_triggers (
+                rule            INT,
+                channel         BIGINT,
+                message         BIGINT,
+                PRIMARY KEY (rule, channel, message),
+                CONSTRAINT fk_score_moderation_rules
+                    FOREIGN KEY (rule)
+                    REFERENCES score_moderation_rules(id)
+                    ON DELETE CASCADE
+            );
+
+            INSERT INTO score_moderation_rules (guild, score, action)
+            SELECT guild, score, 'pin' FROM score_auto_pin;
+
+            INSERT INTO score_moderation_rules (guild, score, action)
+            SELECT guild, score, 'delete' FROM score_auto_delete;
+
+            DROP TABLE score_auto_pin;
+
+            DROP TABLE score_auto_delete;
+        ",
+    },
+    Migration {
+        version: 19,
+        description: "Remove the moderation triggers of deleted messages",
+        sql: "
+            ALTER TABLE score_moderation_triggers RENAME TO score_moderation_triggers_old;
+
+            CREATE TABLE score_moderation_triggers (
+                rule            INT,
+                guild           BIGINT NOT NULL,
+                channel         BIGINT,
+                message         BIGINT,
+                PRIMARY KEY (rule, channel, message),
+                CONSTRAINT fk_score_moderation_rules
+                    FOREIGN KEY (rule)
+                    REFERENCES score_moderation_rules(id)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_messages
+                    FOREIGN KEY (guild, channel, message)
+                    REFERENCES messages(guild, channel, message)
+                    ON DELETE CASCADE
+            );
+
+            INSERT INTO score_moderation_triggers
+            SELECT t.rule, r.guild, t.channel, t.message
+            FROM score_moderation_triggers_old t
+            INNER JOIN score_moderation_rules r ON r.id = t.rule
+            INNER JOIN messages m
+            ON m.guild = r.guild AND m.channel = t.channel AND m.message = t.message;
+
+            DROP TABLE score_moderation_triggers_old;
+        ",
+    },
+    Migration {
+        version: 20,
+        description: "Store the start of the current season",
+        sql: "
+            ALTER TABLE guilds
+            ADD COLUMN IF NOT EXISTS season_started TIMESTAMP WITH TIME ZONE;
+
+            UPDATE guilds g
+            SET season_started = s.ended
+            FROM (
+                SELECT guild, MAX(ended) ended FROM score_seasons
+                GROUP BY guild
+            ) s
+            WHERE g.guild = s.guild;
+
+            CREATE OR REPLACE VIEW score_votes AS
+                SELECT r.guild, r.user_from, r.user_to, r.channel, r.message, r.emoji, r.native,
+                r.time, se.weight * c.score_multiplier weight, c.score_multiplier multiplier
+                FROM score_reactions r
+                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
+                INNER JOIN channels c ON r.guild = c.guild AND r.channel = c.channel
+                INNER JOIN guilds g ON r.guild = g.guild
+                WHERE NOT c.score_excluded
+                AND (g.season_started IS NULL OR r.time >= g.season_started);
+        ",
+    },
+    Migration {
+        version: 21,
+        description: "Remember the votes removed by the bot",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_removed_votes (
+                guild           BIGINT,
+                user_from       BIGINT,
+                channel         BIGINT,
+                message         BIGINT,
+                emoji           INT,
+                PRIMARY KEY (guild, user_from, channel, message, emoji),
+                CONSTRAINT fk_messages
+                    FOREIGN KEY (guild, channel, message)
+                    REFERENCES messages(guild, channel, message)
+                    ON DELETE CASCADE,
+                CONSTRAINT fk_score_emojis
+                    FOREIGN KEY (guild, emoji)
+                    REFERENCES score_emojis(guild, emoji)
+                    ON DELETE CASCADE
+            );
+        ",
+    },
+    Migration {
+        version: 22,
+        description: "Credit returned upvotes to their givers in a ledger",
+        sql: "
+            CREATE TABLE IF NOT EXISTS score_returns (
+                guild           BIGINT,
+                user_from       BIGINT,
+                user_to         BIGINT,
+                time            TIMESTAMP WITH TIME ZONE NOT NULL,
+                weight          INT NOT NULL,
+                CONSTRAINT fk_users
+                    FOREIGN KEY (guild, user_to)
+                    REFERENCES users(guild, \"user\")
+                    ON DELETE CASCADE
+            );
+
+            CREATE OR REPLACE VIEW score_points AS
+                SELECT guild, user_to, time, weight FROM score_votes
+                UNION ALL
+                SELECT r.guild, r.user_to, r.time, r.weight
+                FROM score_returns r
+                INNER JOIN guilds g ON r.guild = g.guild
+                WHERE g.season_started IS NULL OR r.time >= g.season_started;
+        ",
+    },
+];
+
+/// Get the schema version this version of the bot expects.
+pub fn latest_version() -> i32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// Bring the database schema up to date by applying all pending migrations.
+///
+/// Note: This fails if the database was already migrated by a newer version of the bot
+pub async fn migrate(client: &mut Client) -> Result<(), KowalskiError> {
+    // Make sure the migrations are numbered consecutively
+    check_order()?;
+
+    let transaction = client.transaction().await?;
+
+    transaction
+        .batch_execute(
+            "
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version         INT PRIMARY KEY,
+                description     TEXT NOT NULL,
+                applied         TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+            );
+
+            LOCK TABLE schema_version IN EXCLUSIVE MODE;
+            ",
+        )
+        .await?;
+
+    // Get the current version of the database
+    let current: i32 = {
+        let row = transaction
+            .query_one("SELECT MAX(version) FROM schema_version", &[])
+            .await?;
+
+        row.get::<_, Option<i32>>(0).unwrap_or_default()
+    };
+
+    // Refuse to work on a schema we do not know about
+    let latest = latest_version();
+    if current > latest {
+        return Err(KowalskiError::MigrationError(format!(
+            "{} (database: {}, supported: {})",
+            ERR_DB_VERSION_NEWER, current, latest
+        )));
+    }
+
+    for migration in MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > current)
+    {
+        transaction.batch_execute(migration.sql).await?;
+        transaction
+            .execute(
+                "
+                INSERT INTO schema_version (version, description)
+                VALUES ($1::INT, $2::TEXT)
+                ",
+                &[&migration.version, &migration.description],
+            )
+            .await?;
+
+        info!(
+            "{} {}: {}",
+            INFO_DB_MIGRATED, migration.version, migration.description
+        );
+    }
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+fn check_order() -> Result<(), KowalskiError> {
+    for (index, migration) in MIGRATIONS.iter().enumerate() {
+        if migration.version != index as i32 + 1 {
+            return Err(KowalskiError::MigrationError(format!(
+                "{}: {}",
+                ERR_DB_MIGRATIONS_ORDER, migration.version
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -1,2 +1,8 @@
 pub mod client;
+pub mod memory;
+pub mod migrations;
+pub mod pool;
+pub mod postgres;
+pub mod storage;
+pub mod tls;
 pub mod types;
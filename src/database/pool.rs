@@ -0,0 +1,185 @@
+use std::{cmp::min, sync::Arc, time::Duration};
+
+use deadpool_postgres::{Manager, ManagerConfig, Object, RecyclingMethod, Runtime};
+use tokio::time::{interval, sleep};
+use tokio_postgres::{config::SslMode as PgSslMode, types::ToSql, NoTls, Row, ToStatement};
+use tracing::{info, warn};
+
+use crate::{
+    config::{DatabaseConfig, SslMode},
+    database::tls::create_connector,
+    error::KowalskiError,
+    strings::{ERR_DB_CLOSED, ERR_DB_CONNECTION, ERR_DB_RECONNECT, INFO_DB_RECONNECTED},
+};
+
+/// A pool of database connections, reconnecting automatically when the connection gets lost.
+///
+/// Note: The query functions mirror the ones of the tokio-postgres client, but check out a pooled
+/// connection for every call
+#[derive(Clone)]
+pub struct Pool {
+    pool: deadpool_postgres::Pool,
+    settings: Arc<DatabaseConfig>,
+}
+
+impl Pool {
+    pub fn new(
+        mut pg_config: tokio_postgres::Config,
+        settings: DatabaseConfig,
+    ) -> Result<Self, KowalskiError> {
+        // The config takes precedence over the sslmode of the connection string
+        let tls = match settings.ssl_mode {
+            Some(ssl_mode) => {
+                pg_config.ssl_mode(ssl_mode.into());
+
+                !matches!(ssl_mode, SslMode::Disable)
+            }
+            None => matches!(pg_config.get_ssl_mode(), PgSslMode::Require),
+        };
+
+        let manager_config = ManagerConfig {
+            // Make sure the connection is still alive before handing it out
+            recycling_method: RecyclingMethod::Verified,
+        };
+        let manager = if tls {
+            Manager::from_config(pg_config, create_connector(&settings)?, manager_config)
+        } else {
+            Manager::from_config(pg_config, NoTls, manager_config)
+        };
+
+        let timeout = Some(Duration::from_secs(settings.connection_timeout));
+        let pool = deadpool_postgres::Pool::builder(manager)
+            .max_size(settings.pool_size)
+            .wait_timeout(timeout)
+            .create_timeout(timeout)
+            .recycle_timeout(timeout)
+            .runtime(Runtime::Tokio1)
+            .build()
+            .map_err(|why| KowalskiError::PoolError(why.to_string()))?;
+
+        Ok(Pool {
+            pool,
+            settings: Arc::new(settings),
+        })
+    }
+
+    /// Get a connection from the pool.
+    ///
+    /// Note: If no connection can be established, this will retry with an exponential backoff
+    pub async fn get(&self) -> Result<Object, KowalskiError> {
+        let mut backoff = Duration::from_secs(self.settings.reconnect_backoff);
+        let backoff_max = Duration::from_secs(self.settings.reconnect_backoff_max);
+        let mut attempt = 1;
+
+        loop {
+            match self.pool.get().await {
+                Ok(client) => {
+                    if attempt > 1 {
+                        info!("{}", INFO_DB_RECONNECTED);
+                    }
+
+                    return Ok(client);
+                }
+                Err(why) => {
+                    if attempt >= self.settings.reconnect_attempts {
+                        return Err(KowalskiError::PoolError(why.to_string()));
+                    }
+
+                    warn!(
+                        "{} (attempt {}/{}, retrying in {:?}): {}",
+                        ERR_DB_RECONNECT, attempt, self.settings.reconnect_attempts, backoff, why
+                    );
+
+                    sleep(backoff).await;
+
+                    backoff = min(backoff * 2, backoff_max);
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    pub async fn query<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Vec<Row>, KowalskiError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        let client = self.get().await?;
+
+        Ok(client.query(statement, params).await?)
+    }
+
+    pub async fn query_one<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Row, KowalskiError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        let client = self.get().await?;
+
+        Ok(client.query_one(statement, params).await?)
+    }
+
+    pub async fn query_opt<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<Option<Row>, KowalskiError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        let client = self.get().await?;
+
+        Ok(client.query_opt(statement, params).await?)
+    }
+
+    pub async fn execute<T>(
+        &self,
+        statement: &T,
+        params: &[&(dyn ToSql + Sync)],
+    ) -> Result<u64, KowalskiError>
+    where
+        T: ?Sized + ToStatement + Sync,
+    {
+        let client = self.get().await?;
+
+        Ok(client.execute(statement, params).await?)
+    }
+
+    pub async fn batch_execute(&self, query: &str) -> Result<(), KowalskiError> {
+        let client = self.get().await?;
+
+        Ok(client.batch_execute(query).await?)
+    }
+
+    /// Periodically remove closed connections from the pool.
+    pub fn check_health(&self) {
+        let pool = self.clone();
+
+        tokio::spawn(async move {
+            // Create the interval at which we will check the connections
+            let mut interval = interval(Duration::from_secs(pool.settings.health_check_interval));
+
+            loop {
+                // Wait for the next tick
+                interval.tick().await;
+
+                let removed = pool.pool.retain(|client, _| !client.is_closed()).removed;
+
+                if !removed.is_empty() {
+                    warn!("{}: {}", ERR_DB_CLOSED, removed.len());
+                }
+
+                // Check whether the database is reachable at all
+                if let Err(why) = pool.get().await {
+                    warn!("{}: {}", ERR_DB_CONNECTION, why);
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,3736 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serenity::{
+    async_trait,
+    model::{
+        channel::ReactionType,
+        id::{ChannelId, EmojiId, GuildId, MessageId, RoleId, UserId},
+    },
+};
+use tokio_postgres::Row;
+
+use crate::{
+    database::{
+        client::Database,
+        storage::Storage,
+        types::{
+            ChannelScoring, CooldownPolicy, DominantGiver, DropMode, GiftCaps, LevelUpAnnouncement,
+            ModerationRule, ModuleStatus, NewAccountBurst, PendingDrop, ReactionRole,
+            ReactionRoleExpiration, ReactionRoleGroup, ReciprocalVotes, Reminder, ScoreDecay,
+            ScoreGift, ScoreReaction, ScoreReward, Season, SeasonRank, StoredEmoji,
+        },
+    },
+    error::KowalskiError,
+    row_id,
+};
+
+#[async_trait]
+impl Storage for Database {
+    async fn get_guild(&self, guild_id: GuildId) -> Result<i64, KowalskiError> {
+        self.client
+            .execute(
+                "
+            INSERT INTO guilds
+            VALUES($1::BIGINT)
+            ON CONFLICT
+            DO NOTHING
+            ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(guild_id.0 as i64)
+    }
+
+    async fn get_user(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO users
+            VALUES($1::BIGINT, $2::BIGINT)
+            ON CONFLICT
+            DO NOTHING
+            ",
+                &[&guild_db_id, &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(user_id.0 as i64)
+    }
+
+    async fn get_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<i64, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO roles
+            VALUES($1::BIGINT, $2::BIGINT)
+            ON CONFLICT
+            DO NOTHING
+            ",
+                &[&guild_db_id, &(role_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(role_id.0 as i64)
+    }
+
+    async fn get_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<i64, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO channels
+            VALUES($1::BIGINT, $2::BIGINT)
+            ON CONFLICT
+            DO NOTHING
+            ",
+                &[&guild_db_id, &(channel_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(channel_id.0 as i64)
+    }
+
+    async fn get_message(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<i64, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let channel_db_id = self.get_channel(guild_id, channel_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO messages
+            VALUES($1::BIGINT, $2::BIGINT, $3::BIGINT)
+            ON CONFLICT
+            DO NOTHING
+            ",
+                &[&guild_db_id, &channel_db_id, &(message_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(message_id.0 as i64)
+    }
+
+    async fn get_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: &ReactionType,
+    ) -> Result<i32, KowalskiError> {
+        let row = match emoji {
+            ReactionType::Custom { id: emoji_id, .. } => {
+                // Get guild id
+                let guild_db_id = self.get_guild(guild_id).await?;
+
+                self.client
+                    .query_one(
+                        "
+                        WITH id_row AS (
+                            SELECT id FROM emojis
+                            WHERE guild = $1::BIGINT AND guild_emoji = $2::BIGINT
+                        ), new_row AS (
+                            INSERT INTO emojis (guild, guild_emoji)
+                            SELECT $1::BIGINT, $2::BIGINT
+                            WHERE NOT EXISTS (SELECT * FROM id_row)
+                            RETURNING id
+                        )
+
+                        SELECT * FROM id_row
+                        UNION ALL
+                        SELECT * FROM new_row
+                        ",
+                        &[&guild_db_id, &(emoji_id.0 as i64)],
+                    )
+                    .await?
+            }
+            ReactionType::Unicode(string) => {
+                self.client
+                    .query_one(
+                        "
+                        WITH id_row AS (
+                            SELECT id FROM emojis
+                            WHERE unicode = $1::TEXT
+                        ), new_row AS (
+                            INSERT INTO emojis (unicode)
+                            SELECT $1::TEXT
+                            WHERE NOT EXISTS (SELECT * FROM id_row)
+                            RETURNING id
+                        )
+
+                        SELECT * FROM id_row
+                        UNION ALL
+                        SELECT * FROM new_row
+                        ",
+                        &[string],
+                    )
+                    .await?
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(row.get(0))
+    }
+
+    async fn find_emoji(&self, emoji: &ReactionType) -> Result<Option<i32>, KowalskiError> {
+        let rows = match emoji {
+            ReactionType::Unicode(string) => {
+                self.client
+                    .query(
+                        "
+                SELECT id FROM emojis
+                WHERE unicode = $1::TEXT
+                ",
+                        &[string],
+                    )
+                    .await?
+            }
+            ReactionType::Custom { id: emoji_id, .. } => {
+                self.client
+                    .query(
+                        "
+                    SELECT id FROM emojis
+                    WHERE guild_emoji = $1::BIGINT
+                    ",
+                        &[&(emoji_id.0 as i64)],
+                    )
+                    .await?
+            }
+            _ => unreachable!(),
+        };
+
+        Ok(rows.first().map(|row| row.get(0)))
+    }
+
+    async fn remove_user(&self, guild_id: GuildId, user_id: UserId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                WITH removed AS (
+                    INSERT INTO score_removed_votes
+                    SELECT guild, user_from, channel, message, emoji FROM score_reactions
+                    WHERE guild = $1::BIGINT AND user_to = $2::BIGINT
+                    ON CONFLICT
+                    DO NOTHING
+                )
+
+                DELETE FROM users
+                WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn user_guild_count(&self, user_id: UserId) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT COUNT(*) FROM users
+                WHERE \"user\" = $1::BIGINT
+                ",
+                &[&(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn guilds(&self) -> Result<Vec<GuildId>, KowalskiError> {
+        let rows = self.client.query("SELECT guild FROM guilds", &[]).await?;
+
+        Ok(rows.iter().map(|row| row_id!(GuildId, row, 0)).collect())
+    }
+
+    async fn remove_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "DELETE FROM guilds WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn guild_users(&self, guild_id: GuildId) -> Result<Vec<UserId>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT \"user\" FROM users WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(UserId, row, 0)).collect())
+    }
+
+    async fn guild_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT channel FROM channels WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(ChannelId, row, 0)).collect())
+    }
+
+    async fn remove_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM channels
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(channel_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn guild_roles(&self, guild_id: GuildId) -> Result<Vec<RoleId>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT role FROM roles WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(RoleId, row, 0)).collect())
+    }
+
+    async fn remove_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM roles
+                WHERE guild = $1::BIGINT AND role = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(role_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_messages(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_ids: &[MessageId],
+    ) -> Result<(), KowalskiError> {
+        let message_db_ids: Vec<_> = message_ids
+            .iter()
+            .map(|message_id| message_id.0 as i64)
+            .collect();
+
+        self.client
+            .execute(
+                "
+                DELETE FROM messages
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+                AND message = ANY($3::BIGINT[])
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &message_db_ids,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn guild_emojis(&self, guild_id: GuildId) -> Result<Vec<EmojiId>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT guild_emoji FROM emojis WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(EmojiId, row, 0)).collect())
+    }
+
+    async fn remove_guild_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji_id: EmojiId,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM emojis
+                WHERE guild = $1::BIGINT AND guild_emoji = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(emoji_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn module_status(&self, guild_id: GuildId) -> Result<ModuleStatus, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT status
+                FROM modules
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map_or(ModuleStatus::default(), |row| row.get(0)))
+    }
+
+    async fn module_statuses(&self) -> Result<Vec<(GuildId, ModuleStatus)>, KowalskiError> {
+        let rows = self
+            .client
+            .query("SELECT guild, status FROM modules", &[])
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(GuildId, row, 0), row.get(1)))
+            .collect())
+    }
+
+    async fn set_module_status(
+        &self,
+        guild_id: GuildId,
+        status: &ModuleStatus,
+    ) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO modules
+            VALUES ($1::BIGINT, $2::BIT(8))
+            ON CONFLICT (guild)
+            DO UPDATE SET status = $2::BIT(8)
+            ",
+                &[&guild_db_id, status],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_utility_data(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                WITH publishing AS (
+                    DELETE FROM publishing WHERE guild = $1::BIGINT
+                )
+
+                DELETE FROM reminders WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_score_data(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                WITH cooldowns AS (
+                    DELETE FROM score_cooldowns WHERE guild = $1::BIGINT
+                ),
+                drops AS (
+                    DELETE FROM score_drops WHERE guild = $1::BIGINT
+                ),
+                emojis AS (
+                    DELETE FROM score_emojis WHERE guild = $1::BIGINT
+                ),
+                rules AS (
+                    DELETE FROM score_moderation_rules WHERE guild = $1::BIGINT
+                )
+
+                DELETE FROM score_roles WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_reaction_role_data(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                WITH groups AS (
+                    DELETE FROM reaction_role_groups WHERE guild = $1::BIGINT
+                )
+
+                DELETE FROM reaction_roles WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn score_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: i32,
+    ) -> Result<Option<i32>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT weight FROM score_emojis
+                WHERE guild = $1::BIGINT AND emoji = $2::INT
+                ",
+                &[&(guild_id.0 as i64), &emoji],
+            )
+            .await?;
+
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn score_emojis(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(StoredEmoji, i32)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT unicode, guild_emoji, weight FROM score_emojis se
+                INNER JOIN emojis e ON se.emoji = e.id
+                WHERE se.guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    StoredEmoji::from_columns(row.get(0), row.get(1)),
+                    row.get(2),
+                )
+            })
+            .collect())
+    }
+
+    async fn set_score_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: i32,
+        weight: i32,
+    ) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_emojis (guild, emoji, weight)
+                VALUES ($1::BIGINT, $2::INT, $3::INT)
+                ON CONFLICT (guild, emoji)
+                DO UPDATE SET weight = $3::INT
+                ",
+                &[&guild_db_id, &emoji, &weight],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_score_emoji(&self, guild_id: GuildId, emoji: i32) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM score_emojis
+                WHERE guild = $1::BIGINT AND emoji = $2::INT
+                ",
+                &[&(guild_id.0 as i64), &emoji],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn add_score_reaction(&self, reaction: &ScoreReaction) -> Result<(), KowalskiError> {
+        // Make sure the receiving user and the message are registered
+        let guild_db_id = self.get_guild(reaction.guild).await?;
+        let user_from_db_id = self.get_user(reaction.guild, reaction.user_from).await?;
+        let user_to_db_id = self.get_user(reaction.guild, reaction.user_to).await?;
+        let channel_db_id = self.get_channel(reaction.guild, reaction.channel).await?;
+        let message_db_id = self
+            .get_message(reaction.guild, reaction.channel, reaction.message)
+            .await?;
+
+        self.client
+            .execute(
+                "
+                WITH restored AS (
+                    DELETE FROM score_removed_votes
+                    WHERE guild = $1::BIGINT AND user_from = $2::BIGINT AND channel = $4::BIGINT
+                    AND message = $5::BIGINT AND emoji = $6::INT
+                )
+
+                INSERT INTO score_reactions
+                VALUES($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::BIGINT, $6::INT,
+                $7::BOOLEAN, $8::TIMESTAMPTZ)
+                ON CONFLICT
+                DO NOTHING
+                ",
+                &[
+                    &guild_db_id,
+                    &user_from_db_id,
+                    &user_to_db_id,
+                    &channel_db_id,
+                    &message_db_id,
+                    &reaction.emoji,
+                    &reaction.native,
+                    &reaction.time,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_score_reaction(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+    ) -> Result<Option<UserId>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                DELETE FROM score_reactions
+                WHERE guild = $1::BIGINT AND user_from = $2::BIGINT AND channel = $3::BIGINT
+                AND message = $4::BIGINT AND emoji = $5::INT
+                RETURNING user_to
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(user_from.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &emoji,
+                ],
+            )
+            .await?;
+
+        Ok(row.map(|row| row_id!(UserId, row, 0)))
+    }
+
+    async fn message_score_reactions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<ScoreReaction>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT guild, user_from, user_to, channel, message, emoji, native, time
+                FROM score_reactions
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(rows.iter().map(score_reaction).collect())
+    }
+
+    async fn removed_votes(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<(UserId, i32)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT user_from, emoji
+                FROM score_removed_votes
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(UserId, row, 0), row.get(1)))
+            .collect())
+    }
+
+    async fn tracked_messages(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(ChannelId, MessageId)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, message
+                FROM messages
+                WHERE guild = $1::BIGINT
+                ORDER BY message DESC
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(ChannelId, row, 0), row_id!(MessageId, row, 1)))
+            .collect())
+    }
+
+    async fn score_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT DISTINCT channel
+                FROM score_reactions
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(ChannelId, row, 0)).collect())
+    }
+
+    async fn remove_message_score_reactions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM score_reactions
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn user_score(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT SUM(weight) score
+                FROM score_points r
+                WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn user_decayed_score(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        half_life: i64,
+    ) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT ROUND(SUM(decayed_weight(weight, time, $3::FLOAT8)))::BIGINT score
+                FROM score_points r
+                WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(user_id.0 as i64),
+                    &(half_life as f64),
+                ],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn guild_scores(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(UserId, i64, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT user_to,
+                ROUND(SUM(decayed_weight(weight, time, $3::FLOAT8)) FILTER (WHERE weight > 0))::BIGINT upvotes,
+                ROUND(-SUM(decayed_weight(weight, time, $3::FLOAT8)) FILTER (WHERE weight < 0))::BIGINT downvotes
+                FROM score_points r
+                WHERE r.guild = $1::BIGINT AND time >= $2::TIMESTAMPTZ
+                GROUP BY user_to
+                ORDER BY SUM(decayed_weight(weight, time, $3::FLOAT8)) DESC, user_to
+                ",
+                &[&(guild_id.0 as i64), &start, &half_life],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let upvotes: Option<i64> = row.get(1);
+                let downvotes: Option<i64> = row.get(2);
+
+                (
+                    row_id!(UserId, row, 0),
+                    upvotes.unwrap_or_default(),
+                    downvotes.unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    async fn guild_givens(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT user_from, SUM(weight) FILTER (WHERE weight > 0) upvotes,
+                -SUM(weight) FILTER (WHERE weight < 0) downvotes,
+                SUM(weight) FILTER (WHERE NOT native) gifted
+                FROM score_votes r
+                WHERE r.guild = $1::BIGINT
+                GROUP BY user_from
+                ORDER BY SUM(weight) DESC, user_from
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let upvotes: Option<i64> = row.get(1);
+                let downvotes: Option<i64> = row.get(2);
+                let gifted: Option<i64> = row.get(3);
+
+                (
+                    row_id!(UserId, row, 0),
+                    upvotes.unwrap_or_default(),
+                    downvotes.unwrap_or_default(),
+                    gifted.unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    async fn received_emojis(
+        &self,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+        start: DateTime<Utc>,
+    ) -> Result<Vec<(StoredEmoji, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT unicode, guild_emoji, COUNT(*) FROM score_votes r
+                INNER JOIN emojis e ON r.emoji = e.id
+                WHERE ($1::BIGINT IS NULL OR r.guild = $1::BIGINT) AND user_to = $2::BIGINT
+                AND time >= $3::TIMESTAMPTZ
+                GROUP BY emoji, unicode, guild_emoji
+                ORDER BY count DESC
+                ",
+                &[
+                    &guild_id.map(|guild_id| guild_id.0 as i64),
+                    &(user_id.0 as i64),
+                    &start,
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    StoredEmoji::from_columns(row.get(0), row.get(1)),
+                    row.get(2),
+                )
+            })
+            .collect())
+    }
+
+    async fn given_emojis(
+        &self,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+    ) -> Result<Vec<(StoredEmoji, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT unicode, guild_emoji, COUNT(*) FROM score_votes r
+                INNER JOIN emojis e ON r.emoji = e.id
+                WHERE ($1::BIGINT IS NULL OR r.guild = $1::BIGINT) AND user_from = $2::BIGINT
+                GROUP BY emoji, unicode, guild_emoji
+                ORDER BY count DESC
+                ",
+                &[
+                    &guild_id.map(|guild_id| guild_id.0 as i64),
+                    &(user_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                (
+                    StoredEmoji::from_columns(row.get(0), row.get(1)),
+                    row.get(2),
+                )
+            })
+            .collect())
+    }
+
+    async fn user_channel_scores(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(ChannelId, i32, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, multiplier,
+                ROUND(SUM(decayed_weight(weight, time, $4::FLOAT8)))::BIGINT score
+                FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND time >= $3::TIMESTAMPTZ
+                GROUP BY channel, multiplier
+                ORDER BY score DESC
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(user_id.0 as i64),
+                    &start,
+                    &half_life,
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(ChannelId, row, 0), row.get(1), row.get(2)))
+            .collect())
+    }
+
+    async fn user_voters(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(UserId, i64, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT user_from,
+                ROUND(SUM(decayed_weight(weight, time, $4::FLOAT8)) FILTER (WHERE weight > 0))::BIGINT upvotes,
+                ROUND(-SUM(decayed_weight(weight, time, $4::FLOAT8)) FILTER (WHERE weight < 0))::BIGINT downvotes
+                FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND native = true
+                AND time >= $3::TIMESTAMPTZ
+                GROUP BY user_from
+                ORDER BY SUM(decayed_weight(weight, time, $4::FLOAT8)) DESC, user_from
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64), &start, &half_life],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let upvotes: Option<i64> = row.get(1);
+                let downvotes: Option<i64> = row.get(2);
+
+                (
+                    row_id!(UserId, row, 0),
+                    upvotes.unwrap_or_default(),
+                    downvotes.unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    async fn user_recipients(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT user_to, SUM(weight) FILTER (WHERE weight > 0) upvotes,
+                -SUM(weight) FILTER (WHERE weight < 0) downvotes,
+                SUM(weight) FILTER (WHERE NOT native) gifted
+                FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT
+                GROUP BY user_to
+                ORDER BY SUM(weight) DESC, user_to
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let upvotes: Option<i64> = row.get(1);
+                let downvotes: Option<i64> = row.get(2);
+                let gifted: Option<i64> = row.get(3);
+
+                (
+                    row_id!(UserId, row, 0),
+                    upvotes.unwrap_or_default(),
+                    downvotes.unwrap_or_default(),
+                    gifted.unwrap_or_default(),
+                )
+            })
+            .collect())
+    }
+
+    async fn global_user_score(
+        &self,
+        user_id: UserId,
+    ) -> Result<(i64, i64, Option<i64>), KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                WITH ranks AS (
+                    SELECT user_to, SUM(weight) FILTER (WHERE weight > 0) upvotes,
+                    -SUM(weight) FILTER (WHERE weight < 0) downvotes,
+                    RANK() OVER (ORDER BY SUM(weight) DESC, user_to) rank
+                    FROM score_points r
+                    GROUP BY user_to
+                )
+
+                SELECT upvotes, downvotes, rank FROM ranks
+                WHERE user_to = $1::BIGINT
+                ",
+                &[&(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map_or((0, 0, None), |row| {
+            let upvotes: Option<i64> = row.get(0);
+            let downvotes: Option<i64> = row.get(1);
+
+            (
+                upvotes.unwrap_or_default(),
+                downvotes.unwrap_or_default(),
+                Some(row.get(2)),
+            )
+        }))
+    }
+
+    async fn global_user_givens(
+        &self,
+        user_id: UserId,
+    ) -> Result<(i64, i64, Option<i64>), KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                WITH ranks AS (
+                    SELECT user_from, SUM(weight) FILTER (WHERE weight > 0) upvotes,
+                    -SUM(weight) FILTER (WHERE weight < 0) downvotes,
+                    RANK() OVER (ORDER BY SUM(weight) DESC, user_from) rank
+                    FROM score_votes r
+                    GROUP BY user_from
+                )
+
+                SELECT upvotes, downvotes, rank FROM ranks
+                WHERE user_from = $1::BIGINT
+                ",
+                &[&(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map_or((0, 0, None), |row| {
+            let upvotes: Option<i64> = row.get(0);
+            let downvotes: Option<i64> = row.get(1);
+
+            (
+                upvotes.unwrap_or_default(),
+                downvotes.unwrap_or_default(),
+                Some(row.get(2)),
+            )
+        }))
+    }
+
+    async fn message_score(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT SUM(weight) FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn user_upvotes(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT SUM(weight) FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND weight > 0
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn pair_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+    ) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT SUM(weight) FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT AND user_to = $3::BIGINT
+                AND native = true AND weight > 0
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(user_from.0 as i64),
+                    &(user_to.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn reciprocal_votes(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        min_score: i64,
+        limit: i64,
+    ) -> Result<Vec<ReciprocalVotes>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                WITH pairs AS (
+                    SELECT user_from, user_to, SUM(weight) score
+                    FROM score_votes r
+                    WHERE r.guild = $1::BIGINT AND native = true AND weight > 0
+                    AND time >= $2::TIMESTAMPTZ
+                    GROUP BY user_from, user_to
+                )
+
+                SELECT a.user_from, a.user_to, a.score, b.score
+                FROM pairs a
+                INNER JOIN pairs b ON a.user_from = b.user_to AND a.user_to = b.user_from
+                WHERE a.user_from < a.user_to AND a.score >= $3::BIGINT AND b.score >= $3::BIGINT
+                ORDER BY LEAST(a.score, b.score) DESC
+                LIMIT $4::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &start, &min_score, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| ReciprocalVotes {
+                users: (row_id!(UserId, row, 0), row_id!(UserId, row, 1)),
+                scores: (row.get(2), row.get(3)),
+            })
+            .collect())
+    }
+
+    async fn dominant_givers(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        min_score: i64,
+        min_share: i64,
+        limit: i64,
+    ) -> Result<Vec<DominantGiver>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                WITH pairs AS (
+                    SELECT user_from, user_to, SUM(weight) score
+                    FROM score_votes r
+                    WHERE r.guild = $1::BIGINT AND native = true AND weight > 0
+                    AND time >= $2::TIMESTAMPTZ
+                    GROUP BY user_from, user_to
+                ),
+                totals AS (
+                    SELECT user_to, SUM(score)::BIGINT total
+                    FROM pairs
+                    GROUP BY user_to
+                )
+
+                SELECT p.user_from, p.user_to, p.score, t.total
+                FROM pairs p
+                INNER JOIN totals t ON p.user_to = t.user_to
+                WHERE p.score >= $3::BIGINT AND p.score * 100 >= t.total * $4::BIGINT
+                ORDER BY p.score::FLOAT8 / t.total DESC, p.score DESC
+                LIMIT $5::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &start, &min_score, &min_share, &limit],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| DominantGiver {
+                user_from: row_id!(UserId, row, 0),
+                user_to: row_id!(UserId, row, 1),
+                score: row.get(2),
+                total: row.get(3),
+            })
+            .collect())
+    }
+
+    async fn new_account_bursts(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        account_age: i32,
+        min_upvotes: i64,
+        limit: i64,
+    ) -> Result<Vec<NewAccountBurst>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT user_from, DATE_TRUNC('hour', time) hour, COUNT(*) upvotes
+                FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND native = true AND weight > 0
+                AND time >= $2::TIMESTAMPTZ
+                AND time < TO_TIMESTAMP(((user_from >> 22) + 1420070400000) / 1000.0)
+                    + MAKE_INTERVAL(days => $3::INT)
+                GROUP BY user_from, hour
+                HAVING COUNT(*) >= $4::BIGINT
+                ORDER BY upvotes DESC, hour DESC
+                LIMIT $5::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &start,
+                    &account_age,
+                    &min_upvotes,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| NewAccountBurst {
+                user: row_id!(UserId, row, 0),
+                hour: row.get(1),
+                upvotes: row.get(2),
+            })
+            .collect())
+    }
+
+    async fn gift_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+        amount: i64,
+        limits: &[(DateTime<Utc>, i64)],
+    ) -> Result<u64, KowalskiError> {
+        let user_from_db_id = self.get_user(guild_id, user_from).await?;
+        let user_to_db_id = self.get_user(guild_id, user_to).await?;
+
+        let mut client = self.client.get().await?;
+        let transaction = client.transaction().await?;
+
+        // Lock the user gifting, so concurrent gifts can't exceed the limits together
+        transaction
+            .execute(
+                "
+                SELECT 1 FROM users
+                WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                FOR UPDATE
+                ",
+                &[&(guild_id.0 as i64), &user_from_db_id],
+            )
+            .await?;
+
+        // Get the score which can still be gifted
+        let mut limit = i64::MAX;
+        for (since, cap) in limits {
+            let row = transaction
+                .query_one(
+                    "
+                    SELECT SUM(amount)::BIGINT FROM score_gifts
+                    WHERE guild = $1::BIGINT AND user_from = $2::BIGINT
+                    AND time >= $3::TIMESTAMPTZ AND NOT undone
+                    ",
+                    &[&(guild_id.0 as i64), &user_from_db_id, since],
+                )
+                .await?;
+
+            limit = limit.min(cap - row.get::<_, Option<i64>>(0).unwrap_or_default());
+        }
+
+        // Move the upvotes and record the gift in one statement, so it can always be undone
+        let row = transaction
+            .query_one(
+                "
+                WITH upvotes AS (
+                    SELECT r.guild, user_from, user_to, channel, message, r.emoji, native, weight,
+                    SUM(weight) OVER (
+                        ORDER BY native, weight, channel, message, user_from, r.emoji
+                    ) running
+                    FROM score_votes r
+                    WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND weight > 0
+                ),
+                to_update AS (
+                    SELECT * FROM upvotes
+                    WHERE running <= LEAST($4::BIGINT, $5::BIGINT)
+                ),
+                gift AS (
+                    INSERT INTO score_gifts (guild, user_from, user_to, amount)
+                    SELECT $1::BIGINT, $2::BIGINT, $3::BIGINT, SUM(weight) FROM to_update
+                    HAVING COUNT(*) > 0
+                    RETURNING id
+                ),
+                recorded AS (
+                    INSERT INTO score_gift_reactions
+                    SELECT id, user_from, channel, message, emoji, native FROM gift, to_update
+                ),
+                updated AS (
+                    UPDATE score_reactions
+                    SET user_to = $3::BIGINT, native = false
+                    WHERE (guild, user_from, user_to, channel, message, emoji)
+                        IN (SELECT guild, user_from, user_to, channel, message, emoji FROM to_update)
+                )
+
+                SELECT SUM(weight) FROM to_update
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &user_from_db_id,
+                    &user_to_db_id,
+                    &amount,
+                    &limit,
+                ],
+            )
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default() as u64)
+    }
+
+    async fn gifted_score(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT SUM(amount)::BIGINT FROM score_gifts
+                WHERE guild = $1::BIGINT AND user_from = $2::BIGINT AND time >= $3::TIMESTAMPTZ
+                AND NOT undone
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64), &since],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn score_gifts(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+        limit: i64,
+    ) -> Result<Vec<ScoreGift>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT id, user_from, user_to, amount, time, undone FROM score_gifts
+                WHERE guild = $1::BIGINT
+                AND ($2::BIGINT IS NULL OR user_from = $2::BIGINT OR user_to = $2::BIGINT)
+                ORDER BY time DESC, id DESC
+                LIMIT $3::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &user_id.map(|user| user.0 as i64),
+                    &limit,
+                ],
+            )
+            .await?;
+
+        Ok(rows.iter().map(score_gift).collect())
+    }
+
+    async fn undo_gift(
+        &self,
+        guild_id: GuildId,
+        gift: i32,
+    ) -> Result<Option<(ScoreGift, u64)>, KowalskiError> {
+        // Reactions which were given again to the original user in the meantime can't be moved back
+        let row = self
+            .client
+            .query_opt(
+                "
+                WITH gift AS (
+                    UPDATE score_gifts
+                    SET undone = true
+                    WHERE guild = $1::BIGINT AND id = $2::INT AND NOT undone
+                    RETURNING id, user_from, user_to, amount, time, undone
+                ),
+                restored AS (
+                    UPDATE score_reactions r
+                    SET user_to = g.user_from, native = gr.native
+                    FROM gift g
+                    INNER JOIN score_gift_reactions gr ON g.id = gr.gift
+                    WHERE r.guild = $1::BIGINT AND r.user_from = gr.user_from
+                    AND r.user_to = g.user_to AND r.channel = gr.channel
+                    AND r.message = gr.message AND r.emoji = gr.emoji
+                    AND NOT EXISTS (
+                        SELECT * FROM score_reactions o
+                        WHERE o.guild = r.guild AND o.user_from = r.user_from
+                        AND o.user_to = g.user_from AND o.channel = r.channel
+                        AND o.message = r.message AND o.emoji = r.emoji
+                    )
+                    RETURNING r.channel, r.emoji
+                )
+
+                SELECT g.*, (
+                    SELECT SUM(se.weight * c.score_multiplier)::BIGINT FROM restored rs
+                    INNER JOIN score_emojis se ON se.guild = $1::BIGINT AND rs.emoji = se.emoji
+                    INNER JOIN channels c ON c.guild = $1::BIGINT AND rs.channel = c.channel
+                    WHERE NOT c.score_excluded
+                ) FROM gift g
+                ",
+                &[&(guild_id.0 as i64), &gift],
+            )
+            .await?;
+
+        Ok(row.map(|row| {
+            let restored: Option<i64> = row.get(6);
+
+            (score_gift(&row), restored.unwrap_or_default() as u64)
+        }))
+    }
+
+    async fn gift_caps(&self, guild_id: GuildId) -> Result<GiftCaps, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT daily, weekly FROM score_gift_caps
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map_or_else(GiftCaps::default, |row| GiftCaps {
+            daily: row.get(0),
+            weekly: row.get(1),
+        }))
+    }
+
+    async fn set_gift_caps(&self, guild_id: GuildId, caps: &GiftCaps) -> Result<(), KowalskiError> {
+        // Gifts without caps do not need to be stored
+        if *caps == GiftCaps::default() {
+            self.client
+                .execute(
+                    "
+                    DELETE FROM score_gift_caps
+                    WHERE guild = $1::BIGINT
+                    ",
+                    &[&(guild_id.0 as i64)],
+                )
+                .await?;
+
+            return Ok(());
+        }
+
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_gift_caps
+                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT)
+                ON CONFLICT (guild)
+                DO UPDATE SET daily = $2::BIGINT, weekly = $3::BIGINT
+                ",
+                &[&guild_db_id, &caps.daily, &caps.weekly],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn transfer_score(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+    ) -> Result<u64, KowalskiError> {
+        let user_to_db_id = self.get_user(guild_id, user_to).await?;
+
+        self.client
+            .execute(
+                "
+                UPDATE score_reactions
+                SET user_to = $3::BIGINT, native = false
+                WHERE guild = $1::BIGINT AND user_to = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(user_from.0 as i64), &user_to_db_id],
+            )
+            .await
+    }
+
+    async fn end_season(&self, guild_id: GuildId) -> Result<Season, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        // Archive and reset the scores in one statement, so no reaction gets lost in between
+        let row = self
+            .client
+            .query_one(
+                "
+                WITH previous AS (
+                    SELECT COALESCE(MAX(season), 0) + 1 season, MAX(ended) started
+                    FROM score_seasons
+                    WHERE guild = $1::BIGINT
+                ),
+                season AS (
+                    INSERT INTO score_seasons (guild, season, started, ended)
+                    SELECT $1::BIGINT, season, started, NOW() FROM previous
+                    RETURNING season, started, ended
+                ),
+                ranks AS (
+                    INSERT INTO score_season_ranks
+                        (guild, season, \"user\", rank, score, upvotes, downvotes)
+                    SELECT $1::BIGINT, (SELECT season FROM previous), user_to,
+                    RANK() OVER (ORDER BY SUM(weight) DESC), SUM(weight),
+                    COALESCE(SUM(weight) FILTER (WHERE weight > 0), 0),
+                    COALESCE(-SUM(weight) FILTER (WHERE weight < 0), 0)
+                    FROM score_points r
+                    WHERE r.guild = $1::BIGINT
+                    GROUP BY user_to
+                ),
+                started AS (
+                    UPDATE guilds
+                    SET season_started = (SELECT ended FROM season)
+                    WHERE guild = $1::BIGINT
+                )
+
+                SELECT season, started, ended FROM season
+                ",
+                &[&guild_db_id],
+            )
+            .await?;
+
+        Ok(season(&row))
+    }
+
+    async fn split_score(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        users: &[UserId],
+    ) -> Result<u64, KowalskiError> {
+        if users.is_empty() {
+            return Ok(0);
+        }
+
+        let mut user_db_ids = Vec::new();
+        for &user in users {
+            user_db_ids.push(self.get_user(guild_id, user).await?);
+        }
+
+        // Deal the reactions out one by one, the heaviest first
+        self.client
+            .execute(
+                "
+                WITH reactions AS (
+                    SELECT r.guild, user_from, user_to, channel, message, r.emoji,
+                    ROW_NUMBER() OVER (
+                        ORDER BY weight DESC, channel, message, user_from, r.emoji
+                    ) - 1 number
+                    FROM score_votes r
+                    WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT
+                )
+
+                UPDATE score_reactions s
+                SET user_to = ($3::BIGINT[])[r.number % CARDINALITY($3::BIGINT[]) + 1],
+                native = false
+                FROM reactions r
+                WHERE (s.guild, s.user_from, s.user_to, s.channel, s.message, s.emoji)
+                    = (r.guild, r.user_from, r.user_to, r.channel, r.message, r.emoji)
+                ",
+                &[&(guild_id.0 as i64), &(user_from.0 as i64), &user_db_ids],
+            )
+            .await
+    }
+
+    async fn return_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                WITH returned AS (
+                    INSERT INTO score_returns (guild, user_from, user_to, time, weight)
+                    SELECT r.guild, user_to, user_from, time, weight
+                    FROM score_votes r
+                    INNER JOIN users u ON r.guild = u.guild AND r.user_from = u.\"user\"
+                    WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT AND weight > 0
+                    RETURNING weight
+                )
+
+                SELECT SUM(weight)::BIGINT FROM returned
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.get::<_, Option<i64>>(0).unwrap_or_default())
+    }
+
+    async fn drop_mode(&self, guild_id: GuildId) -> Result<DropMode, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT mode FROM score_drop_modes
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| row.get(0)).unwrap_or_default())
+    }
+
+    async fn set_drop_mode(&self, guild_id: GuildId, mode: DropMode) -> Result<(), KowalskiError> {
+        // The default mode does not need to be stored
+        if mode == DropMode::default() {
+            self.client
+                .execute(
+                    "
+                    DELETE FROM score_drop_modes
+                    WHERE guild = $1::BIGINT
+                    ",
+                    &[&(guild_id.0 as i64)],
+                )
+                .await?;
+
+            return Ok(());
+        }
+
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_drop_modes
+                VALUES ($1::BIGINT, $2::TEXT)
+                ON CONFLICT (guild)
+                DO UPDATE SET mode = $2::TEXT
+                ",
+                &[&guild_db_id, &mode],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn drop_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "SELECT channel FROM score_drops WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(ChannelId, row, 0)).collect())
+    }
+
+    async fn random_drop_channel(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<ChannelId>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT channel FROM score_drops
+                WHERE guild = $1::BIGINT
+                OFFSET FLOOR(RANDOM() * (SELECT COUNT(*) FROM score_drops WHERE guild = $1::BIGINT))
+                LIMIT 1
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| row_id!(ChannelId, row, 0)))
+    }
+
+    async fn add_drop_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let channel_db_id = self.get_channel(guild_id, channel_id).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_drops
+                VALUES($1::BIGINT, $2::BIGINT)
+                ON CONFLICT
+                DO NOTHING
+                ",
+                &[&guild_db_id, &channel_db_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_drop_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<bool, KowalskiError> {
+        let modified = self
+            .client
+            .execute(
+                "
+                DELETE FROM score_drops
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(channel_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(modified > 0)
+    }
+
+    async fn add_pending_drop(&self, drop: &PendingDrop) -> Result<(), KowalskiError> {
+        let user_db_id = self.get_user(drop.guild, drop.user).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_pending_drops
+                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::TEXT, $6::TIMESTAMPTZ)
+                ON CONFLICT (guild, \"user\")
+                DO UPDATE SET channel = $3::BIGINT, message = $4::BIGINT, mode = $5::TEXT,
+                ends = $6::TIMESTAMPTZ
+                ",
+                &[
+                    &(drop.guild.0 as i64),
+                    &user_db_id,
+                    &(drop.channel.0 as i64),
+                    &(drop.message.0 as i64),
+                    &drop.mode,
+                    &drop.ends,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn pending_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<PendingDrop>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT guild, \"user\", channel, message, mode, ends FROM score_pending_drops
+                WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| pending_drop(&row)))
+    }
+
+    async fn due_drops(&self) -> Result<Vec<PendingDrop>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT guild, \"user\", channel, message, mode, ends FROM score_pending_drops
+                WHERE ends <= NOW()
+                ",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(pending_drop).collect())
+    }
+
+    async fn join_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        participant: UserId,
+    ) -> Result<bool, KowalskiError> {
+        let joined = self
+            .client
+            .execute(
+                "
+                INSERT INTO score_drop_participants
+                SELECT guild, \"user\", $3::BIGINT FROM score_pending_drops
+                WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                ON CONFLICT
+                DO NOTHING
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(user_id.0 as i64),
+                    &(participant.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(joined > 0)
+    }
+
+    async fn take_pending_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<(PendingDrop, Vec<UserId>)>, KowalskiError> {
+        // Read the participants in the same statement, the deletion removes them as well
+        let row = self
+            .client
+            .query_opt(
+                "
+                WITH taken AS (
+                    DELETE FROM score_pending_drops
+                    WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                    RETURNING guild, \"user\", channel, message, mode, ends
+                )
+
+                SELECT taken.*, ARRAY(
+                    SELECT participant FROM score_drop_participants
+                    WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                ) FROM taken
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| {
+            let participants: Vec<i64> = row.get(6);
+
+            (
+                pending_drop(&row),
+                participants
+                    .into_iter()
+                    .map(|participant| UserId(participant as u64))
+                    .collect(),
+            )
+        }))
+    }
+
+    async fn season_start(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<DateTime<Utc>>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT season_started FROM guilds
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.and_then(|row| row.get(0)))
+    }
+
+    async fn seasons(&self, guild_id: GuildId) -> Result<Vec<Season>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT season, started, ended FROM score_seasons
+                WHERE guild = $1::BIGINT
+                ORDER BY season DESC
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(season).collect())
+    }
+
+    async fn season_ranks(
+        &self,
+        guild_id: GuildId,
+        season: i32,
+    ) -> Result<Vec<SeasonRank>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT \"user\", rank, score, upvotes, downvotes FROM score_season_ranks
+                WHERE guild = $1::BIGINT AND season = $2::INT
+                ORDER BY rank, \"user\"
+                ",
+                &[&(guild_id.0 as i64), &season],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| SeasonRank {
+                user: row_id!(UserId, row, 0),
+                rank: row.get(1),
+                score: row.get(2),
+                upvotes: row.get(3),
+                downvotes: row.get(4),
+            })
+            .collect())
+    }
+
+    async fn snapshot_scores(&self) -> Result<u64, KowalskiError> {
+        let snapshots = self
+            .client
+            .execute(
+                "
+                INSERT INTO score_snapshots (guild, \"user\", day, score)
+                SELECT r.guild, user_to, (NOW() AT TIME ZONE 'UTC')::DATE,
+                ROUND(SUM(decayed_weight(weight, time, COALESCE(d.half_life, 'Infinity'::FLOAT8))))::BIGINT
+                FROM score_points r
+                LEFT JOIN score_decay d ON r.guild = d.guild
+                GROUP BY r.guild, user_to
+                ON CONFLICT (guild, \"user\", day) DO UPDATE SET score = excluded.score
+                ",
+                &[],
+            )
+            .await?;
+
+        Ok(snapshots)
+    }
+
+    async fn score_snapshots(
+        &self,
+        guild_id: GuildId,
+        users: &[UserId],
+        since: NaiveDate,
+    ) -> Result<Vec<(UserId, NaiveDate, i64)>, KowalskiError> {
+        let users: Vec<_> = users.iter().map(|user| user.0 as i64).collect();
+
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT \"user\", day, score FROM score_snapshots
+                WHERE guild = $1::BIGINT AND \"user\" = ANY($2::BIGINT[]) AND day >= $3::DATE
+                ORDER BY day
+                ",
+                &[&(guild_id.0 as i64), &users, &since],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(UserId, row, 0), row.get(1), row.get(2)))
+            .collect())
+    }
+
+    async fn level_up_roles(&self, guild_id: GuildId) -> Result<Vec<(RoleId, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT role, score FROM score_roles
+                WHERE guild = $1::BIGINT
+                ORDER BY score
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(RoleId, row, 0), row.get(1)))
+            .collect())
+    }
+
+    async fn level_up_stacking(&self, guild_id: GuildId) -> Result<bool, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT guild FROM score_role_stacking
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.is_some())
+    }
+
+    async fn set_level_up_stacking(
+        &self,
+        guild_id: GuildId,
+        stacking: bool,
+    ) -> Result<(), KowalskiError> {
+        if stacking {
+            let guild_db_id = self.get_guild(guild_id).await?;
+
+            self.client
+                .execute(
+                    "
+                    INSERT INTO score_role_stacking
+                    VALUES ($1::BIGINT)
+                    ON CONFLICT
+                    DO NOTHING
+                    ",
+                    &[&guild_db_id],
+                )
+                .await?;
+        } else {
+            self.client
+                .execute(
+                    "
+                    DELETE FROM score_role_stacking
+                    WHERE guild = $1::BIGINT
+                    ",
+                    &[&(guild_id.0 as i64)],
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn add_level_up_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        score: i64,
+    ) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let role_db_id = self.get_role(guild_id, role_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO score_roles
+            VALUES($1::BIGINT, $2::BIGINT, $3::BIGINT)
+            ON CONFLICT
+            DO NOTHING
+            ",
+                &[&guild_db_id, &role_db_id, &score],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_level_up_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        score: i64,
+    ) -> Result<bool, KowalskiError> {
+        let modified = self
+            .client
+            .execute(
+                "
+            DELETE FROM score_roles
+            WHERE guild = $1::BIGINT AND role = $2::BIGINT AND score = $3::BIGINT
+            ",
+                &[&(guild_id.0 as i64), &(role_id.0 as i64), &score],
+            )
+            .await?;
+
+        Ok(modified > 0)
+    }
+
+    async fn level_up_announcement(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<LevelUpAnnouncement>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT channel, level_up, level_down FROM score_announcements
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| {
+            let channel: Option<i64> = row.get(0);
+
+            LevelUpAnnouncement {
+                channel: channel.map(|channel| ChannelId(channel as u64)),
+                level_up: row.get(1),
+                level_down: row.get(2),
+            }
+        }))
+    }
+
+    async fn set_level_up_announcement(
+        &self,
+        guild_id: GuildId,
+        announcement: Option<&LevelUpAnnouncement>,
+    ) -> Result<(), KowalskiError> {
+        match announcement {
+            Some(announcement) => {
+                let guild_db_id = self.get_guild(guild_id).await?;
+                let channel_db_id = match announcement.channel {
+                    Some(channel_id) => Some(self.get_channel(guild_id, channel_id).await?),
+                    None => None,
+                };
+
+                self.client
+                    .execute(
+                        "
+                        INSERT INTO score_announcements
+                        VALUES ($1::BIGINT, $2::BIGINT, $3::TEXT, $4::TEXT)
+                        ON CONFLICT (guild)
+                        DO UPDATE SET channel = $2::BIGINT, level_up = $3::TEXT,
+                        level_down = $4::TEXT
+                        ",
+                        &[
+                            &guild_db_id,
+                            &channel_db_id,
+                            &announcement.level_up,
+                            &announcement.level_down,
+                        ],
+                    )
+                    .await?;
+            }
+            None => {
+                self.client
+                    .execute(
+                        "
+                        DELETE FROM score_announcements
+                        WHERE guild = $1::BIGINT
+                        ",
+                        &[&(guild_id.0 as i64)],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn score_rewards(&self, guild_id: GuildId) -> Result<Vec<ScoreReward>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT score, role, channel, message FROM score_rewards
+                WHERE guild = $1::BIGINT
+                ORDER BY score
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(score_reward).collect())
+    }
+
+    async fn add_score_reward(
+        &self,
+        guild_id: GuildId,
+        reward: &ScoreReward,
+    ) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let role_db_id = match reward.role {
+            Some(role_id) => Some(self.get_role(guild_id, role_id).await?),
+            None => None,
+        };
+        let channel_db_id = match reward.channel {
+            Some(channel_id) => Some(self.get_channel(guild_id, channel_id).await?),
+            None => None,
+        };
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_rewards
+                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::TEXT)
+                ON CONFLICT (guild, score)
+                DO UPDATE SET role = $3::BIGINT, channel = $4::BIGINT, message = $5::TEXT
+                ",
+                &[
+                    &guild_db_id,
+                    &reward.score,
+                    &role_db_id,
+                    &channel_db_id,
+                    &reward.message,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_score_reward(
+        &self,
+        guild_id: GuildId,
+        score: i64,
+    ) -> Result<bool, KowalskiError> {
+        let modified = self
+            .client
+            .execute(
+                "
+                DELETE FROM score_rewards
+                WHERE guild = $1::BIGINT AND score = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &score],
+            )
+            .await?;
+
+        Ok(modified > 0)
+    }
+
+    async fn claim_score_reward(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        score: i64,
+    ) -> Result<bool, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let user_db_id = self.get_user(guild_id, user_id).await?;
+
+        let modified = self
+            .client
+            .execute(
+                "
+                INSERT INTO score_reward_claims
+                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT)
+                ON CONFLICT
+                DO NOTHING
+                ",
+                &[&guild_db_id, &score, &user_db_id],
+            )
+            .await?;
+
+        Ok(modified > 0)
+    }
+
+    async fn unclaim_score_reward(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        score: i64,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM score_reward_claims
+                WHERE guild = $1::BIGINT AND score = $2::BIGINT AND \"user\" = $3::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &score, &(user_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn role_cooldown(
+        &self,
+        guild_id: GuildId,
+        role_ids: &[RoleId],
+    ) -> Result<Option<i64>, KowalskiError> {
+        let role_db_ids: Vec<_> = role_ids.iter().map(|role_id| role_id.0 as i64).collect();
+
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT MIN(cooldown)
+                FROM score_cooldowns
+                WHERE guild = $1::BIGINT AND role = ANY($2::BIGINT[])
+                ",
+                &[&(guild_id.0 as i64), &role_db_ids],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn role_cooldowns(&self, guild_id: GuildId) -> Result<Vec<(RoleId, i64)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT role, cooldown FROM score_cooldowns
+                WHERE guild = $1::BIGINT
+                ORDER BY cooldown
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(RoleId, row, 0), row.get(1)))
+            .collect())
+    }
+
+    async fn set_role_cooldown(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        cooldown: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        match cooldown {
+            Some(cooldown) => {
+                let guild_db_id = self.get_guild(guild_id).await?;
+                let role_db_id = self.get_role(guild_id, role_id).await?;
+
+                self.client
+                    .execute(
+                        "
+                INSERT INTO score_cooldowns
+                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT)
+                ON CONFLICT (guild, role)
+                DO UPDATE SET cooldown = $3::BIGINT
+                ",
+                        &[&guild_db_id, &role_db_id, &cooldown],
+                    )
+                    .await?;
+            }
+            None => {
+                self.client
+                    .execute(
+                        "
+                DELETE FROM score_cooldowns
+                WHERE guild = $1::BIGINT AND role = $2::BIGINT
+                ",
+                        &[&(guild_id.0 as i64), &(role_id.0 as i64)],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn cooldown_policy(&self, guild_id: GuildId) -> Result<CooldownPolicy, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT per_receiver, downvote_cooldown, daily_budget, window_votes, window_minutes
+                FROM score_cooldown_policies
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map_or_else(CooldownPolicy::default, |row| {
+            let window_votes: Option<i64> = row.get(3);
+            let window_minutes: Option<i64> = row.get(4);
+
+            CooldownPolicy {
+                per_receiver: row.get(0),
+                downvote_cooldown: row.get(1),
+                daily_budget: row.get(2),
+                window: window_votes.zip(window_minutes),
+            }
+        }))
+    }
+
+    async fn set_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        policy: &CooldownPolicy,
+    ) -> Result<(), KowalskiError> {
+        // The default policy does not need to be stored
+        if *policy == CooldownPolicy::default() {
+            self.client
+                .execute(
+                    "
+                    DELETE FROM score_cooldown_policies
+                    WHERE guild = $1::BIGINT
+                    ",
+                    &[&(guild_id.0 as i64)],
+                )
+                .await?;
+
+            return Ok(());
+        }
+
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO score_cooldown_policies
+                VALUES ($1::BIGINT, $2::BOOLEAN, $3::BIGINT, $4::BIGINT, $5::BIGINT, $6::BIGINT)
+                ON CONFLICT (guild)
+                DO UPDATE SET per_receiver = $2::BOOLEAN, downvote_cooldown = $3::BIGINT,
+                daily_budget = $4::BIGINT, window_votes = $5::BIGINT, window_minutes = $6::BIGINT
+                ",
+                &[
+                    &guild_db_id,
+                    &policy.per_receiver,
+                    &policy.downvote_cooldown,
+                    &policy.daily_budget,
+                    &policy.window.map(|(votes, _)| votes),
+                    &policy.window.map(|(_, minutes)| minutes),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn given_votes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, KowalskiError> {
+        let row = self
+            .client
+            .query_one(
+                "
+                SELECT COUNT(*) FROM score_votes r
+                WHERE r.guild = $1::BIGINT AND user_from = $2::BIGINT AND native = true
+                AND time >= $3::TIMESTAMPTZ
+                ",
+                &[&(guild_id.0 as i64), &(user_id.0 as i64), &since],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn start_cooldown(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        target: Option<UserId>,
+        downvote: bool,
+        ends: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let user_db_id = self.get_user(guild_id, user_id).await?;
+
+        // Cooldowns which apply to votes for all users have no target
+        let target_db_id = target.map_or(0, |target| target.0 as i64);
+
+        // The select sees the cooldown as it was before, so it only finds one if it is still active
+        let row = self
+            .client
+            .query_opt(
+                "
+                WITH started AS (
+                    INSERT INTO score_user_cooldowns
+                    VALUES ($1::BIGINT, $2::BIGINT, $5::TIMESTAMPTZ, $3::BIGINT, $4::BOOLEAN)
+                    ON CONFLICT (guild, \"user\", target, downvote)
+                    DO UPDATE SET ends = $5::TIMESTAMPTZ
+                    WHERE score_user_cooldowns.ends <= NOW()
+                    RETURNING ends
+                )
+
+                SELECT ends FROM score_user_cooldowns
+                WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                AND target = $3::BIGINT AND downvote = $4::BOOLEAN
+                AND NOT EXISTS (SELECT * FROM started)
+                ",
+                &[&guild_db_id, &user_db_id, &target_db_id, &downvote, &ends],
+            )
+            .await?;
+
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn remove_expired_cooldowns(&self) -> Result<u64, KowalskiError> {
+        let removed = self
+            .client
+            .execute(
+                "
+                DELETE FROM score_user_cooldowns
+                WHERE ends <= NOW()
+                ",
+                &[],
+            )
+            .await?;
+
+        Ok(removed)
+    }
+
+    async fn moderation_rules(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ModerationRule>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT id, score, reactions, channel, max_age, action, target, role, duration
+                FROM score_moderation_rules
+                WHERE guild = $1::BIGINT
+                ORDER BY id
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(moderation_rule).collect())
+    }
+
+    async fn add_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: &ModerationRule,
+    ) -> Result<i32, KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let channel_db_id = match rule.channel {
+            Some(channel_id) => Some(self.get_channel(guild_id, channel_id).await?),
+            None => None,
+        };
+        let target_db_id = match rule.target {
+            Some(channel_id) => Some(self.get_channel(guild_id, channel_id).await?),
+            None => None,
+        };
+        let role_db_id = match rule.role {
+            Some(role_id) => Some(self.get_role(guild_id, role_id).await?),
+            None => None,
+        };
+
+        let row = self
+            .client
+            .query_one(
+                "
+                INSERT INTO score_moderation_rules
+                (guild, score, reactions, channel, max_age, action, target, role, duration)
+                VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::BIGINT, $6::TEXT,
+                $7::BIGINT, $8::BIGINT, $9::BIGINT)
+                RETURNING id
+                ",
+                &[
+                    &guild_db_id,
+                    &rule.score,
+                    &rule.reactions,
+                    &channel_db_id,
+                    &rule.max_age,
+                    &rule.action,
+                    &target_db_id,
+                    &role_db_id,
+                    &rule.duration,
+                ],
+            )
+            .await?;
+
+        Ok(row.get(0))
+    }
+
+    async fn remove_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: i32,
+    ) -> Result<bool, KowalskiError> {
+        let modified = self
+            .client
+            .execute(
+                "
+                DELETE FROM score_moderation_rules
+                WHERE guild = $1::BIGINT AND id = $2::INT
+                ",
+                &[&(guild_id.0 as i64), &rule],
+            )
+            .await?;
+
+        Ok(modified > 0)
+    }
+
+    async fn applied_moderation_rules(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<i32>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT rule FROM score_moderation_triggers
+                WHERE channel = $1::BIGINT AND message = $2::BIGINT
+                ",
+                &[&(channel_id.0 as i64), &(message_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(|row| row.get(0)).collect())
+    }
+
+    async fn trigger_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: i32,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<bool, KowalskiError> {
+        // The trigger gets removed together with the message
+        let message_db_id = self.get_message(guild_id, channel_id, message_id).await?;
+
+        let triggered = self
+            .client
+            .execute(
+                "
+                INSERT INTO score_moderation_triggers
+                VALUES ($1::INT, $2::BIGINT, $3::BIGINT, $4::BIGINT)
+                ON CONFLICT
+                DO NOTHING
+                ",
+                &[
+                    &rule,
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &message_db_id,
+                ],
+            )
+            .await?;
+
+        Ok(triggered > 0)
+    }
+
+    async fn score_decay(&self, guild_id: GuildId) -> Result<Option<ScoreDecay>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT half_life, levelups FROM score_decay
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| ScoreDecay {
+            half_life: row.get(0),
+            levelups: row.get(1),
+        }))
+    }
+
+    async fn set_score_decay(
+        &self,
+        guild_id: GuildId,
+        decay: Option<&ScoreDecay>,
+    ) -> Result<(), KowalskiError> {
+        match decay {
+            Some(decay) => {
+                let guild_db_id = self.get_guild(guild_id).await?;
+
+                self.client
+                    .execute(
+                        "
+                        INSERT INTO score_decay
+                        VALUES ($1::BIGINT, $2::BIGINT, $3::BOOLEAN)
+                        ON CONFLICT (guild)
+                        DO UPDATE SET half_life = $2::BIGINT, levelups = $3::BOOLEAN
+                        ",
+                        &[&guild_db_id, &decay.half_life, &decay.levelups],
+                    )
+                    .await?;
+            }
+            None => {
+                self.client
+                    .execute(
+                        "
+                        DELETE FROM score_decay
+                        WHERE guild = $1::BIGINT
+                        ",
+                        &[&(guild_id.0 as i64)],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn score_cap(&self, guild_id: GuildId) -> Result<Option<i64>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT cap FROM score_caps
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn set_score_cap(
+        &self,
+        guild_id: GuildId,
+        cap: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        match cap {
+            Some(cap) => {
+                let guild_db_id = self.get_guild(guild_id).await?;
+
+                self.client
+                    .execute(
+                        "
+                        INSERT INTO score_caps
+                        VALUES ($1::BIGINT, $2::BIGINT)
+                        ON CONFLICT (guild)
+                        DO UPDATE SET cap = $2::BIGINT
+                        ",
+                        &[&guild_db_id, &cap],
+                    )
+                    .await?;
+            }
+            None => {
+                self.client
+                    .execute(
+                        "
+                        DELETE FROM score_caps
+                        WHERE guild = $1::BIGINT
+                        ",
+                        &[&(guild_id.0 as i64)],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn channel_scoring(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<ChannelScoring, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT channel, score_multiplier, score_excluded FROM channels
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+                ",
+                &[&(guild_id.0 as i64), &(channel_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map_or(ChannelScoring::new(channel_id), |row| channel_scoring(&row)))
+    }
+
+    async fn channel_scorings(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ChannelScoring>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, score_multiplier, score_excluded FROM channels
+                WHERE guild = $1::BIGINT AND (score_multiplier <> 1 OR score_excluded)
+                ORDER BY channel
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(channel_scoring).collect())
+    }
+
+    async fn set_channel_scoring(
+        &self,
+        guild_id: GuildId,
+        scoring: &ChannelScoring,
+    ) -> Result<(), KowalskiError> {
+        // Make sure the channel is registered
+        let channel_db_id = self.get_channel(guild_id, scoring.channel).await?;
+
+        self.client
+            .execute(
+                "
+                UPDATE channels
+                SET score_multiplier = $3::INT, score_excluded = $4::BOOLEAN
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &channel_db_id,
+                    &scoring.multiplier,
+                    &scoring.excluded,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reaction_roles(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+    ) -> Result<Vec<ReactionRole>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, message, emoji, unicode, guild_emoji, role, slots, role_group, duration
+                FROM reaction_roles rr
+                INNER JOIN emojis e ON emoji = id
+                WHERE rr.guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                AND emoji = $4::INT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &emoji,
+                ],
+            )
+            .await?;
+
+        Ok(rows.iter().map(reaction_role).collect())
+    }
+
+    async fn message_reaction_roles(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<ReactionRole>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, message, emoji, unicode, guild_emoji, role, slots, role_group, duration
+                FROM reaction_roles rr
+                INNER JOIN emojis e ON emoji = id
+                WHERE rr.guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(rows.iter().map(reaction_role).collect())
+    }
+
+    async fn guild_reaction_roles(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ReactionRole>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, message, emoji, unicode, guild_emoji, role, slots, role_group, duration
+                FROM reaction_roles rr
+                INNER JOIN emojis e ON emoji = id
+                WHERE rr.guild = $1::BIGINT
+                ORDER BY channel, message
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(reaction_role).collect())
+    }
+
+    async fn add_reaction_role(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+        slots: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        // Get the guild, role, channel and message ids
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let role_db_id = self.get_role(guild_id, role_id).await?;
+        let channel_db_id = self.get_channel(guild_id, channel_id).await?;
+        let message_db_id = self.get_message(guild_id, channel_id, message_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO reaction_roles (guild, channel, message, emoji, role, slots)
+            VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::INT, $5::BIGINT, $6::BIGINT)
+            ON CONFLICT (guild, channel, message, emoji, role)
+            DO UPDATE SET slots = $6::BIGINT
+            ",
+                &[
+                    &guild_db_id,
+                    &channel_db_id,
+                    &message_db_id,
+                    &emoji,
+                    &role_db_id,
+                    &slots,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_reaction_role_group_of(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        role_id: RoleId,
+        group: Option<&str>,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+            UPDATE reaction_roles
+            SET role_group = $5::TEXT
+            WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+            AND message = $3::BIGINT AND role = $4::BIGINT
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &(role_id.0 as i64),
+                    &group,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_reaction_role_duration(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+        duration: Option<i64>,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+            UPDATE reaction_roles
+            SET duration = $6::BIGINT
+            WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+            AND message = $3::BIGINT AND emoji = $4::INT AND role = $5::BIGINT
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &emoji,
+                    &(role_id.0 as i64),
+                    &duration,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reaction_role_conditions(
+        &self,
+        guild_id: GuildId,
+        reaction_role: &ReactionRole,
+    ) -> Result<Vec<(RoleId, bool)>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT condition_role, required
+                FROM reaction_role_conditions
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                AND emoji = $4::INT AND role = $5::BIGINT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(reaction_role.channel.0 as i64),
+                    &(reaction_role.message.0 as i64),
+                    &reaction_role.emoji_id,
+                    &(reaction_role.role.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| (row_id!(RoleId, row, 0), row.get(1)))
+            .collect())
+    }
+
+    async fn set_reaction_role_condition(
+        &self,
+        guild_id: GuildId,
+        reaction_role: &ReactionRole,
+        role_id: RoleId,
+        required: Option<bool>,
+    ) -> Result<(), KowalskiError> {
+        match required {
+            Some(required) => {
+                // Get the role id
+                let role_db_id = self.get_role(guild_id, role_id).await?;
+
+                self.client
+                    .execute(
+                        "
+                    INSERT INTO reaction_role_conditions
+                    VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::INT, $5::BIGINT, $6::BIGINT,
+                    $7::BOOLEAN)
+                    ON CONFLICT (guild, channel, message, emoji, role, condition_role)
+                    DO UPDATE SET required = $7::BOOLEAN
+                    ",
+                        &[
+                            &(guild_id.0 as i64),
+                            &(reaction_role.channel.0 as i64),
+                            &(reaction_role.message.0 as i64),
+                            &reaction_role.emoji_id,
+                            &(reaction_role.role.0 as i64),
+                            &role_db_id,
+                            &required,
+                        ],
+                    )
+                    .await?;
+            }
+            None => {
+                self.client
+                    .execute(
+                        "
+                    DELETE FROM reaction_role_conditions
+                    WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                    AND emoji = $4::INT AND role = $5::BIGINT AND condition_role = $6::BIGINT
+                    ",
+                        &[
+                            &(guild_id.0 as i64),
+                            &(reaction_role.channel.0 as i64),
+                            &(reaction_role.message.0 as i64),
+                            &reaction_role.emoji_id,
+                            &(reaction_role.role.0 as i64),
+                            &(role_id.0 as i64),
+                        ],
+                    )
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn remove_reaction_role(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+            DELETE FROM reaction_roles
+            WHERE guild = $1::BIGINT AND channel = $2::BIGINT
+            AND message = $3::BIGINT AND emoji = $4::INT AND role = $5::BIGINT
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &emoji,
+                    &(role_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reaction_role_group(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        name: &str,
+    ) -> Result<ReactionRoleGroup, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT channel, message, role_group, min_roles, max_roles
+                FROM reaction_role_groups
+                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+                AND role_group = $4::TEXT
+                ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &name,
+                ],
+            )
+            .await?;
+
+        Ok(row.map_or_else(
+            || ReactionRoleGroup::new(channel_id, message_id, name),
+            |row| reaction_role_group(&row),
+        ))
+    }
+
+    async fn guild_reaction_role_groups(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ReactionRoleGroup>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT channel, message, role_group, min_roles, max_roles
+                FROM reaction_role_groups
+                WHERE guild = $1::BIGINT
+                ORDER BY channel, message, role_group
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(rows.iter().map(reaction_role_group).collect())
+    }
+
+    async fn set_reaction_role_group(
+        &self,
+        guild_id: GuildId,
+        group: &ReactionRoleGroup,
+    ) -> Result<(), KowalskiError> {
+        // Get the guild and message ids
+        let guild_db_id = self.get_guild(guild_id).await?;
+        let channel_db_id = self.get_channel(guild_id, group.channel).await?;
+        let message_db_id = self
+            .get_message(guild_id, group.channel, group.message)
+            .await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO reaction_role_groups
+            VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::TEXT, $5::INT, $6::INT)
+            ON CONFLICT (guild, channel, message, role_group)
+            DO UPDATE SET min_roles = $5::INT, max_roles = $6::INT
+            ",
+                &[
+                    &guild_db_id,
+                    &channel_db_id,
+                    &message_db_id,
+                    &group.name,
+                    &group.min,
+                    &group.max,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn reserve_reaction_role_slot(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<bool, KowalskiError> {
+        // The row gets locked by the update, so concurrent reservations can't take the same slot
+        let modified = self
+            .client
+            .execute(
+                "
+            UPDATE reaction_roles
+            SET slots = slots - 1
+            WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+            AND emoji = $4::INT AND role = $5::BIGINT AND (slots IS NULL OR slots > 0)
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &emoji,
+                    &(role_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(modified > 0)
+    }
+
+    async fn release_reaction_role_slot(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+            UPDATE reaction_roles
+            SET slots = slots + 1
+            WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
+            AND emoji = $4::INT AND role = $5::BIGINT AND slots IS NOT NULL
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(channel_id.0 as i64),
+                    &(message_id.0 as i64),
+                    &emoji,
+                    &(role_id.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn add_reaction_role_expiration(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reaction_role: &ReactionRole,
+        expires: DateTime<Utc>,
+    ) -> Result<(), KowalskiError> {
+        // Get the user id
+        let user_db_id = self.get_user(guild_id, user_id).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO reaction_role_expirations
+            VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::INT, $6::BIGINT, $7::TIMESTAMPTZ)
+            ON CONFLICT (guild, \"user\", channel, message, emoji, role)
+            DO UPDATE SET expires = $7::TIMESTAMPTZ
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &user_db_id,
+                    &(reaction_role.channel.0 as i64),
+                    &(reaction_role.message.0 as i64),
+                    &reaction_role.emoji_id,
+                    &(reaction_role.role.0 as i64),
+                    &expires,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_reaction_role_expiration(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reaction_role: &ReactionRole,
+    ) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+            DELETE FROM reaction_role_expirations
+            WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT AND channel = $3::BIGINT
+            AND message = $4::BIGINT AND emoji = $5::INT AND role = $6::BIGINT
+            ",
+                &[
+                    &(guild_id.0 as i64),
+                    &(user_id.0 as i64),
+                    &(reaction_role.channel.0 as i64),
+                    &(reaction_role.message.0 as i64),
+                    &reaction_role.emoji_id,
+                    &(reaction_role.role.0 as i64),
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn due_reaction_role_expirations(
+        &self,
+    ) -> Result<Vec<ReactionRoleExpiration>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                SELECT guild, \"user\", channel, message, emoji, role, expires
+                FROM reaction_role_expirations
+                WHERE expires <= NOW()
+                ",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(reaction_role_expiration).collect())
+    }
+
+    async fn complete_reaction_role_expiration(
+        &self,
+        expiration: &ReactionRoleExpiration,
+    ) -> Result<bool, KowalskiError> {
+        let removed = self
+            .client
+            .execute(
+                "
+            DELETE FROM reaction_role_expirations
+            WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT AND channel = $3::BIGINT
+            AND message = $4::BIGINT AND emoji = $5::INT AND role = $6::BIGINT
+            AND expires = $7::TIMESTAMPTZ
+            ",
+                &[
+                    &(expiration.guild.0 as i64),
+                    &(expiration.user.0 as i64),
+                    &(expiration.channel.0 as i64),
+                    &(expiration.message.0 as i64),
+                    &expiration.emoji,
+                    &(expiration.role.0 as i64),
+                    &expiration.expires,
+                ],
+            )
+            .await?;
+
+        Ok(removed > 0)
+    }
+
+    async fn add_reminder(&self, reminder: &Reminder) -> Result<(), KowalskiError> {
+        // Get guild, channel, message and user ids
+        let guild_db_id = self.get_guild(reminder.guild).await?;
+        let channel_db_id = self.get_channel(reminder.guild, reminder.channel).await?;
+        let message_db_id = self
+            .get_message(reminder.guild, reminder.channel, reminder.message)
+            .await?;
+        let user_db_id = self.get_user(reminder.guild, reminder.user).await?;
+
+        self.client
+            .execute(
+                "
+            INSERT INTO reminders
+            VALUES ($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::TIMESTAMPTZ, $6::TEXT)
+            ",
+                &[
+                    &guild_db_id,
+                    &channel_db_id,
+                    &message_db_id,
+                    &user_db_id,
+                    &reminder.time,
+                    &reminder.content,
+                ],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn take_due_reminders(&self) -> Result<Vec<Reminder>, KowalskiError> {
+        let rows = self
+            .client
+            .query(
+                "
+                DELETE FROM reminders
+                WHERE time <= NOW()
+                RETURNING guild, channel, message, \"user\", time, content
+                ",
+                &[],
+            )
+            .await?;
+
+        Ok(rows.iter().map(reminder).collect())
+    }
+
+    async fn reminders(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+    ) -> Result<Vec<Reminder>, KowalskiError> {
+        let rows = match user_id {
+            Some(user_id) => {
+                self.client
+                    .query(
+                        "
+                    SELECT guild, channel, message, \"user\", time, content
+                    FROM reminders
+                    WHERE guild = $1::BIGINT AND \"user\" = $2::BIGINT
+                    ORDER BY time
+                    ",
+                        &[&(guild_id.0 as i64), &(user_id.0 as i64)],
+                    )
+                    .await?
+            }
+            None => {
+                self.client
+                    .query(
+                        "
+                    SELECT guild, channel, message, \"user\", time, content
+                    FROM reminders
+                    WHERE guild = $1::BIGINT
+                    ORDER BY time
+                    ",
+                        &[&(guild_id.0 as i64)],
+                    )
+                    .await?
+            }
+        };
+
+        Ok(rows.iter().map(reminder).collect())
+    }
+
+    async fn publishing_id(&self, guild_id: GuildId) -> Result<Option<String>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT id FROM publishing
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    async fn publishing_guild(&self, id: &str) -> Result<Option<GuildId>, KowalskiError> {
+        let row = self
+            .client
+            .query_opt(
+                "
+                SELECT guild FROM publishing
+                WHERE id = $1::TEXT
+                ",
+                &[&id],
+            )
+            .await?;
+
+        Ok(row.map(|row| row_id!(GuildId, row, 0)))
+    }
+
+    async fn enable_publishing(&self, guild_id: GuildId, id: &str) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "
+                INSERT INTO publishing
+                VALUES($1::TEXT, $2::BIGINT)
+                ",
+                &[&id, &guild_db_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn disable_publishing(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "
+                DELETE FROM publishing
+                WHERE guild = $1::BIGINT
+                ",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn owned_guilds(&self) -> Result<Vec<GuildId>, KowalskiError> {
+        let rows = self
+            .client
+            .query("SELECT guild FROM owned_guilds", &[])
+            .await?;
+
+        Ok(rows.iter().map(|row| row_id!(GuildId, row, 0)).collect())
+    }
+
+    async fn add_owned_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        let guild_db_id = self.get_guild(guild_id).await?;
+
+        self.client
+            .execute(
+                "INSERT INTO owned_guilds VALUES ($1::BIGINT)",
+                &[&guild_db_id],
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn remove_owned_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError> {
+        self.client
+            .execute(
+                "DELETE FROM owned_guilds WHERE guild = $1::BIGINT",
+                &[&(guild_id.0 as i64)],
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+fn reaction_role(row: &Row) -> ReactionRole {
+    ReactionRole {
+        channel: row_id!(ChannelId, row, 0),
+        message: row_id!(MessageId, row, 1),
+        emoji_id: row.get(2),
+        emoji: StoredEmoji::from_columns(row.get(3), row.get(4)),
+        role: row_id!(RoleId, row, 5),
+        slots: row.get(6),
+        group: row.get(7),
+        duration: row.get(8),
+    }
+}
+
+fn reaction_role_group(row: &Row) -> ReactionRoleGroup {
+    ReactionRoleGroup {
+        channel: row_id!(ChannelId, row, 0),
+        message: row_id!(MessageId, row, 1),
+        name: row.get(2),
+        min: row.get(3),
+        max: row.get(4),
+    }
+}
+
+fn score_reward(row: &Row) -> ScoreReward {
+    let role: Option<i64> = row.get(1);
+    let channel: Option<i64> = row.get(2);
+
+    ScoreReward {
+        score: row.get(0),
+        role: role.map(|role| RoleId(role as u64)),
+        channel: channel.map(|channel| ChannelId(channel as u64)),
+        message: row.get(3),
+    }
+}
+
+fn moderation_rule(row: &Row) -> ModerationRule {
+    let channel: Option<i64> = row.get(3);
+    let target: Option<i64> = row.get(6);
+    let role: Option<i64> = row.get(7);
+
+    ModerationRule {
+        id: row.get(0),
+        score: row.get(1),
+        reactions: row.get(2),
+        channel: channel.map(|channel| ChannelId(channel as u64)),
+        max_age: row.get(4),
+        action: row.get(5),
+        target: target.map(|target| ChannelId(target as u64)),
+        role: role.map(|role| RoleId(role as u64)),
+        duration: row.get(8),
+    }
+}
+
+fn pending_drop(row: &Row) -> PendingDrop {
+    PendingDrop {
+        guild: row_id!(GuildId, row, 0),
+        user: row_id!(UserId, row, 1),
+        channel: row_id!(ChannelId, row, 2),
+        message: row_id!(MessageId, row, 3),
+        mode: row.get(4),
+        ends: row.get(5),
+    }
+}
+
+fn score_gift(row: &Row) -> ScoreGift {
+    ScoreGift {
+        id: row.get(0),
+        user_from: row_id!(UserId, row, 1),
+        user_to: row_id!(UserId, row, 2),
+        amount: row.get(3),
+        time: row.get(4),
+        undone: row.get(5),
+    }
+}
+
+fn channel_scoring(row: &Row) -> ChannelScoring {
+    ChannelScoring {
+        channel: row_id!(ChannelId, row, 0),
+        multiplier: row.get(1),
+        excluded: row.get(2),
+    }
+}
+
+fn season(row: &Row) -> Season {
+    Season {
+        number: row.get(0),
+        started: row.get(1),
+        ended: row.get(2),
+    }
+}
+
+fn score_reaction(row: &Row) -> ScoreReaction {
+    ScoreReaction {
+        guild: row_id!(GuildId, row, 0),
+        user_from: row_id!(UserId, row, 1),
+        user_to: row_id!(UserId, row, 2),
+        channel: row_id!(ChannelId, row, 3),
+        message: row_id!(MessageId, row, 4),
+        emoji: row.get(5),
+        native: row.get(6),
+        time: row.get(7),
+    }
+}
+
+fn reaction_role_expiration(row: &Row) -> ReactionRoleExpiration {
+    ReactionRoleExpiration {
+        guild: row_id!(GuildId, row, 0),
+        user: row_id!(UserId, row, 1),
+        channel: row_id!(ChannelId, row, 2),
+        message: row_id!(MessageId, row, 3),
+        emoji: row.get(4),
+        role: row_id!(RoleId, row, 5),
+        expires: row.get::<_, DateTime<Utc>>(6),
+    }
+}
+
+fn reminder(row: &Row) -> Reminder {
+    Reminder {
+        guild: row_id!(GuildId, row, 0),
+        channel: row_id!(ChannelId, row, 1),
+        message: row_id!(MessageId, row, 2),
+        user: row_id!(UserId, row, 3),
+        time: row.get::<_, DateTime<Utc>>(4),
+        content: row.get(5),
+    }
+}
@@ -0,0 +1,950 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serenity::{
+    async_trait,
+    model::{
+        channel::ReactionType,
+        id::{ChannelId, EmojiId, GuildId, MessageId, RoleId, UserId},
+    },
+};
+
+use crate::{
+    database::types::{
+        ChannelScoring, CooldownPolicy, DominantGiver, DropMode, GiftCaps, LevelUpAnnouncement,
+        ModerationRule, ModuleStatus, NewAccountBurst, PendingDrop, ReactionRole,
+        ReactionRoleExpiration, ReactionRoleGroup, ReciprocalVotes, Reminder, ScoreDecay,
+        ScoreGift, ScoreReaction, ScoreReward, Season, SeasonRank, StoredEmoji,
+    },
+    error::KowalskiError,
+};
+
+/// The storage of the bot, implemented by the Postgres database and an in-memory backend.
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Gets the id of a guild given the GuildId object.
+    ///
+    /// Note: If the guild is not registered before, it will create a new row
+    async fn get_guild(&self, guild_id: GuildId) -> Result<i64, KowalskiError>;
+
+    /// Gets the id of a user given the GuildId and UserId object.
+    ///
+    /// Note: If the guild or user is not registered before, it will create new rows
+    async fn get_user(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError>;
+
+    /// Gets the id of a role given the GuildId and RoleId object.
+    ///
+    /// Note: If the guild or role is not registered before, it will create new rows
+    async fn get_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<i64, KowalskiError>;
+
+    /// Gets the id of a channel given the GuildId and ChannelId object.
+    ///
+    /// Note: If the guild or role is not registered before, it will create new rows
+    async fn get_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets the id of a message given the GuildId and MessageId object.
+    ///
+    /// Note: If the guild or role is not registered before, it will create new rows
+    async fn get_message(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets the id of an emoji given the reaction type.
+    ///
+    /// Note: If the emoji is not registered before, it will create a new row
+    async fn get_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: &ReactionType,
+    ) -> Result<i32, KowalskiError>;
+
+    /// Gets the id of an emoji given the reaction type, if the emoji is registered.
+    async fn find_emoji(&self, emoji: &ReactionType) -> Result<Option<i32>, KowalskiError>;
+
+    /// Removes a user and all of their received reactions from a guild.
+    ///
+    /// Note: The reactions are remembered as removed, so they don't get counted again
+    async fn remove_user(&self, guild_id: GuildId, user_id: UserId) -> Result<(), KowalskiError>;
+
+    /// Gets the number of guilds a user is registered on.
+    async fn user_guild_count(&self, user_id: UserId) -> Result<i64, KowalskiError>;
+
+    /// Gets all guilds which are registered.
+    async fn guilds(&self) -> Result<Vec<GuildId>, KowalskiError>;
+
+    /// Removes a guild and all of its data.
+    async fn remove_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+
+    /// Gets all users of a guild which are registered.
+    async fn guild_users(&self, guild_id: GuildId) -> Result<Vec<UserId>, KowalskiError>;
+
+    /// Gets all channels of a guild which are registered.
+    async fn guild_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError>;
+
+    /// Removes a channel and all of its data from a guild.
+    async fn remove_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets all roles of a guild which are registered.
+    async fn guild_roles(&self, guild_id: GuildId) -> Result<Vec<RoleId>, KowalskiError>;
+
+    /// Removes a role and all of its data from a guild.
+    async fn remove_role(&self, guild_id: GuildId, role_id: RoleId) -> Result<(), KowalskiError>;
+
+    /// Removes messages of a channel and all of their data.
+    async fn remove_messages(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_ids: &[MessageId],
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets all custom emojis of a guild which are registered.
+    async fn guild_emojis(&self, guild_id: GuildId) -> Result<Vec<EmojiId>, KowalskiError>;
+
+    /// Removes a custom emoji and all of its data from a guild.
+    async fn remove_guild_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji_id: EmojiId,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the module status of a guild.
+    async fn module_status(&self, guild_id: GuildId) -> Result<ModuleStatus, KowalskiError>;
+
+    /// Gets the module status of all guilds which have a status set.
+    async fn module_statuses(&self) -> Result<Vec<(GuildId, ModuleStatus)>, KowalskiError>;
+
+    /// Sets the module status of a guild.
+    async fn set_module_status(
+        &self,
+        guild_id: GuildId,
+        status: &ModuleStatus,
+    ) -> Result<(), KowalskiError>;
+
+    /// Removes the data of the utility module of a guild.
+    async fn remove_utility_data(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+
+    /// Removes the data of the score module of a guild, including all up- and downvotes.
+    async fn remove_score_data(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+
+    /// Removes the data of the reaction-role module of a guild.
+    async fn remove_reaction_role_data(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+
+    /// Gets the weight with which an emoji counts as an upvote (or downvote) on a guild.
+    ///
+    /// Note: Returns None if the emoji is not a score emoji
+    async fn score_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: i32,
+    ) -> Result<Option<i32>, KowalskiError>;
+
+    /// Gets all score emojis of a guild and their weights.
+    async fn score_emojis(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(StoredEmoji, i32)>, KowalskiError>;
+
+    /// Adds or updates a score emoji of a guild, negative weights count as downvotes.
+    async fn set_score_emoji(
+        &self,
+        guild_id: GuildId,
+        emoji: i32,
+        weight: i32,
+    ) -> Result<(), KowalskiError>;
+
+    /// Removes a score emoji of a guild, including all reactions using it.
+    async fn remove_score_emoji(&self, guild_id: GuildId, emoji: i32) -> Result<(), KowalskiError>;
+
+    /// Adds a reaction counting as an up- or downvote.
+    ///
+    /// Note: The vote is no longer remembered as removed
+    async fn add_score_reaction(&self, reaction: &ScoreReaction) -> Result<(), KowalskiError>;
+
+    /// Removes a reaction counting as an up- or downvote.
+    ///
+    /// Returns the user who received the reaction if there was one
+    async fn remove_score_reaction(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+    ) -> Result<Option<UserId>, KowalskiError>;
+
+    /// Gets all reactions counting as up- or downvotes on a message.
+    async fn message_score_reactions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<ScoreReaction>, KowalskiError>;
+
+    /// Gets the users and emojis of the votes on a message which were removed by the bot.
+    async fn removed_votes(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<(UserId, i32)>, KowalskiError>;
+
+    /// Gets all messages of a guild which are registered.
+    async fn tracked_messages(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(ChannelId, MessageId)>, KowalskiError>;
+
+    /// Gets all channels of a guild in which messages received up- or downvotes.
+    async fn score_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError>;
+
+    /// Removes all reactions counting as up- or downvotes from a message.
+    async fn remove_message_score_reactions(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the score of a user.
+    async fn user_score(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError>;
+
+    /// Gets the score of a user, halving the weight of reactions every `half_life` days.
+    async fn user_decayed_score(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        half_life: i64,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets the scores of all users of a guild with their up- and downvotes since a certain time,
+    /// halving the weight of reactions every `half_life` days, highest score first.
+    async fn guild_scores(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(UserId, i64, i64)>, KowalskiError>;
+
+    /// Gets the up- and downvotes given by the users of a guild with the gifted part, most given
+    /// first.
+    async fn guild_givens(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError>;
+
+    /// Gets how often each emoji was used to vote for a user since a certain time, the most used
+    /// first.
+    ///
+    /// Note: Without a guild, the votes of all guilds are counted
+    async fn received_emojis(
+        &self,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+        start: DateTime<Utc>,
+    ) -> Result<Vec<(StoredEmoji, i64)>, KowalskiError>;
+
+    /// Gets how often a user has used each emoji to vote, the most used first.
+    ///
+    /// Note: Without a guild, the votes of all guilds are counted
+    async fn given_emojis(
+        &self,
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+    ) -> Result<Vec<(StoredEmoji, i64)>, KowalskiError>;
+
+    /// Gets the score a user has received in each channel since a certain time together with the
+    /// multiplier of the channel, halving the weight of reactions every `half_life` days, highest
+    /// score first.
+    async fn user_channel_scores(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(ChannelId, i32, i64)>, KowalskiError>;
+
+    /// Gets the up- and downvotes every user has natively given a user since a certain time,
+    /// halving the weight of reactions every `half_life` days, highest score first.
+    async fn user_voters(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        start: DateTime<Utc>,
+        half_life: f64,
+    ) -> Result<Vec<(UserId, i64, i64)>, KowalskiError>;
+
+    /// Gets the up- and downvotes a user has given every user with the gifted part, highest score
+    /// first.
+    async fn user_recipients(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError>;
+
+    /// Gets the up- and downvotes a user has received on all guilds with the rank of the user.
+    ///
+    /// Note: The rank is None if the user has never received a vote
+    async fn global_user_score(
+        &self,
+        user_id: UserId,
+    ) -> Result<(i64, i64, Option<i64>), KowalskiError>;
+
+    /// Gets the up- and downvotes a user has given on all guilds with the rank of the user.
+    ///
+    /// Note: The rank is None if the user has never given a vote
+    async fn global_user_givens(
+        &self,
+        user_id: UserId,
+    ) -> Result<(i64, i64, Option<i64>), KowalskiError>;
+
+    /// Gets the score of a message.
+    async fn message_score(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets the score a user has received through upvotes.
+    async fn user_upvotes(&self, guild_id: GuildId, user_id: UserId) -> Result<i64, KowalskiError>;
+
+    /// Gets the score one user has given another user through native upvotes.
+    async fn pair_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets the pairs of users who have both given each other at least a certain score through
+    /// native upvotes since a certain time, the pairs with the highest lower score first.
+    async fn reciprocal_votes(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        min_score: i64,
+        limit: i64,
+    ) -> Result<Vec<ReciprocalVotes>, KowalskiError>;
+
+    /// Gets the users who have given another user at least a certain score and share (in percent)
+    /// of the score received through native upvotes since a certain time, the largest share first.
+    async fn dominant_givers(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        min_score: i64,
+        min_share: i64,
+        limit: i64,
+    ) -> Result<Vec<DominantGiver>, KowalskiError>;
+
+    /// Gets the hours since a certain time in which accounts younger than the given amount of days
+    /// have given at least a certain number of native upvotes, the most upvotes first.
+    ///
+    /// Note: The age of an account is derived from its id
+    async fn new_account_bursts(
+        &self,
+        guild_id: GuildId,
+        start: DateTime<Utc>,
+        account_age: i32,
+        min_upvotes: i64,
+        limit: i64,
+    ) -> Result<Vec<NewAccountBurst>, KowalskiError>;
+
+    /// Moves upvotes worth at most the given score from one user to another,
+    /// preferring upvotes which were not given natively and small ones.
+    ///
+    /// As upvotes can't be split, moving stops before the upvote which would exceed the score.
+    /// The limits are pairs of a time and the score which may be gifted since then at most,
+    /// they are checked together with moving the upvotes.
+    ///
+    /// Note: The gift gets recorded, so it can be undone later
+    ///
+    /// Returns the score moved
+    async fn gift_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+        amount: i64,
+        limits: &[(DateTime<Utc>, i64)],
+    ) -> Result<u64, KowalskiError>;
+
+    /// Gets the score a user has gifted since a certain time, not counting gifts which were undone.
+    async fn gifted_score(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets the latest gifts of a guild, optionally only the ones a user has given or received.
+    async fn score_gifts(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+        limit: i64,
+    ) -> Result<Vec<ScoreGift>, KowalskiError>;
+
+    /// Moves the upvotes of a gift back to the user who gifted them,
+    /// skipping the ones which were removed in the meantime.
+    ///
+    /// Returns the gift and the score moved back if the gift was not undone before
+    async fn undo_gift(
+        &self,
+        guild_id: GuildId,
+        gift: i32,
+    ) -> Result<Option<(ScoreGift, u64)>, KowalskiError>;
+
+    /// Gets the maximum score the users of a guild can gift per day and per week.
+    async fn gift_caps(&self, guild_id: GuildId) -> Result<GiftCaps, KowalskiError>;
+
+    /// Sets the maximum score the users of a guild can gift per day and per week.
+    async fn set_gift_caps(&self, guild_id: GuildId, caps: &GiftCaps) -> Result<(), KowalskiError>;
+
+    /// Moves all reactions one user has received to another user.
+    ///
+    /// Returns the amount of reactions moved
+    async fn transfer_score(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        user_to: UserId,
+    ) -> Result<u64, KowalskiError>;
+
+    /// Archives the final ranks of the current season of a guild and resets all of its scores.
+    ///
+    /// Returns the season which ended
+    async fn end_season(&self, guild_id: GuildId) -> Result<Season, KowalskiError>;
+
+    /// Moves the reactions one user has received to other users, distributing the score evenly.
+    ///
+    /// Returns the amount of reactions moved
+    async fn split_score(
+        &self,
+        guild_id: GuildId,
+        user_from: UserId,
+        users: &[UserId],
+    ) -> Result<u64, KowalskiError>;
+
+    /// Credits the users who gave a user upvotes with the score of them, the reactions stay as
+    /// they are. Users who have left the guild get nothing back.
+    ///
+    /// Returns the score returned
+    async fn return_upvotes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Gets how the score of users leaving a guild gets dropped.
+    async fn drop_mode(&self, guild_id: GuildId) -> Result<DropMode, KowalskiError>;
+
+    /// Sets how the score of users leaving a guild gets dropped.
+    async fn set_drop_mode(&self, guild_id: GuildId, mode: DropMode) -> Result<(), KowalskiError>;
+
+    /// Gets the channels of a guild into which the score of users leaving the guild can drop.
+    async fn drop_channels(&self, guild_id: GuildId) -> Result<Vec<ChannelId>, KowalskiError>;
+
+    /// Gets a random channel of a guild into which the score of users leaving the guild can drop.
+    async fn random_drop_channel(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<ChannelId>, KowalskiError>;
+
+    /// Lets the score of users leaving a guild drop into a channel.
+    async fn add_drop_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<(), KowalskiError>;
+
+    /// Stops the score of users leaving a guild from dropping into a channel.
+    ///
+    /// Returns whether the score could drop into the channel before
+    async fn remove_drop_channel(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Adds a drop waiting to be picked up.
+    async fn add_pending_drop(&self, drop: &PendingDrop) -> Result<(), KowalskiError>;
+
+    /// Gets the drop of the score of a user if it is still waiting to be picked up.
+    async fn pending_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<PendingDrop>, KowalskiError>;
+
+    /// Gets all drops whose time to be picked up is over.
+    async fn due_drops(&self) -> Result<Vec<PendingDrop>, KowalskiError>;
+
+    /// Adds a user to the ones picking up the drop of the score of a user.
+    ///
+    /// Returns whether the user was added, i.e. the drop is pending and the user did not join before
+    async fn join_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        participant: UserId,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Removes the drop of the score of a user, so it can only be handed out once.
+    ///
+    /// Returns the drop and the users who picked it up if it was still pending
+    async fn take_pending_drop(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+    ) -> Result<Option<(PendingDrop, Vec<UserId>)>, KowalskiError>;
+
+    /// Gets the time the current season of a guild started at.
+    ///
+    /// Note: Returns None if no season has ended yet
+    async fn season_start(&self, guild_id: GuildId)
+        -> Result<Option<DateTime<Utc>>, KowalskiError>;
+
+    /// Gets all past seasons of a guild, the latest first.
+    async fn seasons(&self, guild_id: GuildId) -> Result<Vec<Season>, KowalskiError>;
+
+    /// Gets the final ranks of a past season of a guild, the best first.
+    async fn season_ranks(
+        &self,
+        guild_id: GuildId,
+        season: i32,
+    ) -> Result<Vec<SeasonRank>, KowalskiError>;
+
+    /// Records the current (decayed) score of every user who received reactions as the score of today.
+    ///
+    /// Returns the amount of snapshots taken
+    async fn snapshot_scores(&self) -> Result<u64, KowalskiError>;
+
+    /// Gets the daily score snapshots of users since a certain day, ordered by day.
+    async fn score_snapshots(
+        &self,
+        guild_id: GuildId,
+        users: &[UserId],
+        since: NaiveDate,
+    ) -> Result<Vec<(UserId, NaiveDate, i64)>, KowalskiError>;
+
+    /// Gets all level-up roles of a guild and their required score, ordered by score.
+    async fn level_up_roles(&self, guild_id: GuildId) -> Result<Vec<(RoleId, i64)>, KowalskiError>;
+
+    /// Gets whether users keep the level-up roles of all scores they reached,
+    /// instead of only the ones of the highest score.
+    async fn level_up_stacking(&self, guild_id: GuildId) -> Result<bool, KowalskiError>;
+
+    /// Sets whether users keep the level-up roles of all scores they reached.
+    async fn set_level_up_stacking(
+        &self,
+        guild_id: GuildId,
+        stacking: bool,
+    ) -> Result<(), KowalskiError>;
+
+    /// Adds a level-up role, assigned on a certain score.
+    async fn add_level_up_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        score: i64,
+    ) -> Result<(), KowalskiError>;
+
+    /// Removes a level-up role.
+    ///
+    /// Returns whether there was a level-up role to remove
+    async fn remove_level_up_role(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        score: i64,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Gets how the level-ups and level-downs of a guild get announced if they do.
+    async fn level_up_announcement(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Option<LevelUpAnnouncement>, KowalskiError>;
+
+    /// Sets how the level-ups and level-downs of a guild get announced
+    /// (disable the announcements if no announcement is given).
+    async fn set_level_up_announcement(
+        &self,
+        guild_id: GuildId,
+        announcement: Option<&LevelUpAnnouncement>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets all score rewards of a guild, ordered by score.
+    async fn score_rewards(&self, guild_id: GuildId) -> Result<Vec<ScoreReward>, KowalskiError>;
+
+    /// Adds a score reward to a guild, replacing the one of the same score.
+    async fn add_score_reward(
+        &self,
+        guild_id: GuildId,
+        reward: &ScoreReward,
+    ) -> Result<(), KowalskiError>;
+
+    /// Removes the score reward of a score.
+    ///
+    /// Returns whether there was a score reward to remove
+    async fn remove_score_reward(
+        &self,
+        guild_id: GuildId,
+        score: i64,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Claims the score reward of a score for a user.
+    ///
+    /// Returns whether the user did not claim the reward before
+    async fn claim_score_reward(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        score: i64,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Gives the claim of a score reward back, so the user can receive it again.
+    async fn unclaim_score_reward(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        score: i64,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the smallest reaction cooldown of the given roles in seconds,
+    /// if any of them has a custom one.
+    async fn role_cooldown(
+        &self,
+        guild_id: GuildId,
+        role_ids: &[RoleId],
+    ) -> Result<Option<i64>, KowalskiError>;
+
+    /// Gets all roles with a custom reaction cooldown, ordered by the cooldown.
+    async fn role_cooldowns(&self, guild_id: GuildId) -> Result<Vec<(RoleId, i64)>, KowalskiError>;
+
+    /// Sets the reaction cooldown of a role in seconds (reset it if no cooldown is given).
+    async fn set_role_cooldown(
+        &self,
+        guild_id: GuildId,
+        role_id: RoleId,
+        cooldown: Option<i64>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the rules limiting how often the users of a guild can vote.
+    async fn cooldown_policy(&self, guild_id: GuildId) -> Result<CooldownPolicy, KowalskiError>;
+
+    /// Sets the rules limiting how often the users of a guild can vote.
+    async fn set_cooldown_policy(
+        &self,
+        guild_id: GuildId,
+        policy: &CooldownPolicy,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the amount of votes a user has given since a certain time.
+    async fn given_votes(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        since: DateTime<Utc>,
+    ) -> Result<i64, KowalskiError>;
+
+    /// Starts the reaction cooldown of a user unless one is active already.
+    /// The cooldown can be limited to votes for a single user and to downvotes.
+    ///
+    /// Returns the end of the cooldown which is already active
+    async fn start_cooldown(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        target: Option<UserId>,
+        downvote: bool,
+        ends: DateTime<Utc>,
+    ) -> Result<Option<DateTime<Utc>>, KowalskiError>;
+
+    /// Removes all reaction cooldowns which are over.
+    ///
+    /// Returns the amount of cooldowns removed
+    async fn remove_expired_cooldowns(&self) -> Result<u64, KowalskiError>;
+
+    /// Gets the auto-moderation rules of a guild, ordered by their creation.
+    async fn moderation_rules(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ModerationRule>, KowalskiError>;
+
+    /// Adds an auto-moderation rule to a guild, ignoring the id of the given rule.
+    ///
+    /// Returns the id of the new rule
+    async fn add_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: &ModerationRule,
+    ) -> Result<i32, KowalskiError>;
+
+    /// Removes an auto-moderation rule of a guild.
+    ///
+    /// Returns whether there was a rule to remove
+    async fn remove_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: i32,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Gets the ids of the auto-moderation rules already applied to a message.
+    async fn applied_moderation_rules(
+        &self,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<i32>, KowalskiError>;
+
+    /// Marks an auto-moderation rule as applied to a message.
+    ///
+    /// Returns whether the rule was not applied to the message before
+    async fn trigger_moderation_rule(
+        &self,
+        guild_id: GuildId,
+        rule: i32,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Gets the decay of the scores of a guild if it is enabled.
+    async fn score_decay(&self, guild_id: GuildId) -> Result<Option<ScoreDecay>, KowalskiError>;
+
+    /// Sets the decay of the scores of a guild (disable it if no decay is given).
+    async fn set_score_decay(
+        &self,
+        guild_id: GuildId,
+        decay: Option<&ScoreDecay>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the maximum score a single user can give another user if it is limited.
+    async fn score_cap(&self, guild_id: GuildId) -> Result<Option<i64>, KowalskiError>;
+
+    /// Sets the maximum score a single user can give another user (remove the limit if no cap is given).
+    async fn set_score_cap(&self, guild_id: GuildId, cap: Option<i64>)
+        -> Result<(), KowalskiError>;
+
+    /// Gets how reactions in a channel count towards the score.
+    async fn channel_scoring(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+    ) -> Result<ChannelScoring, KowalskiError>;
+
+    /// Gets all channels of a guild in which reactions don't count normally.
+    async fn channel_scorings(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ChannelScoring>, KowalskiError>;
+
+    /// Sets how reactions in a channel count towards the score.
+    async fn set_channel_scoring(
+        &self,
+        guild_id: GuildId,
+        scoring: &ChannelScoring,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the reaction-roles of a message bound to a certain emoji.
+    async fn reaction_roles(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+    ) -> Result<Vec<ReactionRole>, KowalskiError>;
+
+    /// Gets all reaction-roles of a message.
+    async fn message_reaction_roles(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+    ) -> Result<Vec<ReactionRole>, KowalskiError>;
+
+    /// Gets all reaction-roles of a guild, ordered by channel and message.
+    async fn guild_reaction_roles(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ReactionRole>, KowalskiError>;
+
+    /// Adds a reaction-role or updates its slots if it exists already.
+    async fn add_reaction_role(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+        slots: Option<i64>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Sets the group of a role on a message (remove it from its group if no group is given).
+    async fn set_reaction_role_group_of(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        role_id: RoleId,
+        group: Option<&str>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Sets the duration in seconds after which a reaction-role gets removed again (keep it forever
+    /// if no duration is given).
+    async fn set_reaction_role_duration(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+        duration: Option<i64>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets the roles a user has to hold (required) or must not hold to get a reaction-role.
+    async fn reaction_role_conditions(
+        &self,
+        guild_id: GuildId,
+        reaction_role: &ReactionRole,
+    ) -> Result<Vec<(RoleId, bool)>, KowalskiError>;
+
+    /// Sets whether a role is required or forbidden to get a reaction-role (remove the condition
+    /// if neither is given).
+    async fn set_reaction_role_condition(
+        &self,
+        guild_id: GuildId,
+        reaction_role: &ReactionRole,
+        role_id: RoleId,
+        required: Option<bool>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Removes a reaction-role.
+    async fn remove_reaction_role(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets a reaction-role group of a message.
+    ///
+    /// Note: If the limits of the group were never set, the default limits are returned
+    async fn reaction_role_group(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        name: &str,
+    ) -> Result<ReactionRoleGroup, KowalskiError>;
+
+    /// Gets all reaction-role groups of a guild with custom limits, ordered by channel and message.
+    async fn guild_reaction_role_groups(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<ReactionRoleGroup>, KowalskiError>;
+
+    /// Sets the limits of a reaction-role group.
+    async fn set_reaction_role_group(
+        &self,
+        guild_id: GuildId,
+        group: &ReactionRoleGroup,
+    ) -> Result<(), KowalskiError>;
+
+    /// Takes one of the available slots of a reaction-role.
+    ///
+    /// Returns whether a slot was reserved, unlimited reaction-roles always have a slot available
+    async fn reserve_reaction_role_slot(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Gives back a previously reserved slot of a reaction-role.
+    async fn release_reaction_role_slot(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_id: MessageId,
+        emoji: i32,
+        role_id: RoleId,
+    ) -> Result<(), KowalskiError>;
+
+    /// Adds or renews the expiration of a reaction-role held by a user.
+    async fn add_reaction_role_expiration(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reaction_role: &ReactionRole,
+        expires: DateTime<Utc>,
+    ) -> Result<(), KowalskiError>;
+
+    /// Removes the expiration of a reaction-role held by a user.
+    async fn remove_reaction_role_expiration(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        reaction_role: &ReactionRole,
+    ) -> Result<(), KowalskiError>;
+
+    /// Gets all reaction-role expirations which are due.
+    async fn due_reaction_role_expirations(
+        &self,
+    ) -> Result<Vec<ReactionRoleExpiration>, KowalskiError>;
+
+    /// Removes a reaction-role expiration once its role was taken away, unless it got renewed.
+    ///
+    /// Returns whether the expiration was removed
+    async fn complete_reaction_role_expiration(
+        &self,
+        expiration: &ReactionRoleExpiration,
+    ) -> Result<bool, KowalskiError>;
+
+    /// Adds a reminder.
+    async fn add_reminder(&self, reminder: &Reminder) -> Result<(), KowalskiError>;
+
+    /// Removes and returns all reminders which are due.
+    async fn take_due_reminders(&self) -> Result<Vec<Reminder>, KowalskiError>;
+
+    /// Gets the reminders of a guild (or a user of the guild), ordered by time.
+    async fn reminders(
+        &self,
+        guild_id: GuildId,
+        user_id: Option<UserId>,
+    ) -> Result<Vec<Reminder>, KowalskiError>;
+
+    /// Gets the id under which the events of a guild are published.
+    async fn publishing_id(&self, guild_id: GuildId) -> Result<Option<String>, KowalskiError>;
+
+    /// Gets the guild publishing its events under a certain id.
+    async fn publishing_guild(&self, id: &str) -> Result<Option<GuildId>, KowalskiError>;
+
+    /// Publishes the events of a guild under a certain id.
+    async fn enable_publishing(&self, guild_id: GuildId, id: &str) -> Result<(), KowalskiError>;
+
+    /// Stops publishing the events of a guild.
+    async fn disable_publishing(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+
+    /// Gets all guilds the bot has created and still owns.
+    async fn owned_guilds(&self) -> Result<Vec<GuildId>, KowalskiError>;
+
+    /// Adds a guild the bot has created.
+    async fn add_owned_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+
+    /// Removes a guild the bot no longer owns.
+    async fn remove_owned_guild(&self, guild_id: GuildId) -> Result<(), KowalskiError>;
+}
@@ -0,0 +1,193 @@
+use std::{
+    fs::File,
+    future::Future,
+    io::{self, BufReader},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use rustls::{
+    client::{InvalidDnsNameError, ServerName},
+    Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore,
+};
+use rustls_pemfile::Item;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_postgres::tls::{self, ChannelBinding, MakeTlsConnect, TlsConnect};
+use tokio_rustls::{client, TlsConnector};
+
+use crate::{
+    config::DatabaseConfig,
+    error::KowalskiError,
+    strings::{ERR_TLS_CERTIFICATE, ERR_TLS_KEY, ERR_TLS_KEY_PAIR},
+};
+
+/// Creates TLS connections to the database, using the same rustls version as the Discord client.
+#[derive(Clone)]
+pub struct MakeRustlsConnect {
+    config: Arc<ClientConfig>,
+}
+
+/// Creates a single TLS connection to the database host.
+pub struct RustlsConnect {
+    hostname: ServerName,
+    connector: TlsConnector,
+}
+
+/// A TLS connection to the database.
+pub struct RustlsStream<S>(client::TlsStream<S>);
+
+impl<S> MakeTlsConnect<S> for MakeRustlsConnect
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Stream = RustlsStream<S>;
+    type TlsConnect = RustlsConnect;
+    type Error = InvalidDnsNameError;
+
+    fn make_tls_connect(&mut self, hostname: &str) -> Result<Self::TlsConnect, Self::Error> {
+        Ok(RustlsConnect {
+            hostname: ServerName::try_from(hostname)?,
+            connector: TlsConnector::from(self.config.clone()),
+        })
+    }
+}
+
+impl<S> TlsConnect<S> for RustlsConnect
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Stream = RustlsStream<S>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<RustlsStream<S>>> + Send>>;
+
+    fn connect(self, stream: S) -> Self::Future {
+        let connect = self.connector.connect(self.hostname, stream);
+
+        Box::pin(async move { connect.await.map(RustlsStream) })
+    }
+}
+
+impl<S> tls::TlsStream for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn channel_binding(&self) -> ChannelBinding {
+        // The hash of the binding depends on the signature of the certificate, which we don't parse
+        ChannelBinding::none()
+    }
+}
+
+impl<S> AsyncRead for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+/// Create a TLS connector for the database connection.
+///
+/// Note: If no custom CA bundle is configured, the Mozilla root certificates are trusted
+pub fn create_connector(settings: &DatabaseConfig) -> Result<MakeRustlsConnect, KowalskiError> {
+    // Get the certificates to trust
+    let mut roots = RootCertStore::empty();
+    match &settings.ca_certificate {
+        Some(path) => {
+            for certificate in read_certificates(path)? {
+                roots
+                    .add(&certificate)
+                    .map_err(|why| KowalskiError::TlsError(format!("{}: {}", path, why)))?;
+            }
+        }
+        None => {
+            roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|anchor| {
+                OwnedTrustAnchor::from_subject_spki_name_constraints(
+                    anchor.subject,
+                    anchor.spki,
+                    anchor.name_constraints,
+                )
+            }))
+        }
+    }
+
+    let builder = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+
+    // Authenticate using a client certificate if there is one
+    let config = match (&settings.client_certificate, &settings.client_key) {
+        (Some(certificate_path), Some(key_path)) => builder
+            .with_single_cert(read_certificates(certificate_path)?, read_key(key_path)?)
+            .map_err(|why| KowalskiError::TlsError(why.to_string()))?,
+        (None, None) => builder.with_no_client_auth(),
+        _ => return Err(KowalskiError::TlsError(ERR_TLS_KEY_PAIR.to_string())),
+    };
+
+    Ok(MakeRustlsConnect {
+        config: Arc::new(config),
+    })
+}
+
+fn read_certificates(path: &str) -> Result<Vec<Certificate>, KowalskiError> {
+    let mut reader = open(path)?;
+
+    let certificates = rustls_pemfile::certs(&mut reader)
+        .map_err(|why| KowalskiError::TlsError(format!("{}: {}", path, why)))?;
+
+    if certificates.is_empty() {
+        return Err(KowalskiError::TlsError(format!(
+            "{}: {}",
+            ERR_TLS_CERTIFICATE, path
+        )));
+    }
+
+    Ok(certificates.into_iter().map(Certificate).collect())
+}
+
+fn read_key(path: &str) -> Result<PrivateKey, KowalskiError> {
+    let mut reader = open(path)?;
+
+    // Use the first private key of the file, no matter its format
+    rustls_pemfile::read_all(&mut reader)
+        .map_err(|why| KowalskiError::TlsError(format!("{}: {}", path, why)))?
+        .into_iter()
+        .find_map(|item| match item {
+            Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .ok_or_else(|| KowalskiError::TlsError(format!("{}: {}", ERR_TLS_KEY, path)))
+}
+
+fn open(path: &str) -> Result<BufReader<File>, KowalskiError> {
+    let file =
+        File::open(path).map_err(|why| KowalskiError::TlsError(format!("{}: {}", path, why)))?;
+
+    Ok(BufReader::new(file))
+}
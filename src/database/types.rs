@@ -4,7 +4,7 @@ use bit_vec::BitVec;
 use chrono::{DateTime, Utc};
 use serenity::{
     client::Context,
-    model::id::{GuildId, RoleId, UserId},
+    model::id::{ChannelId, EmojiId, GuildId, MessageId, RoleId, UserId},
 };
 use tabled::{builder::Builder, Style, Table};
 use tokio_postgres::{
@@ -22,6 +22,243 @@ pub struct ModuleStatus {
     pub analyze: bool,
 }
 
+/// An emoji as it is stored in the database.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StoredEmoji {
+    Unicode(String),
+    Guild(EmojiId),
+}
+
+/// A reaction counting as an up- or downvote.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreReaction {
+    pub guild: GuildId,
+    pub user_from: UserId,
+    pub user_to: UserId,
+    pub channel: ChannelId,
+    pub message: MessageId,
+    pub emoji: i32,
+    pub native: bool,
+    pub time: DateTime<Utc>,
+}
+
+/// The decay of the scores of a guild, halving the weight of a reaction every `half_life` days.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreDecay {
+    pub half_life: i64,
+    pub levelups: bool,
+}
+
+/// The rules limiting how often the users of a guild can vote.
+///
+/// Note: The cooldown itself is defined by the roles of a user
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CooldownPolicy {
+    /// Whether the cooldown only applies to voting the same user again.
+    pub per_receiver: bool,
+    /// The cooldown of downvotes in seconds, separate from the one of upvotes.
+    pub downvote_cooldown: Option<i64>,
+    /// The maximum amount of votes per day.
+    pub daily_budget: Option<i64>,
+    /// The maximum amount of votes within a number of minutes.
+    pub window: Option<(i64, i64)>,
+}
+
+/// How the level-ups and level-downs of the users of a guild get announced.
+///
+/// Note: The messages get sent as direct messages if there is no channel
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelUpAnnouncement {
+    pub channel: Option<ChannelId>,
+    pub level_up: Option<String>,
+    pub level_down: Option<String>,
+}
+
+/// A reward users receive once, the first time they reach a score.
+///
+/// Note: The role is kept even if the score is lost again
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreReward {
+    pub score: i64,
+    pub role: Option<RoleId>,
+    pub channel: Option<ChannelId>,
+    pub message: Option<String>,
+}
+
+/// A part of the score one user has gifted another user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoreGift {
+    pub id: i32,
+    pub user_from: UserId,
+    pub user_to: UserId,
+    pub amount: i64,
+    pub time: DateTime<Utc>,
+    pub undone: bool,
+}
+
+/// The maximum score a user of a guild can gift per day and per week.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GiftCaps {
+    pub daily: Option<i64>,
+    pub weekly: Option<i64>,
+}
+
+/// How the score of a user leaving a guild gets handed out to the users picking it up.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DropMode {
+    /// The first user picking up the score gets all of it.
+    #[default]
+    FirstPickup,
+    /// A random user out of the ones picking up the score gets all of it.
+    Lottery,
+    /// The score gets split evenly among the users picking it up.
+    Split,
+    /// The upvotes get returned to the users who gave them, no one has to pick them up.
+    ReturnToGivers,
+}
+
+/// A drop of the score of a user who left a guild, waiting to be picked up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingDrop {
+    pub guild: GuildId,
+    pub user: UserId,
+    pub channel: ChannelId,
+    pub message: MessageId,
+    pub mode: DropMode,
+    pub ends: DateTime<Utc>,
+}
+
+/// What happens to a message matching an auto-moderation rule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ModerationAction {
+    /// Pin the message.
+    Pin,
+    /// Delete the message.
+    Delete,
+    /// Post a copy of the message in the target channel.
+    Repost,
+    /// Tell the moderators about the message in the target channel.
+    Notify,
+    /// Lock the thread the message was sent in.
+    Lock,
+    /// Time out the author of the message for the duration of the rule.
+    Timeout,
+}
+
+/// A rule of the auto-moderation of a guild, applied once a message matches all of its conditions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModerationRule {
+    pub id: i32,
+    pub score: Option<i64>,
+    pub reactions: Option<i64>,
+    pub channel: Option<ChannelId>,
+    /// The maximum age of the message in hours.
+    pub max_age: Option<i64>,
+    pub action: ModerationAction,
+    pub target: Option<ChannelId>,
+    pub role: Option<RoleId>,
+    /// The duration of a timeout in minutes.
+    pub duration: Option<i64>,
+}
+
+/// How reactions in a channel count towards the score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelScoring {
+    pub channel: ChannelId,
+    pub multiplier: i32,
+    pub excluded: bool,
+}
+
+/// A finished season of the scores of a guild.
+///
+/// Note: The first season of a guild has no start
+#[derive(Clone, Debug, PartialEq)]
+pub struct Season {
+    pub number: i32,
+    pub started: Option<DateTime<Utc>>,
+    pub ended: DateTime<Utc>,
+}
+
+/// The final rank of a user in a season.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeasonRank {
+    pub user: UserId,
+    pub rank: i64,
+    pub score: i64,
+    pub upvotes: i64,
+    pub downvotes: i64,
+}
+
+/// Two users who upvote each other a lot.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReciprocalVotes {
+    pub users: (UserId, UserId),
+    pub scores: (i64, i64),
+}
+
+/// A user who has given another user most of their score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DominantGiver {
+    pub user_from: UserId,
+    pub user_to: UserId,
+    pub score: i64,
+    pub total: i64,
+}
+
+/// A new account which has given a lot of upvotes within an hour.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewAccountBurst {
+    pub user: UserId,
+    pub hour: DateTime<Utc>,
+    pub upvotes: i64,
+}
+
+/// A role assigned when reacting to a message with a certain emoji.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReactionRole {
+    pub channel: ChannelId,
+    pub message: MessageId,
+    pub emoji_id: i32,
+    pub emoji: StoredEmoji,
+    pub role: RoleId,
+    pub slots: Option<i32>,
+    pub group: Option<String>,
+    pub duration: Option<i64>,
+}
+
+/// A group of reaction-roles on a message, of which users can only hold a limited number.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReactionRoleGroup {
+    pub channel: ChannelId,
+    pub message: MessageId,
+    pub name: String,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// A reaction-role held by a user which gets removed at a certain time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReactionRoleExpiration {
+    pub guild: GuildId,
+    pub user: UserId,
+    pub channel: ChannelId,
+    pub message: MessageId,
+    pub emoji: i32,
+    pub role: RoleId,
+    pub expires: DateTime<Utc>,
+}
+
+/// A reminder scheduled by a user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reminder {
+    pub guild: GuildId,
+    pub channel: ChannelId,
+    pub message: MessageId,
+    pub user: UserId,
+    pub time: DateTime<Utc>,
+    pub content: String,
+}
+
 /// A table with all fields resolved to a String.
 pub struct TableResolved {
     header: Vec<String>,
@@ -45,6 +282,93 @@ impl ModuleStatus {
     }
 }
 
+impl ChannelScoring {
+    /// Create the scoring of a channel in which reactions count normally.
+    pub fn new(channel: ChannelId) -> Self {
+        ChannelScoring {
+            channel,
+            multiplier: 1,
+            excluded: false,
+        }
+    }
+}
+
+impl ReactionRoleGroup {
+    /// Create a group with the default limits, allowing users to hold at most one of its roles.
+    pub fn new(channel: ChannelId, message: MessageId, name: &str) -> Self {
+        ReactionRoleGroup {
+            channel,
+            message,
+            name: name.to_string(),
+            min: 0,
+            max: 1,
+        }
+    }
+}
+
+impl StoredEmoji {
+    /// Create the stored emoji from the unicode and guild_emoji columns of the emojis table.
+    pub fn from_columns(unicode: Option<String>, guild_emoji: Option<i64>) -> Self {
+        match (unicode, guild_emoji) {
+            (Some(string), _) => StoredEmoji::Unicode(string),
+            (_, Some(id)) => StoredEmoji::Guild(EmojiId(id as u64)),
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl DropMode {
+    /// Gets the name of the mode, as it is stored and chosen in commands.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DropMode::FirstPickup => "first pickup",
+            DropMode::Lottery => "lottery",
+            DropMode::Split => "split",
+            DropMode::ReturnToGivers => "return to givers",
+        }
+    }
+
+    /// Gets the mode with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            DropMode::FirstPickup,
+            DropMode::Lottery,
+            DropMode::Split,
+            DropMode::ReturnToGivers,
+        ]
+        .into_iter()
+        .find(|mode| mode.name() == name)
+    }
+}
+
+impl ModerationAction {
+    /// Gets the name of the action, as it is stored and chosen in commands.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModerationAction::Pin => "pin",
+            ModerationAction::Delete => "delete",
+            ModerationAction::Repost => "repost",
+            ModerationAction::Notify => "notify",
+            ModerationAction::Lock => "lock",
+            ModerationAction::Timeout => "timeout",
+        }
+    }
+
+    /// Gets the action with the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            ModerationAction::Pin,
+            ModerationAction::Delete,
+            ModerationAction::Repost,
+            ModerationAction::Notify,
+            ModerationAction::Lock,
+            ModerationAction::Timeout,
+        ]
+        .into_iter()
+        .find(|action| action.name() == name)
+    }
+}
+
 impl<'a> FromSql<'a> for ModuleStatus {
     fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
         let bits: BitVec<u32> = FromSql::from_sql(ty, raw)?;
@@ -83,6 +407,55 @@ impl ToSql for ModuleStatus {
     to_sql_checked!();
 }
 
+impl<'a> FromSql<'a> for DropMode {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let name: &str = FromSql::from_sql(ty, raw)?;
+
+        DropMode::from_name(name).ok_or_else(|| format!("Unknown drop mode: {}", name).into())
+    }
+
+    accepts!(TEXT);
+}
+
+impl ToSql for DropMode {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.name().to_sql(ty, out)
+    }
+
+    accepts!(TEXT);
+
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for ModerationAction {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let name: &str = FromSql::from_sql(ty, raw)?;
+
+        ModerationAction::from_name(name)
+            .ok_or_else(|| format!("Unknown moderation action: {}", name).into())
+    }
+
+    accepts!(TEXT);
+}
+
+impl ToSql for ModerationAction {
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.name().to_sql(ty, out)
+    }
+
+    accepts!(TEXT);
+
+    to_sql_checked!();
+}
+
 impl TableResolved {
     pub async fn new(ctx: &Context, rows: Vec<Row>) -> Self {
         let header = {
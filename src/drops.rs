@@ -0,0 +1,371 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{Duration, Utc};
+use itertools::Itertools;
+use rand::seq::SliceRandom;
+use serenity::{
+    builder::CreateActionRow,
+    client::Context,
+    model::{
+        id::{ChannelId, GuildId, UserId},
+        interactions::{
+            message_component::{ButtonStyle, MessageComponentInteraction},
+            InteractionResponseType,
+        },
+        user::User,
+    },
+    prelude::Mentionable,
+};
+use tokio::time::interval;
+use tracing::error;
+
+use crate::{
+    config::Config,
+    data,
+    database::{
+        client::Database,
+        storage::Storage,
+        types::{DropMode, PendingDrop},
+    },
+    error::KowalskiError,
+    pluralize,
+    strings::ERR_DROP,
+    utils::create_embed,
+};
+
+/// Prefix of the custom id of drop buttons, followed by the user who dropped the score.
+const BUTTON_PREFIX: &str = "drop";
+
+/// Whether the loop handing out drops was started already, the ready event is fired again on
+/// reconnects.
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Drops the score of a user who left the guild into a channel.
+///
+/// Returns whether the drop waits to be picked up, the user has to be kept until it is handed out
+pub async fn drop_score(
+    ctx: &Context,
+    config: &Config,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    user: &User,
+    channel: ChannelId,
+) -> Result<bool, KowalskiError> {
+    // Get the score of the user
+    let score = storage.user_score(guild_id, user.id).await?;
+    let mode = storage.drop_mode(guild_id).await?;
+
+    let title = format!("User {} has dropped a score of {}", user.name, score);
+
+    // Upvotes returned to their givers don't have to be picked up
+    if mode == DropMode::ReturnToGivers {
+        let returned = storage.return_upvotes(guild_id, user.id).await?;
+
+        let embed = create_embed(
+            &title,
+            &format!(
+                "{} of the user {} went back to the users who gave them.",
+                pluralize!("point", returned),
+                user.mention()
+            ),
+        );
+
+        channel
+            .send_message(&ctx.http, |message| message.set_embeds(vec![embed]))
+            .await?;
+
+        return Ok(false);
+    }
+
+    let ends = Utc::now() + Duration::seconds(config.general.pickup_timeout as i64);
+
+    let (label, content) = match mode {
+        DropMode::Lottery => (
+            "Enter the lottery",
+            format!(
+                "Click the button to take part in the lottery for the score of the user {}!
+                The winner gets drawn <t:{}:R>.",
+                user.mention(),
+                ends.timestamp()
+            ),
+        ),
+        DropMode::Split => (
+            "Take a share",
+            format!(
+                "Click the button to get a share of the score of the user {}!
+                The score gets split <t:{}:R>.",
+                user.mention(),
+                ends.timestamp()
+            ),
+        ),
+        _ => (
+            "Pick up the score",
+            format!(
+                "Click the button to pick up the score of the user {}!",
+                user.mention()
+            ),
+        ),
+    };
+
+    // Create action row
+    let mut row = CreateActionRow::default();
+    row.create_button(|button| {
+        button
+            .label(label)
+            .custom_id(format!("{}:{}", BUTTON_PREFIX, user.id.0))
+            .style(ButtonStyle::Primary)
+    });
+
+    // Send embed
+    let embed = create_embed(&title, &content);
+    let message = channel
+        .send_message(&ctx.http, |message| {
+            message
+                .set_embeds(vec![embed])
+                .components(|components| components.set_action_rows(vec![row]))
+        })
+        .await?;
+
+    // Persist the drop, so it survives a restart
+    storage
+        .add_pending_drop(&PendingDrop {
+            guild: guild_id,
+            user: user.id,
+            channel,
+            message: message.id,
+            mode,
+            ends,
+        })
+        .await?;
+
+    Ok(true)
+}
+
+/// Checks whether a message component belongs to a drop.
+pub fn is_drop(message_component: &MessageComponentInteraction) -> bool {
+    parse_id(&message_component.data.custom_id).is_some()
+}
+
+/// Answers a user clicking the button of a drop.
+pub async fn answer_drop(
+    ctx: &Context,
+    message_component: &MessageComponentInteraction,
+) -> Result<(), KowalskiError> {
+    // Handing out the score can take longer than the interaction allows, so answer later
+    message_component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|data| data.ephemeral(true))
+        })
+        .await?;
+
+    let database = data!(ctx, Database);
+
+    let guild_id = message_component.guild_id.unwrap();
+    let user_id = parse_id(&message_component.data.custom_id).unwrap();
+    let participant = message_component.user.id;
+
+    let content = match database.pending_drop(guild_id, user_id).await? {
+        Some(drop) if drop.mode == DropMode::FirstPickup => {
+            // Only the first click gets the score
+            match database.take_pending_drop(guild_id, user_id).await? {
+                Some((drop, _)) => {
+                    hand_out(ctx, database.as_ref(), &drop, vec![participant]).await?;
+
+                    "You have picked up the score!"
+                }
+                None => "Someone else was faster...",
+            }
+        }
+        Some(_) => {
+            if database.join_drop(guild_id, user_id, participant).await? {
+                "You are taking part, the score gets handed out once the time is over."
+            } else {
+                "You are already taking part."
+            }
+        }
+        None => "The score has been handed out already.",
+    };
+
+    message_component
+        .edit_original_interaction_response(&ctx.http, |response| {
+            response.add_embed(create_embed("Drop", content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Repeatedly hand out the drops whose time to be picked up is over.
+pub fn resolve_drops(ctx: Context, period: std::time::Duration) {
+    // Only run one loop handing out the drops
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        // Get database
+        let database = data!(ctx, Database);
+
+        // Create the interval at which we will check for drops
+        let mut interval = interval(period);
+
+        loop {
+            // Wait for the next tick
+            interval.tick().await;
+
+            let drops = match database.due_drops().await {
+                Ok(drops) => drops,
+                Err(why) => {
+                    error!("{}: {}", ERR_DROP, why);
+                    continue;
+                }
+            };
+
+            for drop in drops {
+                if let Err(why) = resolve(&ctx, database.as_ref(), &drop).await {
+                    error!("{}: {}", ERR_DROP, why);
+                }
+            }
+        }
+    });
+}
+
+async fn resolve(
+    ctx: &Context,
+    storage: &dyn Storage,
+    drop: &PendingDrop,
+) -> Result<(), KowalskiError> {
+    // The drop might have been picked up in the meantime
+    if let Some((drop, participants)) = storage.take_pending_drop(drop.guild, drop.user).await? {
+        hand_out(ctx, storage, &drop, participants).await?;
+    }
+
+    Ok(())
+}
+
+/// Hands out a drop taken from the pending ones.
+///
+/// Note: The drop is put back if the score could not be moved, so it gets handed out again later
+async fn hand_out(
+    ctx: &Context,
+    storage: &dyn Storage,
+    drop: &PendingDrop,
+    participants: Vec<UserId>,
+) -> Result<(), KowalskiError> {
+    let content = match move_score(storage, drop, &participants).await {
+        Ok(content) => content,
+        Err(why) => {
+            storage.add_pending_drop(drop).await?;
+            for &participant in &participants {
+                storage
+                    .join_drop(drop.guild, drop.user, participant)
+                    .await?;
+            }
+
+            return Err(why);
+        }
+    };
+
+    // Keep the title of the drop
+    let mut message = drop.channel.message(&ctx.http, drop.message).await?;
+    let title = message
+        .embeds
+        .first()
+        .and_then(|embed| embed.title.clone())
+        .unwrap_or_default();
+    let embed = create_embed(&title, &content);
+
+    message
+        .edit(&ctx.http, |message| {
+            message
+                .components(|components| components.set_action_rows(vec![]))
+                .set_embeds(vec![embed])
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Moves the score of a drop to the users who picked it up and removes the user who dropped it.
+///
+/// Returns the description of where the score went
+async fn move_score(
+    storage: &dyn Storage,
+    drop: &PendingDrop,
+    participants: &[UserId],
+) -> Result<String, KowalskiError> {
+    let user = drop.user.mention();
+
+    let content = if participants.is_empty() {
+        "No one has picked up the reactions in time :(".to_string()
+    } else {
+        match drop.mode {
+            DropMode::FirstPickup => {
+                storage
+                    .transfer_score(drop.guild, drop.user, participants[0])
+                    .await?;
+
+                format!(
+                    "The user {} has picked up the score of {}!",
+                    participants[0].mention(),
+                    user
+                )
+            }
+            DropMode::Lottery => {
+                let winner = *participants.choose(&mut rand::thread_rng()).unwrap();
+
+                storage
+                    .transfer_score(drop.guild, drop.user, winner)
+                    .await?;
+
+                format!(
+                    "The user {} has won the score of {} in a lottery among {}!",
+                    winner.mention(),
+                    user,
+                    pluralize!("user", participants.len())
+                )
+            }
+            DropMode::Split => {
+                storage
+                    .split_score(drop.guild, drop.user, participants)
+                    .await?;
+
+                format!(
+                    "The score of {} has been split among {}.",
+                    user,
+                    participants
+                        .iter()
+                        .map(|participant| participant.mention())
+                        .join(", ")
+                )
+            }
+            DropMode::ReturnToGivers => {
+                let returned = storage.return_upvotes(drop.guild, drop.user).await?;
+
+                format!(
+                    "{} of the user {} went back to the users who gave them.",
+                    pluralize!("point", returned),
+                    user
+                )
+            }
+        }
+    };
+
+    // The user is only kept until the drop is handed out
+    storage.remove_user(drop.guild, drop.user).await?;
+
+    Ok(content)
+}
+
+/// Parses the custom id of a drop button into the user who dropped the score.
+fn parse_id(custom_id: &str) -> Option<UserId> {
+    let (prefix, user) = custom_id.split_once(':')?;
+
+    if prefix == BUTTON_PREFIX {
+        user.parse().ok().map(UserId)
+    } else {
+        None
+    }
+}
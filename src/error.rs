@@ -12,6 +12,16 @@ pub enum KowalskiError {
         #[from]
         source: tokio_postgres::Error,
     },
+    #[error("Failed to get a connection from the database pool: {0}")]
+    PoolError(String),
+    #[error("Failed to set up TLS for the database connection: {0}")]
+    TlsError(String),
+    #[error("Failed to migrate the database schema: {0}")]
+    MigrationError(String),
+    #[error("Failed to render the chart: {0}")]
+    ChartError(String),
+    #[error("Failed to export the leaderboard: {0}")]
+    ExportError(String),
     #[cfg(feature = "nlp-model")]
     #[error("Something went wrong handling the language model: {source:?}")]
     ModelError {
@@ -3,7 +3,11 @@ use serenity::{
     model::guild::{Guild, UnavailableGuild},
 };
 
-use crate::{data, database::client::Database, error::KowalskiError};
+use crate::{
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+};
 
 pub async fn guild_delete(
     ctx: &Context,
@@ -15,16 +19,7 @@ pub async fn guild_delete(
         // Get database
         let database = data!(ctx, Database);
 
-        // Get guild id
-        let guild_db_id = incomplete.id.0 as i64;
-
-        database
-            .client
-            .execute(
-                "DELETE FROM guilds WHERE guild = $1::BIGINT",
-                &[&guild_db_id],
-            )
-            .await?;
+        database.remove_guild(incomplete.id).await?;
     }
 
     Ok(())
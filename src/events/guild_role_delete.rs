@@ -6,7 +6,11 @@ use serenity::{
     },
 };
 
-use crate::{data, database::client::Database, error::KowalskiError};
+use crate::{
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+};
 
 pub async fn guild_role_delete(
     ctx: &Context,
@@ -17,20 +21,7 @@ pub async fn guild_role_delete(
     // Get database
     let database = data!(ctx, Database);
 
-    // Get guild and channel ids
-    let guild_db_id = guild_id.0 as i64;
-    let role_db_id = removed_role_id.0 as i64;
-
-    database
-        .client
-        .execute(
-            "
-            DELETE FROM roles
-            WHERE guild = $1::BIGINT AND role = $2::BIGINT
-            ",
-            &[&guild_db_id, &role_db_id],
-        )
-        .await?;
+    database.remove_role(guild_id, removed_role_id).await?;
 
     Ok(())
 }
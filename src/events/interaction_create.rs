@@ -14,8 +14,10 @@ use crate::{
     config::{CommandType, Config},
     credits::Credits,
     data,
+    drops::{answer_drop, is_drop},
     error::KowalskiError,
     history::History,
+    reaction_roles,
     strings::{
         ERR_AUTOCOMPLETE, ERR_CMD_EXECUTION, ERR_MESSAGE_COMPONENT, ERR_USER_EXECUTION_FAILED,
         ERR_USER_TITLE,
@@ -141,30 +143,49 @@ async fn execute_command(
                 CommandType::Publish => disabled::execute(ctx, command, command_config).await,
                 CommandType::Reminder => reminder::execute(ctx, command, command_config).await,
                 CommandType::Reminders => reminders::execute(ctx, command, command_config).await,
+                CommandType::Abuse => abuse::execute(ctx, command, command_config).await,
+                CommandType::Announcement => {
+                    announcement::execute(ctx, command, command_config).await
+                }
+                CommandType::Cap => cap::execute(ctx, command, command_config).await,
+                CommandType::Channel => channel::execute(ctx, command, command_config).await,
+                CommandType::Channels => channels::execute(ctx, command, command_config).await,
                 CommandType::Cooldown => cooldown::execute(ctx, command, command_config).await,
                 CommandType::Cooldowns => cooldowns::execute(ctx, command, command_config).await,
+                CommandType::Decay => decay::execute(ctx, command, command_config).await,
                 CommandType::Drop => drop::execute(ctx, command, command_config).await,
                 CommandType::Drops => drops::execute(ctx, command, command_config).await,
                 CommandType::Emoji => emoji::execute(ctx, command, command_config).await,
                 CommandType::Emojis => emojis::execute(ctx, command, command_config).await,
+                CommandType::Export => export::execute(ctx, command, command_config).await,
                 CommandType::Gift => gift::execute(ctx, command, command_config).await,
+                CommandType::GiftCap => giftcap::execute(ctx, command, command_config).await,
+                CommandType::Gifts => gifts::execute(ctx, command, command_config).await,
                 CommandType::Given => given::execute(ctx, command, command_config).await,
                 CommandType::Givens => givens::execute(ctx, command, command_config).await,
                 CommandType::Global => global::execute(ctx, command, command_config).await,
+                CommandType::History => history::execute(ctx, command, command_config).await,
                 CommandType::LevelUp => levelup::execute(ctx, command, command_config).await,
                 CommandType::LevelUps => levelups::execute(ctx, command, command_config).await,
                 CommandType::Moderation => moderation::execute(ctx, command, command_config).await,
                 CommandType::Moderations => {
                     moderations::execute(ctx, command, command_config).await
                 }
+                CommandType::NewSeason => newseason::execute(ctx, command, command_config).await,
+                CommandType::Resync => resync::execute(ctx, command, command_config).await,
+                CommandType::Reward => reward::execute(ctx, command, command_config).await,
+                CommandType::Rewards => rewards::execute(ctx, command, command_config).await,
                 CommandType::Score => score::execute(ctx, command, command_config).await,
                 CommandType::Scores => scores::execute(ctx, command, command_config).await,
+                CommandType::Season => season::execute(ctx, command, command_config).await,
+                CommandType::Ungift => ungift::execute(ctx, command, command_config).await,
                 CommandType::ReactionRole => {
                     reactionrole::execute(ctx, command, command_config).await
                 }
                 CommandType::ReactionRoles => {
                     reactionroles::execute(ctx, command, command_config).await
                 }
+                CommandType::RoleMenu => rolemenu::execute(ctx, command, command_config).await,
                 #[cfg(feature = "nlp-model")]
                 CommandType::Mood => mood::execute(ctx, command, command_config).await,
                 #[cfg(feature = "nlp-model")]
@@ -244,6 +265,14 @@ async fn answer_message_component(
     ctx: &Context,
     message_component: MessageComponentInteraction,
 ) -> Result<(), KowalskiError> {
+    // Role menus and drops are answered here, other components are handled by their collectors
+    if reaction_roles::is_role_menu(&message_component) {
+        return reaction_roles::answer_role_menu(ctx, &message_component).await;
+    }
+    if is_drop(&message_component) {
+        return answer_drop(ctx, &message_component).await;
+    }
+
     message_component
         .create_interaction_response(&ctx.http, |response| {
             response.kind(InteractionResponseType::DeferredUpdateMessage)
@@ -1,10 +1,13 @@
-use itertools::Itertools;
 use serenity::{
     client::Context,
     model::id::{ChannelId, GuildId, MessageId},
 };
 
-use crate::{data, database::client::Database, error::KowalskiError};
+use crate::{
+    data,
+    database::{client::Database, storage::Storage},
+    error::KowalskiError,
+};
 
 pub async fn message_delete(
     ctx: &Context,
@@ -16,20 +19,8 @@ pub async fn message_delete(
         // Get database
         let database = data!(ctx, Database);
 
-        // Get guild, channel and message ids
-        let guild_db_id = guild_id.0 as i64;
-        let channel_db_id = channel_id.0 as i64;
-        let message_db_id = deleted_message_id.0 as i64;
-
         database
-            .client
-            .execute(
-                "
-                DELETE FROM messages
-                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
-                ",
-                &[&guild_db_id, &channel_db_id, &message_db_id],
-            )
+            .remove_messages(guild_id, channel_id, &[deleted_message_id])
             .await?;
     }
 
@@ -46,26 +37,8 @@ pub async fn message_delete_bulk(
         // Get database
         let database = data!(ctx, Database);
 
-        // Get guild, channel and message ids
-        let guild_db_id = guild_id.0 as i64;
-        let channel_db_id = channel_id.0 as i64;
-        let message_db_ids: Vec<_> = deleted_messages_ids
-            .iter()
-            .map(|deleted_message_id| deleted_message_id.0 as i64)
-            .collect();
-
         database
-            .client
-            .execute(
-                &format!(
-                    "
-                DELETE FROM messages
-                WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message IN ({})
-                ",
-                    message_db_ids.iter().join(",")
-                ),
-                &[&guild_db_id, &channel_db_id],
-            )
+            .remove_messages(guild_id, channel_id, &deleted_messages_ids)
             .await?;
     }
 
@@ -1,94 +1,66 @@
+use chrono::Utc;
+use itertools::Itertools;
 use serenity::{
     client::Context,
     model::{
-        channel::{Message, Reaction, ReactionType},
+        channel::{Message, Reaction},
         guild::Member,
         id::{ChannelId, GuildId, MessageId, RoleId, UserId},
     },
 };
+use tracing::error;
 
 use crate::{
+    auto_moderation::{apply_rule, matching_rules},
     config::Config,
-    cooldowns::Cooldowns,
+    cooldowns::{Cooldowns, Vote},
     data,
-    database::{client::Database, types::ModuleStatus},
+    database::{
+        client::Database,
+        storage::Storage,
+        types::{ModerationAction, ReactionRole, ScoreReaction},
+    },
     error::KowalskiError,
+    level_ups::{announce_level_change, grant_rewards},
+    reaction_roles::{self, Toggle},
+    strings::ERR_AUTO_MODERATION,
 };
 
 pub async fn reaction_add(ctx: &Context, add_reaction: Reaction) -> Result<(), KowalskiError> {
     // Get database
-    let (config, database, cooldowns_lock) = data!(ctx, (Config, Database, Cooldowns));
+    let database = data!(ctx, Database);
 
     // Check if the emoji is registered and get its id
-    if let Some(emoji_db_id) = get_emoji_id(&add_reaction.emoji, &database).await? {
+    if let Some(emoji_db_id) = database.find_emoji(&add_reaction.emoji).await? {
         // Get reaction data
         let (guild_id, user_from_id, user_to_id, channel_id, message_id) =
             get_reaction_data(ctx, &add_reaction).await?;
 
-        // Get guild, channel, user_from, user_to and message ids
-        let guild_db_id = database.get_guild(guild_id).await?;
-        let user_from_db_id = database.get_user(guild_id, user_from_id).await?;
-        let user_to_db_id = database.get_user(guild_id, user_to_id).await?;
-        let channel_db_id = database.get_channel(guild_id, channel_id).await?;
-        let message_db_id = database
-            .get_message(guild_id, channel_id, message_id)
-            .await?;
-
         // Get guild status
-        let status = database
-            .client
-            .query_opt(
-                "
-                SELECT status
-                FROM modules
-                WHERE guild = $1::BIGINT
-                ",
-                &[&guild_db_id],
-            )
-            .await?
-            .map_or(ModuleStatus::default(), |row| row.get(0));
+        let status = database.module_status(guild_id).await?;
 
         // Get the reaction-roles to assign
-        let reaction_roles: Vec<_> = if status.reaction_roles {
-            let rows = database
-                .client
-                .query(
-                    "
-                    SELECT role, slots FROM reaction_roles
-                    WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
-                    AND emoji = $4::INT
-                    ",
-                    &[&guild_db_id, &channel_db_id, &message_db_id, &emoji_db_id],
-                )
-                .await?;
-
-            rows.iter()
-                .map(|row| {
-                    (
-                        RoleId(row.get::<_, i64>(0) as u64),
-                        row.get::<_, Option<i32>>(1),
-                    )
-                })
-                .collect()
+        let reaction_roles: Vec<ReactionRole> = if status.reaction_roles {
+            database
+                .reaction_roles(guild_id, channel_id, message_id, emoji_db_id)
+                .await?
         } else {
             Vec::new()
         };
 
+        // Get the score the reaction is worth if the emoji is a up-/downvote
+        let scoring = database.channel_scoring(guild_id, channel_id).await?;
+        let weight = database
+            .score_emoji(guild_id, emoji_db_id)
+            .await?
+            .map(|weight| weight as i64 * scoring.multiplier as i64);
+
         // Whether the emoji should count as a up-/downvote
         let levelup = status.score
             && user_from_id != user_to_id
             && reaction_roles.is_empty()
-            && database
-                .client
-                .query_opt(
-                    "
-                    SELECT * FROM score_emojis
-                    WHERE guild = $1::BIGINT AND emoji = $2::INT
-                    ",
-                    &[&guild_db_id, &emoji_db_id],
-                )
-                .await?
-                .is_some();
+            && weight.is_some()
+            && !scoring.excluded;
 
         if !reaction_roles.is_empty() {
             // Get guild
@@ -108,90 +80,72 @@ pub async fn reaction_add(ctx: &Context, add_reaction: Reaction) -> Result<(), K
             // Remove the reaction
             add_reaction.delete(&ctx.http).await?;
 
-            for (role, slots) in reaction_roles {
-                if member.roles.contains(&role) {
-                    // Increment slots
-                    database
-                        .client
-                        .execute(
-                            "
-                        UPDATE reaction_roles
-                        SET slots = slots + 1
-                        WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
-                        AND emoji = $4::INT AND slots IS NOT NULL
-                        ",
-                            &[&guild_db_id, &channel_db_id, &message_db_id, &emoji_db_id],
-                        )
-                        .await?;
-
-                    // Remove role from user
-                    member.remove_role(&ctx.http, role).await?;
-                } else {
-                    if !matches!(slots, Some(0)) {
-                        // Decrement slots
-                        database
-                            .client
-                            .execute(
-                                "
-                    UPDATE reaction_roles
-                    SET slots = slots - 1
-                    WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
-                    AND emoji = $4::INT AND slots IS NOT NULL
-                    ",
-                                &[&guild_db_id, &channel_db_id, &message_db_id, &emoji_db_id],
-                            )
-                            .await?;
-
-                        // Add role to user
-                        member.add_role(&ctx.http, role).await?;
-                    }
+            for reaction_role in reaction_roles {
+                let toggle = reaction_roles::toggle(
+                    ctx,
+                    database.as_ref(),
+                    guild_id,
+                    &mut member,
+                    &reaction_role,
+                )
+                .await?;
+
+                // Tell the user why the role was not changed
+                if let Toggle::Denied(denial) = toggle {
+                    reaction_roles::report_denial(
+                        ctx,
+                        channel_id,
+                        user_from_id,
+                        &reaction_role,
+                        denial,
+                    )
+                    .await?;
                 }
             }
         } else if levelup {
-            // Check for cooldown
-            let cooldown_active = {
-                let mut cooldowns = cooldowns_lock.write().await;
-
-                // Get role ids of user
-                let roles: Vec<_> = add_reaction
-                    .member
-                    .as_ref()
-                    .unwrap()
-                    .roles
-                    .iter()
-                    .map(|role_id| role_id.clone())
-                    .collect();
-
-                cooldowns
-                    .check_cooldown(&config, &database, guild_id, user_from_id, &roles)
-                    .await?
+            // Get role ids of user
+            let roles: Vec<_> = add_reaction
+                .member
+                .as_ref()
+                .unwrap()
+                .roles
+                .iter()
+                .map(|role_id| role_id.clone())
+                .collect();
+
+            let vote = Vote {
+                guild: guild_id,
+                user_from: user_from_id,
+                user_to: user_to_id,
+                upvote: weight.unwrap_or_default() > 0,
             };
 
+            // Check for the score cap and cooldown
+            let cooldown_active = !vote_allowed(
+                ctx,
+                database.as_ref(),
+                &vote,
+                weight.unwrap_or_default(),
+                &roles,
+            )
+            .await?;
+
             if cooldown_active {
                 // Remove reaction
                 add_reaction.delete(&ctx.http).await?;
             } else {
                 // Insert row
                 database
-                    .client
-                    .execute(
-                        "
-                INSERT INTO score_reactions
-                VALUES($1::BIGINT, $2::BIGINT, $3::BIGINT, $4::BIGINT, $5::BIGINT, $6::INT,
-                $7::BOOLEAN)
-                ON CONFLICT
-                DO NOTHING
-                ",
-                        &[
-                            &guild_db_id,
-                            &user_from_db_id,
-                            &user_to_db_id,
-                            &channel_db_id,
-                            &message_db_id,
-                            &emoji_db_id,
-                            &true,
-                        ],
-                    )
+                    .add_score_reaction(&ScoreReaction {
+                        guild: guild_id,
+                        user_from: user_from_id,
+                        user_to: user_to_id,
+                        channel: channel_id,
+                        message: message_id,
+                        emoji: emoji_db_id,
+                        native: true,
+                        time: Utc::now(),
+                    })
                     .await?;
 
                 // Get guild
@@ -202,11 +156,11 @@ pub async fn reaction_add(ctx: &Context, add_reaction: Reaction) -> Result<(), K
 
                 // Update the roles of the user
                 let mut member = guild.member(&ctx, user_to_id.0).await?;
-                update_roles(&ctx, &database, &mut member).await?;
+                update_roles(ctx, database.as_ref(), &mut member).await?;
 
                 // Auto moderate the message if necessary
                 let message = add_reaction.message(&ctx.http).await?;
-                auto_moderate(&ctx, &database, guild, message).await?;
+                auto_moderate(ctx, database.as_ref(), guild, message).await?;
             }
         }
     }
@@ -222,62 +176,17 @@ pub async fn reaction_remove(
     let database = data!(ctx, Database);
 
     // Check if the emoji is registered
-    if let Some(emoji_db_id) = get_emoji_id(&removed_reaction.emoji, &database).await? {
+    if let Some(emoji_db_id) = database.find_emoji(&removed_reaction.emoji).await? {
         // Get reaction data
         let (guild_id, user_from_id, _, channel_id, message_id) =
             get_reaction_data(ctx, &removed_reaction).await?;
 
-        // Get guild, channel, user_from and message ids
-        let guild_db_id = database.get_guild(guild_id).await?;
-        let user_from_db_id = database.get_user(guild_id, user_from_id).await?;
-        let channel_db_id = database.get_channel(guild_id, channel_id).await?;
-        let message_db_id = database
-            .get_message(guild_id, channel_id, message_id)
+        // Delete possible reaction emoji and get the user of which the reaction was removed
+        let user_to_id = database
+            .remove_score_reaction(guild_id, user_from_id, channel_id, message_id, emoji_db_id)
             .await?;
 
-        // Get user of which the reaction was removed
-        let user_to_db_id = {
-            let row = database
-                .client
-                .query_opt(
-                    "
-            SELECT user_to FROM score_reactions
-            WHERE guild = $1::BIGINT AND user_from = $2::BIGINT AND channel = $3::BIGINT
-            AND message = $4::BIGINT AND emoji = $5::INT
-            ",
-                    &[
-                        &guild_db_id,
-                        &user_from_db_id,
-                        &channel_db_id,
-                        &message_db_id,
-                        &emoji_db_id,
-                    ],
-                )
-                .await?;
-
-            row.map(|row| row.get::<_, i64>(0))
-        };
-
-        if let Some(user_to_db_id) = user_to_db_id {
-            // Delete possible reaction emoji
-            database
-                .client
-                .execute(
-                    "
-        DELETE FROM score_reactions
-        WHERE guild = $1::BIGINT AND user_from = $2::BIGINT AND channel = $3::BIGINT
-        AND message = $4::BIGINT AND emoji = $5::INT
-        ",
-                    &[
-                        &guild_db_id,
-                        &user_from_db_id,
-                        &channel_db_id,
-                        &message_db_id,
-                        &emoji_db_id,
-                    ],
-                )
-                .await?;
-
+        if let Some(user_to_id) = user_to_id {
             // Get guild
             let guild = {
                 let channel = removed_reaction.channel_id.to_channel(&ctx.http).await?;
@@ -285,12 +194,12 @@ pub async fn reaction_remove(
             };
 
             // Update the roles of the user
-            let mut member = guild.member(&ctx, user_to_db_id as u64).await?;
-            update_roles(&ctx, &database, &mut member).await?;
+            let mut member = guild.member(&ctx, user_to_id).await?;
+            update_roles(ctx, database.as_ref(), &mut member).await?;
 
             // Auto moderate the message if necessary
             let message = removed_reaction.message(&ctx.http).await?;
-            auto_moderate(&ctx, &database, guild, message).await?;
+            auto_moderate(ctx, database.as_ref(), guild, message).await?;
         }
     }
 
@@ -312,23 +221,9 @@ pub async fn reaction_remove_all(
 
     // Check if there is a guild
     if let Some(guild_id) = guild_id {
-        // Get guild id
-        let guild_db_id = database.get_guild(guild_id).await?;
-        let channel_db_id = database.get_channel(guild_id, channel_id).await?;
-        let message_db_id = database
-            .get_message(guild_id, channel_id, removed_from_message_id)
-            .await?;
-
         // Delete possible reaction emojis
         database
-            .client
-            .execute(
-                "
-        DELETE FROM score_reactions
-        WHERE guild = $1::BIGINT AND channel = $2::BIGINT AND message = $3::BIGINT
-        ",
-                &[&guild_db_id, &channel_db_id, &message_db_id],
-            )
+            .remove_message_score_reactions(guild_id, channel_id, removed_from_message_id)
             .await?;
 
         // Update the roles of the user
@@ -336,50 +231,51 @@ pub async fn reaction_remove_all(
             .message(&ctx.http, removed_from_message_id)
             .await?;
         let mut member = guild_id.member(&ctx, message.author.id).await?;
-        update_roles(&ctx, &database, &mut member).await?;
+        update_roles(&ctx, database.as_ref(), &mut member).await?;
 
         let message = channel_id
             .message(&ctx.http, removed_from_message_id)
             .await?;
-        auto_moderate(&ctx, &database, guild_id, message).await?;
+        auto_moderate(&ctx, database.as_ref(), guild_id, message).await?;
     }
 
     Ok(())
 }
 
-async fn get_emoji_id(
-    emoji: &ReactionType,
-    database: &Database,
-) -> Result<Option<i32>, KowalskiError> {
-    let rows = match emoji {
-        ReactionType::Unicode(string) => {
-            database
-                .client
-                .query(
-                    "
-                SELECT id FROM emojis
-                WHERE unicode = $1::TEXT
-                ",
-                    &[string],
-                )
-                .await?
-        }
-        ReactionType::Custom { id: emoji_id, .. } => {
-            database
-                .client
-                .query(
-                    "
-                    SELECT id FROM emojis
-                    WHERE guild_emoji = $1::BIGINT
-                    ",
-                    &[&(emoji_id.0 as i64)],
-                )
+/// Checks whether a vote may count, the user might have given the other user as much score as
+/// allowed already or be on cooldown.
+///
+/// Note: This will start a new cooldown, if the vote may count.
+pub async fn vote_allowed(
+    ctx: &Context,
+    storage: &dyn Storage,
+    vote: &Vote,
+    weight: i64,
+    roles: &[RoleId],
+) -> Result<bool, KowalskiError> {
+    let (config, cooldowns_lock) = data!(ctx, (Config, Cooldowns));
+
+    // Check whether the user has given the other user as much score as allowed already
+    if weight > 0 {
+        if let Some(cap) = storage.score_cap(vote.guild).await? {
+            if storage
+                .pair_upvotes(vote.guild, vote.user_from, vote.user_to)
                 .await?
+                + weight
+                > cap
+            {
+                return Ok(false);
+            }
         }
-        _ => unreachable!(),
-    };
+    }
 
-    Ok(rows.first().map(|row| row.get(0)))
+    // Check for cooldown
+    let mut cooldowns = cooldowns_lock.write().await;
+    let cooldown_active = cooldowns
+        .check_cooldown(&config, storage, vote, roles)
+        .await?;
+
+    Ok(!cooldown_active)
 }
 
 async fn get_reaction_data(
@@ -398,84 +294,45 @@ async fn get_reaction_data(
     Ok((guild_id, user_from_id, user_to_id, channel_id, message_id))
 }
 
-async fn update_roles(
-    ctx: &Context,
-    database: &Database,
-    member: &mut Member,
-) -> Result<(), KowalskiError> {
-    // Never update roles of bots
-    if member.user.bot {
-        return Ok(());
+/// Gets the score the level-up roles of a user are based on, which might decay over time.
+pub async fn level_up_score(
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    user_id: UserId,
+) -> Result<i64, KowalskiError> {
+    match storage.score_decay(guild_id).await? {
+        Some(decay) if decay.levelups => {
+            storage
+                .user_decayed_score(guild_id, user_id, decay.half_life)
+                .await
+        }
+        _ => storage.user_score(guild_id, user_id).await,
     }
+}
 
-    // Get guild and user ids
-    let guild_db_id = database.get_guild(member.guild_id).await?;
-    let user_db_id = database.get_user(member.guild_id, member.user.id).await?;
-
-    // Get the score of the user
-    let score = {
-        let row = database
-            .client
-            .query_one(
-                "
-        SELECT SUM(CASE WHEN upvote THEN 1 ELSE -1 END) score
-        FROM score_reactions r
-        INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-        WHERE r.guild = $1::BIGINT AND user_to = $2::BIGINT
-        ",
-                &[&guild_db_id, &user_db_id],
-            )
-            .await?;
-
-        row.get::<_, Option<i64>>(0).unwrap_or_default()
-    };
-
+/// Gets the level-up roles to add to and remove from a user, given the score and the roles the user has.
+pub async fn level_up_changes(
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    score: i64,
+    roles: &[RoleId],
+) -> Result<(Vec<RoleId>, Vec<RoleId>), KowalskiError> {
     // Get all roles handled by the level-up system
-    let handled: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "SELECT DISTINCT role FROM score_roles WHERE guild = $1::BIGINT",
-                &[&guild_db_id],
-            )
-            .await?;
+    let level_up_roles = storage.level_up_roles(guild_id).await?;
+    let stacking = storage.level_up_stacking(guild_id).await?;
 
-        rows.iter()
-            .map(|row| RoleId(row.get::<_, i64>(0) as u64))
-            .collect()
-    };
+    Ok(role_changes(&level_up_roles, stacking, score, roles))
+}
 
+/// Gets the level-up roles to add to and remove from a user, given all level-up roles of the guild.
+pub fn role_changes(
+    level_up_roles: &[(RoleId, i64)],
+    stacking: bool,
+    score: i64,
+    roles: &[RoleId],
+) -> (Vec<RoleId>, Vec<RoleId>) {
     // Get all roles the user should currently have
-    let current: Vec<_> = {
-        let rows = database
-            .client
-            .query(
-                "
-            WITH role_score AS (
-                SELECT score
-                FROM score_roles
-                WHERE (score >= 0 AND score <= $2::BIGINT)
-                    OR (score < 0 AND score >= $2::BIGINT)
-                ORDER BY ABS(score) DESC
-                LIMIT 1
-            )
-
-            SELECT role
-            FROM score_roles
-            WHERE guild = $1::BIGINT
-                AND score = (SELECT score FROM role_score)
-            ",
-                &[&guild_db_id, &score],
-            )
-            .await?;
-
-        rows.iter()
-            .map(|row| RoleId(row.get::<_, i64>(0) as u64))
-            .collect()
-    };
-
-    // Current roles of the user
-    let roles = &member.roles;
+    let current = reached_roles(level_up_roles, score, stacking);
 
     // Filter roles the user should have but doesn't
     let add: Vec<_> = current
@@ -486,10 +343,64 @@ async fn update_roles(
     // Filter roles the user shouldn't have but does
     let remove: Vec<_> = roles
         .iter()
-        .filter(|role| handled.contains(role) && !current.contains(role))
+        .filter(|role| {
+            level_up_roles.iter().any(|(handled, _)| handled == *role) && !current.contains(role)
+        })
         .copied()
         .collect();
 
+    (add, remove)
+}
+
+/// Gets the level-up roles a user with the given score should have.
+///
+/// Note: Unless the roles stack, only the roles of the highest reached score get assigned
+pub fn reached_roles(level_up_roles: &[(RoleId, i64)], score: i64, stacking: bool) -> Vec<RoleId> {
+    // Negative scores are reached by going below them
+    let is_reached = |role_score: i64| {
+        (role_score >= 0 && role_score <= score) || (role_score < 0 && role_score >= score)
+    };
+
+    if stacking {
+        return level_up_roles
+            .iter()
+            .filter(|&&(_, role_score)| is_reached(role_score))
+            .map(|&(role_id, _)| role_id)
+            .unique()
+            .collect();
+    }
+
+    // Get the highest score reached
+    let reached = level_up_roles
+        .iter()
+        .map(|&(_, role_score)| role_score)
+        .filter(|&role_score| is_reached(role_score))
+        .max_by_key(|role_score| role_score.abs());
+
+    match reached {
+        Some(reached) => level_up_roles
+            .iter()
+            .filter(|&&(_, role_score)| role_score == reached)
+            .map(|&(role_id, _)| role_id)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Updates the level-up roles of a member according to the score of the member.
+pub async fn update_roles(
+    ctx: &Context,
+    storage: &dyn Storage,
+    member: &mut Member,
+) -> Result<(), KowalskiError> {
+    // Never update roles of bots
+    if member.user.bot {
+        return Ok(());
+    }
+
+    let score = level_up_score(storage, member.guild_id, member.user.id).await?;
+    let (add, remove) = level_up_changes(storage, member.guild_id, score, &member.roles).await?;
+
     // Add new roles
     if !add.is_empty() {
         member.add_roles(&ctx.http, &add[..]).await?;
@@ -499,95 +410,137 @@ async fn update_roles(
         member.remove_roles(&ctx.http, &remove[..]).await?;
     }
 
-    Ok(())
+    // Tell the member about the new level
+    announce_level_change(ctx, storage, member, score, &add, &remove).await?;
+
+    // Hand out the rewards reached for the first time
+    grant_rewards(ctx, storage, member, score).await
 }
 
-async fn auto_moderate(
+/// Applies the auto-moderation rules of the guild which a message matches, each one only once.
+pub async fn auto_moderate(
     ctx: &Context,
-    database: &Database,
+    storage: &dyn Storage,
     guild_id: GuildId,
     message: Message,
 ) -> Result<(), KowalskiError> {
-    // Get guild and message ids
-    let guild_db_id = database.get_guild(guild_id).await?;
-    let message_db_id = database
-        .get_message(guild_id, message.channel_id, message.id)
-        .await?;
+    let mut rules = matching_rules(storage, guild_id, &message).await?;
+    // The other actions still need the message, so delete it last
+    rules.sort_by_key(|rule| rule.action == ModerationAction::Delete);
+
+    for rule in rules {
+        // A failing rule should not keep the others from being applied, it gets retried next time
+        if let Err(why) = apply_rule(ctx, storage, guild_id, &message, &rule).await {
+            error!("{} #{}: {}", ERR_AUTO_MODERATION, rule.id, why);
+            continue;
+        }
 
-    // Get scores of auto-pin and auto-delete
-    let pin_score = {
-        let row = database
-            .client
-            .query_opt(
-                "
-        SELECT score FROM score_auto_pin
-        WHERE guild = $1::BIGINT
-        ",
-                &[&guild_db_id],
-            )
+        storage
+            .trigger_moderation_rule(guild_id, rule.id, message.channel_id, message.id)
             .await?;
 
-        row.map(|row| row.get::<_, i64>(0))
-    };
+        if rule.action == ModerationAction::Delete {
+            break;
+        }
+    }
 
-    let delete_score = {
-        let row = database
-            .client
-            .query_opt(
-                "
-        SELECT score FROM score_auto_delete
-        WHERE guild = $1::BIGINT
-        ",
-                &[&guild_db_id],
-            )
-            .await?;
+    Ok(())
+}
 
-        row.map(|row| row.get::<_, i64>(0))
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::memory::MemoryStorage;
 
-    // Check whether auto moderation is enabled
-    if pin_score.is_some() || delete_score.is_some() {
-        // Get score of the message
-        let score = {
-            let row = database
-                .client
-                .query_one(
-                    "
-                SELECT SUM(CASE WHEN upvote THEN 1 ELSE -1 END) FROM score_reactions r
-                INNER JOIN score_emojis se ON r.guild = se.guild AND r.emoji = se.emoji
-                WHERE r.guild = $1::BIGINT AND message = $2::BIGINT
-                ",
-                    &[&guild_db_id, &message_db_id],
-                )
-                .await?;
+    const GUILD: GuildId = GuildId(1);
+    const USER: UserId = UserId(2);
 
-            row.get::<_, Option<i64>>(0).unwrap_or_default()
-        };
+    /// Sets up a guild with level-up roles at the given scores.
+    async fn setup(level_up_roles: &[(u64, i64)], stacking: bool) -> MemoryStorage {
+        let storage = MemoryStorage::new();
 
-        // Check whether message should get pinned
-        if !message.pinned {
-            if let Some(pin_score) = pin_score {
-                // Check whether scores share the same sign
-                if (score >= 0) == (pin_score >= 0) {
-                    if score.abs() >= pin_score.abs() {
-                        // Pin the message
-                        message.pin(&ctx.http).await?;
-                    }
-                }
-            }
+        for &(role, score) in level_up_roles {
+            storage
+                .add_level_up_role(GUILD, RoleId(role), score)
+                .await
+                .unwrap();
         }
+        storage
+            .set_level_up_stacking(GUILD, stacking)
+            .await
+            .unwrap();
 
-        // Check whether message should get deleted
-        if let Some(delete_score) = delete_score {
-            // Check whether scores share the same sign
-            if (score >= 0) == (delete_score >= 0) {
-                if score.abs() >= delete_score.abs() {
-                    // Delete the message
-                    message.delete(&ctx.http).await?;
-                }
-            }
-        }
+        storage
     }
 
-    Ok(())
+    /// Gives the user a vote of the given weight from another user.
+    async fn vote(storage: &MemoryStorage, user_from: u64, weight: i32) {
+        storage
+            .vote(
+                GUILD,
+                UserId(user_from),
+                USER,
+                ChannelId(3),
+                MessageId(4),
+                weight,
+            )
+            .await;
+    }
+
+    async fn changes(storage: &MemoryStorage, roles: &[u64]) -> (Vec<RoleId>, Vec<RoleId>) {
+        let score = storage.user_score(GUILD, USER).await.unwrap();
+        let roles: Vec<_> = roles.iter().map(|&role| RoleId(role)).collect();
+
+        level_up_changes(storage, GUILD, score, &roles)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn assigns_highest_reached_role() {
+        let storage = setup(&[(10, 5), (11, 10), (12, 20)], false).await;
+
+        vote(&storage, 100, 12).await;
+
+        assert_eq!(
+            changes(&storage, &[10]).await,
+            (vec![RoleId(11)], vec![RoleId(10)])
+        );
+        assert_eq!(changes(&storage, &[11, 99]).await, (vec![], vec![]));
+    }
+
+    #[tokio::test]
+    async fn stacks_reached_roles() {
+        let storage = setup(&[(10, 5), (11, 10), (12, 20)], true).await;
+
+        vote(&storage, 100, 12).await;
+
+        assert_eq!(
+            changes(&storage, &[]).await,
+            (vec![RoleId(10), RoleId(11)], vec![])
+        );
+        assert_eq!(changes(&storage, &[12]).await.1, vec![RoleId(12)]);
+    }
+
+    #[tokio::test]
+    async fn reaches_negative_roles_by_going_below() {
+        let storage = setup(&[(10, 5), (11, -5)], false).await;
+
+        vote(&storage, 100, -3).await;
+        assert_eq!(changes(&storage, &[10]).await, (vec![], vec![RoleId(10)]));
+
+        vote(&storage, 101, -2).await;
+        assert_eq!(changes(&storage, &[]).await, (vec![RoleId(11)], vec![]));
+    }
+
+    #[test]
+    fn assigns_all_roles_of_same_score() {
+        let level_up_roles = [(RoleId(10), 5), (RoleId(11), 5), (RoleId(12), 1)];
+
+        assert_eq!(
+            reached_roles(&level_up_roles, 7, false),
+            vec![RoleId(10), RoleId(11)]
+        );
+        assert!(reached_roles(&level_up_roles, 0, false).is_empty());
+    }
 }
@@ -4,7 +4,6 @@ use serenity::{
     client::Context,
     model::{
         gateway::{Activity, Ready},
-        id::GuildId,
         interactions::application_command::ApplicationCommand,
     },
 };
@@ -14,9 +13,14 @@ use tracing::info;
 use crate::calendar::host_calendar;
 use crate::{
     config::Config,
+    cooldowns::evict_cooldowns,
     data,
-    database::{client::Database, types::ModuleStatus},
+    database::{client::Database, storage::Storage},
+    drops::resolve_drops,
+    reaction_roles::check_expirations,
+    reconciliation::reconcile_reactions,
     reminders::check_reminders,
+    snapshots::take_snapshots,
     strings::{ERR_CMD_CREATION, ERR_DB_QUERY, INFO_CMD_GLOBAL, INFO_CMD_MODULE, INFO_CONNECTED},
     utils::{create_command, create_module_command},
 };
@@ -31,6 +35,21 @@ pub async fn ready(ctx: &Context, rdy: Ready) {
     // Repeatedly check for reminders
     check_reminders(ctx.clone(), Duration::from_secs(60));
 
+    // Repeatedly remove expired reaction-roles
+    check_expirations(ctx.clone(), Duration::from_secs(60));
+
+    // Repeatedly remove cooldowns which are over
+    evict_cooldowns(ctx.clone(), Duration::from_secs(600));
+
+    // Repeatedly hand out dropped scores whose time to be picked up is over
+    resolve_drops(ctx.clone(), Duration::from_secs(60));
+
+    // Repeatedly record the scores of the users
+    take_snapshots(ctx.clone(), Duration::from_secs(3600));
+
+    // Catch up with the reactions missed while the bot was offline
+    reconcile_reactions(ctx.clone());
+
     // Activate the event calendar
     #[cfg(feature = "event-calendar")]
     host_calendar(ctx.clone());
@@ -71,16 +90,9 @@ async fn create_global_commands(ctx: &Context, config: &Config) -> Vec<Applicati
 }
 
 async fn create_module_commands(ctx: &Context, config: &Config, database: &Database) {
-    let modules = database
-        .client
-        .query("SELECT * FROM modules", &[])
-        .await
-        .expect(ERR_DB_QUERY);
-
-    for row in modules {
-        let guild = GuildId(row.get::<_, i64>(0) as u64);
-        let status: ModuleStatus = row.get(1);
+    let modules = database.module_statuses().await.expect(ERR_DB_QUERY);
 
+    for (guild, status) in modules {
         create_module_command(ctx, config, guild, &status).await
     }
 }
@@ -0,0 +1,115 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+use serenity::{
+    model::id::{GuildId, UserId},
+    prelude::Mentionable,
+};
+
+use crate::{
+    database::{storage::Storage, types::SeasonRank},
+    error::KowalskiError,
+    error::KowalskiError::DiscordApiError,
+    strings::ERR_CMD_ARGS_INVALID,
+};
+
+/// The time frame of the reactions a score is based on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Period {
+    Week,
+    Month,
+    AllTime,
+}
+
+impl Period {
+    /// Gets the time from which on reactions count towards the score.
+    pub fn start(&self) -> DateTime<Utc> {
+        match self {
+            Period::Week => Utc::now() - Duration::weeks(1),
+            Period::Month => Utc::now() - Duration::days(30),
+            Period::AllTime => DateTime::UNIX_EPOCH,
+        }
+    }
+}
+
+impl Display for Period {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Period::Week => "this week",
+            Period::Month => "this month",
+            Period::AllTime => "all time",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Period {
+    type Err = KowalskiError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "week" => Ok(Period::Week),
+            "month" => Ok(Period::Month),
+            "all time" => Ok(Period::AllTime),
+            _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+        }
+    }
+}
+
+/// Gets the half-life of the reactions of a guild in days.
+///
+/// Note: The half-life is infinite if scores don't decay on the guild
+pub async fn half_life(storage: &dyn Storage, guild_id: GuildId) -> Result<f64, KowalskiError> {
+    Ok(storage
+        .score_decay(guild_id)
+        .await?
+        .map_or(f64::INFINITY, |decay| decay.half_life as f64))
+}
+
+/// Gets the scores of all users of a guild with their up- and downvotes, highest score first.
+pub async fn scores(
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    period: Period,
+) -> Result<Vec<(UserId, i64, i64)>, KowalskiError> {
+    let half_life = half_life(storage, guild_id).await?;
+
+    storage
+        .guild_scores(guild_id, period.start(), half_life)
+        .await
+}
+
+/// Gets the up- and downvotes given by the users of a guild with the gifted part, most given first.
+pub async fn givens(
+    storage: &dyn Storage,
+    guild_id: GuildId,
+) -> Result<Vec<(UserId, i64, i64, i64)>, KowalskiError> {
+    storage.guild_givens(guild_id).await
+}
+
+/// Formats the final ranks of a season as one line per user, using the titles of the top ranks.
+pub fn format_ranks(ranks: &[SeasonRank], rank_titles: &[String]) -> String {
+    ranks
+        .iter()
+        .map(|rank| {
+            let title = match rank_titles.get(rank.rank as usize - 1) {
+                Some(title) => title.clone(),
+                None => format!("#{}", rank.rank),
+            };
+
+            format!(
+                "{} {}: **{}** [+{}, -{}]",
+                title,
+                rank.user.mention(),
+                rank.score,
+                rank.upvotes,
+                rank.downvotes
+            )
+        })
+        .join("\n")
+}
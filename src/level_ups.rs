@@ -0,0 +1,355 @@
+use std::{sync::Mutex, time::Duration};
+
+use itertools::Itertools;
+use serenity::{
+    client::Context,
+    model::{
+        channel::Message,
+        guild::Member,
+        id::{GuildId, RoleId},
+    },
+    prelude::Mentionable,
+};
+use tokio::time::sleep;
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    data,
+    database::{client::Database, storage::Storage, types::ScoreReward},
+    error::KowalskiError,
+    events::reaction::{level_up_score, role_changes},
+    strings::{ERR_LEVEL_UP_ANNOUNCEMENT, ERR_RESYNC, INFO_RESYNC_DONE},
+    utils::{create_embed, get_members},
+};
+
+/// The guilds whose level-up roles are being resynchronized right now,
+/// and whether another resync was requested in the meantime.
+static RESYNCING: Mutex<Vec<(GuildId, bool)>> = Mutex::new(Vec::new());
+
+/// The counts of changes made while resynchronizing the level-up roles of a guild.
+#[derive(Default)]
+struct ResyncProgress {
+    checked: usize,
+    total: usize,
+    updated: usize,
+    failed: usize,
+}
+
+/// Announces that a member reached a higher or lower level, if the guild wants it to.
+pub async fn announce_level_change(
+    ctx: &Context,
+    storage: &dyn Storage,
+    member: &Member,
+    score: i64,
+    added: &[RoleId],
+    removed: &[RoleId],
+) -> Result<(), KowalskiError> {
+    if added.is_empty() && removed.is_empty() {
+        return Ok(());
+    }
+
+    let announcement = match storage.level_up_announcement(member.guild_id).await? {
+        Some(announcement) => announcement,
+        None => return Ok(()),
+    };
+
+    // Get the scores of the previous and the new level
+    let level_up_roles = storage.level_up_roles(member.guild_id).await?;
+    let level = |roles: &[RoleId]| {
+        roles
+            .iter()
+            .filter_map(|role| {
+                level_up_roles
+                    .iter()
+                    .find(|(handled, _)| handled == role)
+                    .map(|&(_, score)| score)
+            })
+            .max_by_key(|score| score.abs())
+            .unwrap_or_default()
+    };
+    let (previous, current) = (level(removed), level(added));
+
+    // Get the message and the roles gained or lost
+    let (title, template, roles) = if current > previous {
+        ("Level-up", announcement.level_up, added)
+    } else if current < previous {
+        ("Level-down", announcement.level_down, removed)
+    } else {
+        return Ok(());
+    };
+    let template = match template {
+        Some(template) => template,
+        None => return Ok(()),
+    };
+
+    let embed = create_embed(title, &fill_template(&template, member, roles, score));
+
+    let result = match announcement.channel {
+        Some(channel_id) => channel_id
+            .send_message(&ctx.http, |message| {
+                message.content(member.mention()).set_embed(embed)
+            })
+            .await
+            .map(|_| ()),
+        None => member
+            .user
+            .direct_message(&ctx.http, |message| message.set_embed(embed))
+            .await
+            .map(|_| ()),
+    };
+
+    // The member might not accept direct messages or the channel might be gone
+    if let Err(why) = result {
+        error!("{}: {}", ERR_LEVEL_UP_ANNOUNCEMENT, why);
+    }
+
+    Ok(())
+}
+
+/// Hands out the rewards of all scores a member reached for the first time.
+pub async fn grant_rewards(
+    ctx: &Context,
+    storage: &dyn Storage,
+    member: &mut Member,
+    score: i64,
+) -> Result<(), KowalskiError> {
+    for reward in storage.score_rewards(member.guild_id).await? {
+        // Negative scores are reached by going below them
+        let reached = (reward.score >= 0 && score >= reward.score)
+            || (reward.score < 0 && score <= reward.score);
+
+        // Claim the reward before handing it out, so it is only handed out once
+        if !reached
+            || !storage
+                .claim_score_reward(member.guild_id, member.user.id, reward.score)
+                .await?
+        {
+            continue;
+        }
+
+        // Give the claim back if the reward could not be handed out, it gets retried next time
+        if let Err(why) = hand_out_reward(ctx, member, &reward).await {
+            storage
+                .unclaim_score_reward(member.guild_id, member.user.id, reward.score)
+                .await?;
+
+            return Err(why);
+        }
+    }
+
+    Ok(())
+}
+
+async fn hand_out_reward(
+    ctx: &Context,
+    member: &mut Member,
+    reward: &ScoreReward,
+) -> Result<(), KowalskiError> {
+    if let Some(role_id) = reward.role {
+        member.add_role(&ctx.http, role_id).await?;
+    }
+
+    if let (Some(channel_id), Some(template)) = (reward.channel, &reward.message) {
+        let roles: Vec<_> = reward.role.into_iter().collect();
+        let embed = create_embed(
+            "Score reward",
+            &fill_template(template, member, &roles, reward.score),
+        );
+
+        channel_id
+            .send_message(&ctx.http, |message| message.set_embed(embed))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Resynchronizes the level-up roles of all members of a guild in the background,
+/// reporting the progress in the given message.
+///
+/// Returns whether the resync was started, there is only one at a time per guild.
+/// Otherwise another resync is run once the current one is done, which might have missed the
+/// latest changes.
+pub fn resync_roles(ctx: Context, guild_id: GuildId, message: Option<Message>) -> bool {
+    {
+        let mut resyncing = RESYNCING.lock().unwrap();
+        if let Some((_, requested)) = resyncing
+            .iter_mut()
+            .find(|(resyncing, _)| *resyncing == guild_id)
+        {
+            *requested = true;
+            return false;
+        }
+        resyncing.push((guild_id, false));
+    }
+
+    tokio::spawn(async move {
+        let mut message = message;
+
+        loop {
+            match resync(&ctx, guild_id, &mut message).await {
+                Ok(progress) => {
+                    info!(
+                        "{} {}: {} members checked, {} members updated, {} members failed",
+                        INFO_RESYNC_DONE,
+                        guild_id,
+                        progress.checked,
+                        progress.updated,
+                        progress.failed
+                    );
+
+                    report(&ctx, &mut message, &progress, true).await;
+                }
+                Err(why) => error!("{} of guild {}: {}", ERR_RESYNC, guild_id, why),
+            }
+
+            // Run again if another resync was requested in the meantime
+            let again = {
+                let mut resyncing = RESYNCING.lock().unwrap();
+                let index = resyncing
+                    .iter()
+                    .position(|(resyncing, _)| *resyncing == guild_id)
+                    .unwrap();
+
+                if resyncing[index].1 {
+                    resyncing[index].1 = false;
+                    true
+                } else {
+                    resyncing.remove(index);
+                    false
+                }
+            };
+
+            if !again {
+                break;
+            }
+        }
+    });
+
+    true
+}
+
+async fn resync(
+    ctx: &Context,
+    guild_id: GuildId,
+    message: &mut Option<Message>,
+) -> Result<ResyncProgress, KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    // Get all members
+    let mut members = get_members(ctx, guild_id).await?;
+
+    let mut progress = ResyncProgress {
+        total: members.len(),
+        ..Default::default()
+    };
+
+    for batch in members.chunks_mut(config.general.resync_batch_size) {
+        // The level-up roles are the same for every member, but might change during the resync
+        let level_up_roles = database.level_up_roles(guild_id).await?;
+        let stacking = database.level_up_stacking(guild_id).await?;
+
+        for member in batch {
+            progress.checked += 1;
+
+            // Never update roles of bots
+            if member.user.bot {
+                continue;
+            }
+
+            // A single member, e.g. one whose roles the bot is not allowed to manage,
+            // should not stop the resync
+            match resync_member(ctx, database.as_ref(), &level_up_roles, stacking, member).await {
+                Ok(true) => progress.updated += 1,
+                Ok(false) => {}
+                Err(why) => {
+                    error!("{} of member {}: {}", ERR_RESYNC, member.user.id, why);
+                    progress.failed += 1;
+                }
+            }
+        }
+
+        report(ctx, message, &progress, false).await;
+
+        // Wait to stay clear of the rate limits
+        sleep(Duration::from_millis(config.general.resync_delay)).await;
+    }
+
+    Ok(progress)
+}
+
+/// Brings the level-up roles of a member up to date.
+///
+/// Returns whether the roles of the member were changed
+async fn resync_member(
+    ctx: &Context,
+    storage: &dyn Storage,
+    level_up_roles: &[(RoleId, i64)],
+    stacking: bool,
+    member: &mut Member,
+) -> Result<bool, KowalskiError> {
+    let score = level_up_score(storage, member.guild_id, member.user.id).await?;
+    let (add, remove) = role_changes(level_up_roles, stacking, score, &member.roles);
+    if add.is_empty() && remove.is_empty() {
+        return Ok(false);
+    }
+
+    if !add.is_empty() {
+        member.add_roles(&ctx.http, &add).await?;
+    }
+    if !remove.is_empty() {
+        member.remove_roles(&ctx.http, &remove).await?;
+    }
+
+    Ok(true)
+}
+
+/// Shows the progress of a resync in its message, if there is one.
+async fn report(
+    ctx: &Context,
+    message: &mut Option<Message>,
+    progress: &ResyncProgress,
+    done: bool,
+) {
+    let message = match message {
+        Some(message) => message,
+        None => return,
+    };
+
+    let mut content = format!(
+        "Checked **{}/{}** members, updated the level-up roles of **{}** members.",
+        progress.checked, progress.total, progress.updated
+    );
+    if progress.failed > 0 {
+        content.push_str(&format!(
+            "\nFailed to update the level-up roles of **{}** members, \
+            the bot might not be allowed to manage the roles.",
+            progress.failed
+        ));
+    }
+    if done {
+        content.push_str("\n\nThe level-up roles of all members are up to date.");
+    }
+
+    let embed = create_embed("Resync level-up roles", &content);
+
+    if let Err(why) = message
+        .edit(&ctx.http, |message| message.set_embed(embed))
+        .await
+    {
+        error!("{}: {}", ERR_RESYNC, why);
+    }
+}
+
+/// Fills in the placeholders of a message, which are `{user}`, `{role}` and `{score}`.
+pub fn fill_template(template: &str, member: &Member, roles: &[RoleId], score: i64) -> String {
+    template
+        .replace("{user}", &member.mention().to_string())
+        .replace(
+            "{role}",
+            &roles.iter().map(|role_id| role_id.mention()).join(", "),
+        )
+        .replace("{score}", &score.to_string())
+}
@@ -1,16 +1,24 @@
+pub mod auto_moderation;
 #[cfg(feature = "event-calendar")]
 pub mod calendar;
+pub mod chart;
 pub mod client;
 pub mod commands;
 pub mod config;
 pub mod cooldowns;
 pub mod credits;
 pub mod database;
+pub mod drops;
 pub mod error;
 pub mod events;
 pub mod history;
+pub mod leaderboard;
+pub mod level_ups;
 #[cfg(feature = "nlp-model")]
 pub mod model;
+pub mod reaction_roles;
+pub mod reconciliation;
 pub mod reminders;
+pub mod snapshots;
 pub mod strings;
 pub mod utils;
@@ -0,0 +1,701 @@
+use std::{
+    fmt::{Display, Formatter},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use chrono::{Duration, Utc};
+
+use serenity::{
+    client::Context,
+    model::{
+        channel::ReactionType,
+        guild::Member,
+        id::{ChannelId, GuildId, MessageId, RoleId, UserId},
+        interactions::{
+            message_component::{ComponentType, MessageComponentInteraction},
+            InteractionResponseType,
+        },
+    },
+    prelude::Mentionable,
+};
+use tokio::time::{interval, sleep};
+use tracing::error;
+
+use crate::{
+    config::Config,
+    data,
+    database::{
+        client::Database,
+        storage::Storage,
+        types::{ReactionRole, ReactionRoleGroup, StoredEmoji},
+    },
+    error::KowalskiError,
+    error::KowalskiError::DiscordApiError,
+    pluralize,
+    strings::{ERR_CMD_ARGS_INVALID, ERR_REACTION, ERR_REACTION_ROLE_EXPIRATION},
+    utils::create_embed,
+};
+
+/// Prefix of the custom id of role menu buttons, followed by the channel, message, emoji and role.
+pub const BUTTON_PREFIX: &str = "reaction_role";
+/// Prefix of the custom id of role menu select menus, followed by the channel and message.
+pub const SELECT_PREFIX: &str = "reaction_roles";
+
+/// Whether the expiration sweeper was started already, the ready event is fired again on
+/// reconnects.
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// The change to apply when a user toggles a reaction-role.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    /// Add the role, removing the other roles of its group given.
+    Add(Vec<ReactionRole>),
+    /// Remove the role.
+    Remove,
+    /// Leave the roles of the user untouched.
+    Deny(Denial),
+}
+
+/// The reason why a user did not get a reaction-role.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Denial {
+    /// There were no slots of the role left.
+    NoSlots,
+    /// The user already holds the maximum number of roles of the group.
+    GroupFull,
+    /// The user would hold less than the minimum number of roles of the group.
+    GroupMinimum,
+    /// The user doesn't hold a role required for the role.
+    MissingRole(RoleId),
+    /// The user holds a role preventing the user from getting the role.
+    ForbiddenRole(RoleId),
+}
+
+impl Display for Denial {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Denial::NoSlots => write!(f, "there are no slots of the role left"),
+            Denial::GroupFull => write!(
+                f,
+                "you already hold the maximum number of roles of its group"
+            ),
+            Denial::GroupMinimum => {
+                write!(f, "you have to hold a minimum number of roles of its group")
+            }
+            Denial::MissingRole(role) => write!(f, "you need the role {} first", role.mention()),
+            Denial::ForbiddenRole(role) => {
+                write!(
+                    f,
+                    "you can't get it while holding the role {}",
+                    role.mention()
+                )
+            }
+        }
+    }
+}
+
+/// The outcome of a user toggling a reaction-role.
+#[derive(Debug, PartialEq)]
+pub enum Toggle {
+    Added,
+    Removed,
+    Denied(Denial),
+}
+
+/// Decides what happens when a user with the given roles toggles a reaction-role.
+///
+/// Note: If the group of the role only allows one role, the other role of the group gets replaced
+pub fn decide(
+    reaction_role: &ReactionRole,
+    message_roles: &[ReactionRole],
+    group: Option<&ReactionRoleGroup>,
+    roles: &[RoleId],
+) -> Change {
+    let has_role = roles.contains(&reaction_role.role);
+
+    let group = match (group, &reaction_role.group) {
+        (Some(group), Some(name)) if group.name == *name => group,
+        _ => {
+            return if has_role {
+                Change::Remove
+            } else {
+                Change::Add(Vec::new())
+            }
+        }
+    };
+
+    // Get the other roles of the group the user holds, a role can be bound to several emojis
+    let mut held: Vec<&ReactionRole> = Vec::new();
+    for other in message_roles {
+        if other.group.as_ref() == Some(&group.name)
+            && other.role != reaction_role.role
+            && roles.contains(&other.role)
+            && !held.iter().any(|role| role.role == other.role)
+        {
+            held.push(other);
+        }
+    }
+
+    if has_role {
+        if (held.len() as i32) < group.min {
+            Change::Deny(Denial::GroupMinimum)
+        } else {
+            Change::Remove
+        }
+    } else if (held.len() as i32) < group.max {
+        Change::Add(Vec::new())
+    } else if group.max == 1 {
+        Change::Add(held.into_iter().cloned().collect())
+    } else {
+        Change::Deny(Denial::GroupFull)
+    }
+}
+
+/// Toggles a reaction-role of a member, respecting the slots and the group of the role.
+pub async fn toggle(
+    ctx: &Context,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    member: &mut Member,
+    reaction_role: &ReactionRole,
+) -> Result<Toggle, KowalskiError> {
+    let (channel_id, message_id) = (reaction_role.channel, reaction_role.message);
+
+    // Get the group of the role
+    let group = match &reaction_role.group {
+        Some(name) => Some(
+            storage
+                .reaction_role_group(guild_id, channel_id, message_id, name)
+                .await?,
+        ),
+        None => None,
+    };
+    let message_roles = match group {
+        Some(_) => {
+            storage
+                .message_reaction_roles(guild_id, channel_id, message_id)
+                .await?
+        }
+        None => Vec::new(),
+    };
+
+    match decide(reaction_role, &message_roles, group.as_ref(), &member.roles) {
+        Change::Remove => {
+            // Remove role from user
+            member.remove_role(&ctx.http, reaction_role.role).await?;
+
+            // Give the slot of the user back
+            release(storage, guild_id, reaction_role).await?;
+            storage
+                .remove_reaction_role_expiration(guild_id, member.user.id, reaction_role)
+                .await?;
+
+            Ok(Toggle::Removed)
+        }
+        Change::Add(replaced) => {
+            // Check whether the user holds the required roles and none of the forbidden ones
+            let conditions = storage
+                .reaction_role_conditions(guild_id, reaction_role)
+                .await?;
+            for (role, required) in conditions {
+                if member.roles.contains(&role) != required {
+                    return Ok(Toggle::Denied(if required {
+                        Denial::MissingRole(role)
+                    } else {
+                        Denial::ForbiddenRole(role)
+                    }));
+                }
+            }
+
+            // Only assign the role if there was a slot left for the user
+            let reserved = storage
+                .reserve_reaction_role_slot(
+                    guild_id,
+                    channel_id,
+                    message_id,
+                    reaction_role.emoji_id,
+                    reaction_role.role,
+                )
+                .await?;
+
+            if !reserved {
+                return Ok(Toggle::Denied(Denial::NoSlots));
+            }
+
+            // Add role to user, freeing the slot again if that fails
+            if let Err(why) = member.add_role(&ctx.http, reaction_role.role).await {
+                release(storage, guild_id, reaction_role).await?;
+
+                return Err(why.into());
+            }
+
+            // Remove the role again once its duration is over
+            if let Some(duration) = reaction_role.duration {
+                storage
+                    .add_reaction_role_expiration(
+                        guild_id,
+                        member.user.id,
+                        reaction_role,
+                        Utc::now() + Duration::seconds(duration),
+                    )
+                    .await?;
+            }
+
+            // Remove the roles replaced by the new one
+            for replaced in replaced {
+                member.remove_role(&ctx.http, replaced.role).await?;
+                release(storage, guild_id, &replaced).await?;
+                storage
+                    .remove_reaction_role_expiration(guild_id, member.user.id, &replaced)
+                    .await?;
+            }
+
+            Ok(Toggle::Added)
+        }
+        Change::Deny(denial) => Ok(Toggle::Denied(denial)),
+    }
+}
+
+async fn release(
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    reaction_role: &ReactionRole,
+) -> Result<(), KowalskiError> {
+    storage
+        .release_reaction_role_slot(
+            guild_id,
+            reaction_role.channel,
+            reaction_role.message,
+            reaction_role.emoji_id,
+            reaction_role.role,
+        )
+        .await
+}
+
+/// Tells a user why a reaction-role was not changed, using a message deleted after a while.
+pub async fn report_denial(
+    ctx: &Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    reaction_role: &ReactionRole,
+    denial: Denial,
+) -> Result<(), KowalskiError> {
+    let config = data!(ctx, Config);
+
+    let content = format!(
+        "You can't change the role {}, {}.",
+        reaction_role.role.mention(),
+        denial
+    );
+
+    let message = channel_id
+        .send_message(&ctx.http, |message| {
+            message
+                .content(user_id.mention())
+                .set_embed(create_embed("Reaction-role", &content))
+        })
+        .await?;
+
+    // Delete the message once the user had time to read it
+    let http = ctx.http.clone();
+    let timeout = config.general.notification_timeout;
+    tokio::spawn(async move {
+        sleep(std::time::Duration::from_secs(timeout)).await;
+
+        if let Err(why) = message.delete(&http).await {
+            error!("{}: {}", ERR_REACTION, why);
+        }
+    });
+
+    Ok(())
+}
+
+/// Periodically removes reaction-roles whose duration is over.
+pub fn check_expirations(ctx: Context, period: std::time::Duration) {
+    // Only run one sweeper
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        // Get database
+        let database = data!(ctx, Database);
+
+        // Create the interval at which we will check for expirations
+        let mut interval = interval(period);
+
+        loop {
+            // Wait for the next tick
+            interval.tick().await;
+
+            if let Err(why) = remove_expired(&ctx, database.as_ref()).await {
+                error!("{}: {}", ERR_REACTION_ROLE_EXPIRATION, why);
+            }
+        }
+    });
+}
+
+async fn remove_expired(ctx: &Context, storage: &dyn Storage) -> Result<(), KowalskiError> {
+    // Get the expirations which are due
+    let expirations = storage.due_reaction_role_expirations().await?;
+
+    for expiration in expirations {
+        // The user may have left the guild or lost the role already
+        if let Ok(mut member) = expiration.guild.member(&ctx, expiration.user).await {
+            if member.roles.contains(&expiration.role) {
+                // Keep the expiration to try again next time
+                if let Err(why) = member.remove_role(&ctx.http, expiration.role).await {
+                    error!("{}: {}", ERR_REACTION_ROLE_EXPIRATION, why);
+                    continue;
+                }
+            }
+        }
+
+        // The user might have renewed the role in the meantime
+        if !storage
+            .complete_reaction_role_expiration(&expiration)
+            .await?
+        {
+            continue;
+        }
+
+        // Give the slot of the user back
+        storage
+            .release_reaction_role_slot(
+                expiration.guild,
+                expiration.channel,
+                expiration.message,
+                expiration.emoji,
+                expiration.role,
+            )
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Formats the duration of a timed reaction-role given in seconds.
+pub fn format_duration(duration: i64) -> String {
+    let days = duration / 86400;
+    let hours = duration % 86400 / 3600;
+
+    match (days, hours) {
+        (0, hours) => pluralize!("hour", hours),
+        (days, 0) => pluralize!("day", days),
+        (days, hours) => format!(
+            "{} and {}",
+            pluralize!("day", days),
+            pluralize!("hour", hours)
+        ),
+    }
+}
+
+/// Converts an emoji stored in the database into a reaction type.
+pub async fn reaction_type(
+    ctx: &Context,
+    guild_id: GuildId,
+    emoji: &StoredEmoji,
+) -> Result<ReactionType, KowalskiError> {
+    Ok(match emoji {
+        StoredEmoji::Unicode(string) => ReactionType::Unicode(string.clone()),
+        StoredEmoji::Guild(id) => {
+            let emoji = guild_id.emoji(&ctx.http, *id).await?;
+
+            ReactionType::Custom {
+                animated: emoji.animated,
+                id: emoji.id,
+                name: Some(emoji.name),
+            }
+        }
+    })
+}
+
+/// Creates the custom id of a role menu button.
+pub fn button_id(reaction_role: &ReactionRole) -> String {
+    format!(
+        "{}:{}:{}:{}:{}",
+        BUTTON_PREFIX,
+        reaction_role.channel.0,
+        reaction_role.message.0,
+        reaction_role.emoji_id,
+        reaction_role.role.0
+    )
+}
+
+/// Creates the custom id of a role menu select menu.
+pub fn select_id(channel_id: ChannelId, message_id: MessageId) -> String {
+    format!("{}:{}:{}", SELECT_PREFIX, channel_id.0, message_id.0)
+}
+
+/// Creates the value of a role menu select menu option.
+pub fn select_value(reaction_role: &ReactionRole) -> String {
+    format!("{}:{}", reaction_role.emoji_id, reaction_role.role.0)
+}
+
+/// Parses the custom id of a role menu component into the prefix and the numbers following it.
+fn parse_id(custom_id: &str) -> Option<(&str, Vec<u64>)> {
+    let mut parts = custom_id.split(':');
+    let prefix = parts.next()?;
+
+    let numbers = parts
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<u64>>>()?;
+
+    Some((prefix, numbers))
+}
+
+/// Checks whether a message component belongs to a role menu.
+pub fn is_role_menu(message_component: &MessageComponentInteraction) -> bool {
+    matches!(
+        parse_id(&message_component.data.custom_id),
+        Some((BUTTON_PREFIX, _)) | Some((SELECT_PREFIX, _))
+    )
+}
+
+/// Answers a user clicking a button or choosing roles of a role menu.
+pub async fn answer_role_menu(
+    ctx: &Context,
+    message_component: &MessageComponentInteraction,
+) -> Result<(), KowalskiError> {
+    // Toggling roles can take longer than the interaction allows, so answer later
+    message_component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                .interaction_response_data(|data| data.ephemeral(true))
+        })
+        .await?;
+
+    let database = data!(ctx, Database);
+
+    let guild_id = message_component.guild_id.unwrap();
+    let mut member = message_component.member.clone().unwrap();
+
+    // Role menus stay around when the module gets disabled
+    if !database.module_status(guild_id).await?.reaction_roles {
+        message_component
+            .edit_original_interaction_response(&ctx.http, |response| {
+                response.add_embed(create_embed(
+                    "Role menu",
+                    "Reaction-roles are disabled on this guild.",
+                ))
+            })
+            .await?;
+
+        return Ok(());
+    }
+
+    // Buttons also carry the emoji and the role of the reaction-role
+    let (prefix, ids) = match parse_id(&message_component.data.custom_id) {
+        Some((BUTTON_PREFIX, ids)) if ids.len() == 4 => (BUTTON_PREFIX, ids),
+        Some((SELECT_PREFIX, ids)) if ids.len() == 2 => (SELECT_PREFIX, ids),
+        _ => return Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+    };
+    let (channel_id, message_id) = (ChannelId(ids[0]), MessageId(ids[1]));
+
+    // Get the reaction-roles offered by the menu
+    let reaction_roles = database
+        .message_reaction_roles(guild_id, channel_id, message_id)
+        .await?;
+
+    // Get the reaction-roles the user wants to toggle and whether the user wants to hold them
+    let mut toggles: Vec<(&ReactionRole, bool)> = Vec::new();
+    match (prefix, &message_component.data.component_type) {
+        (BUTTON_PREFIX, ComponentType::Button) => {
+            let reaction_role = reaction_roles.iter().find(|reaction_role| {
+                reaction_role.emoji_id as u64 == ids[2] && reaction_role.role.0 == ids[3]
+            });
+
+            if let Some(reaction_role) = reaction_role {
+                toggles.push((reaction_role, !member.roles.contains(&reaction_role.role)));
+            }
+        }
+        (SELECT_PREFIX, ComponentType::SelectMenu) => {
+            let values = &message_component.data.values;
+
+            // Add the chosen roles first, so roles of a group get replaced instead of refused
+            for adding in [true, false] {
+                for reaction_role in &reaction_roles {
+                    let chosen = values.contains(&select_value(reaction_role));
+
+                    if chosen == adding
+                        && !toggles
+                            .iter()
+                            .any(|(toggled, _)| toggled.role == reaction_role.role)
+                    {
+                        toggles.push((reaction_role, chosen));
+                    }
+                }
+            }
+        }
+        _ => {}
+    }
+
+    // Toggle the roles and describe what happened
+    let mut lines = Vec::new();
+    for (reaction_role, wanted) in toggles {
+        // Skip roles the user already holds (or not), roles of a group may have been replaced
+        if member.roles.contains(&reaction_role.role) == wanted {
+            continue;
+        }
+
+        let role = reaction_role.role.mention();
+        let line = match toggle(ctx, database.as_ref(), guild_id, &mut member, reaction_role)
+            .await?
+        {
+            Toggle::Added => format!("You got the role {}.", role),
+            Toggle::Removed => format!("You no longer have the role {}.", role),
+            Toggle::Denied(denial) => format!("You can't change the role {}, {}.", role, denial),
+        };
+
+        lines.push(line);
+    }
+
+    let content = if lines.is_empty() {
+        "Nothing has changed.".to_string()
+    } else {
+        lines.join("\n")
+    };
+
+    message_component
+        .edit_original_interaction_response(&ctx.http, |response| {
+            response.add_embed(create_embed("Role menu", &content))
+        })
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::memory::MemoryStorage;
+
+    const GUILD: GuildId = GuildId(1);
+    const CHANNEL: ChannelId = ChannelId(2);
+    const MESSAGE: MessageId = MessageId(3);
+
+    /// Sets up a message with a reaction-role for each of the given roles, all in one group.
+    async fn setup(roles: &[u64], min: i32, max: i32) -> (MemoryStorage, Vec<ReactionRole>) {
+        let storage = MemoryStorage::new();
+
+        for (index, &role) in roles.iter().enumerate() {
+            let emoji = storage
+                .get_emoji(GUILD, &ReactionType::Unicode(index.to_string()))
+                .await
+                .unwrap();
+            storage
+                .add_reaction_role(GUILD, CHANNEL, MESSAGE, emoji, RoleId(role), None)
+                .await
+                .unwrap();
+            storage
+                .set_reaction_role_group_of(GUILD, CHANNEL, MESSAGE, RoleId(role), Some("group"))
+                .await
+                .unwrap();
+        }
+
+        let mut group = ReactionRoleGroup::new(CHANNEL, MESSAGE, "group");
+        group.min = min;
+        group.max = max;
+        storage
+            .set_reaction_role_group(GUILD, &group)
+            .await
+            .unwrap();
+
+        let message_roles = storage
+            .message_reaction_roles(GUILD, CHANNEL, MESSAGE)
+            .await
+            .unwrap();
+
+        (storage, message_roles)
+    }
+
+    async fn decide_for(
+        storage: &MemoryStorage,
+        message_roles: &[ReactionRole],
+        role: u64,
+        roles: &[u64],
+    ) -> Change {
+        let reaction_role = message_roles
+            .iter()
+            .find(|reaction_role| reaction_role.role == RoleId(role))
+            .unwrap();
+        let group = storage
+            .reaction_role_group(GUILD, CHANNEL, MESSAGE, "group")
+            .await
+            .unwrap();
+        let roles: Vec<_> = roles.iter().map(|&role| RoleId(role)).collect();
+
+        decide(reaction_role, message_roles, Some(&group), &roles)
+    }
+
+    #[tokio::test]
+    async fn toggles_roles_without_group() {
+        let (storage, _) = setup(&[10], 0, 1).await;
+        storage
+            .set_reaction_role_group_of(GUILD, CHANNEL, MESSAGE, RoleId(10), None)
+            .await
+            .unwrap();
+        let message_roles = storage
+            .message_reaction_roles(GUILD, CHANNEL, MESSAGE)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[]).await,
+            Change::Add(Vec::new())
+        );
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[10]).await,
+            Change::Remove
+        );
+    }
+
+    #[tokio::test]
+    async fn replaces_role_of_single_role_group() {
+        let (storage, message_roles) = setup(&[10, 11, 12], 0, 1).await;
+
+        let held = message_roles
+            .iter()
+            .find(|reaction_role| reaction_role.role == RoleId(11))
+            .cloned()
+            .unwrap();
+
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[11]).await,
+            Change::Add(vec![held])
+        );
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[]).await,
+            Change::Add(Vec::new())
+        );
+    }
+
+    #[tokio::test]
+    async fn denies_role_of_full_group() {
+        let (storage, message_roles) = setup(&[10, 11, 12], 0, 2).await;
+
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[11]).await,
+            Change::Add(Vec::new())
+        );
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[11, 12]).await,
+            Change::Deny(Denial::GroupFull)
+        );
+    }
+
+    #[tokio::test]
+    async fn keeps_minimum_of_group() {
+        let (storage, message_roles) = setup(&[10, 11], 1, 2).await;
+
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[10]).await,
+            Change::Deny(Denial::GroupMinimum)
+        );
+        assert_eq!(
+            decide_for(&storage, &message_roles, 10, &[10, 11]).await,
+            Change::Remove
+        );
+    }
+}
@@ -0,0 +1,458 @@
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use chrono::Utc;
+use itertools::Itertools;
+use serenity::{
+    client::Context,
+    model::{
+        channel::{Message, ReactionType},
+        id::{ChannelId, GuildId, MessageId, UserId},
+        user::User,
+    },
+};
+use tokio::time::sleep;
+use tracing::{error, info};
+
+use crate::{
+    config::Config,
+    cooldowns::Vote,
+    data,
+    database::{
+        client::Database,
+        storage::Storage,
+        types::{ReactionRole, ScoreReaction},
+    },
+    error::KowalskiError,
+    events::reaction::{auto_moderate, update_roles, vote_allowed},
+    reaction_roles::toggle,
+    strings::{ERR_RECONCILIATION, INFO_RECONCILIATION_DONE, INFO_RECONCILIATION_PROGRESS},
+};
+
+/// Whether a reconciliation is running already, the ready event is fired again on reconnects.
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// The amount of messages after which the progress gets logged.
+const PROGRESS_INTERVAL: usize = 50;
+
+/// The counts of changes made while reconciling a guild.
+#[derive(Default)]
+struct Progress {
+    messages: usize,
+    added: usize,
+    removed: usize,
+    toggled: usize,
+}
+
+/// Reconciles the reactions added or removed while the bot was offline in the background.
+pub fn reconcile_reactions(ctx: Context) {
+    // Only run one reconciliation at a time
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        if let Err(why) = reconcile(&ctx).await {
+            error!("{}: {}", ERR_RECONCILIATION, why);
+        }
+
+        RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
+async fn reconcile(ctx: &Context) -> Result<(), KowalskiError> {
+    // Get config and database
+    let (config, database) = data!(ctx, (Config, Database));
+
+    for (guild_id, status) in database.module_statuses().await? {
+        let mut progress = Progress::default();
+
+        if status.reaction_roles {
+            if let Err(why) =
+                reconcile_reaction_roles(ctx, &config, database.as_ref(), guild_id, &mut progress)
+                    .await
+            {
+                error!("{} of guild {}: {}", ERR_RECONCILIATION, guild_id, why);
+            }
+        }
+
+        if status.score {
+            if let Err(why) =
+                reconcile_scores(ctx, &config, database.as_ref(), guild_id, &mut progress).await
+            {
+                error!("{} of guild {}: {}", ERR_RECONCILIATION, guild_id, why);
+            }
+        }
+
+        info!(
+            "{} {}: {} messages checked, {} reactions added, {} reactions removed, {} reaction-roles toggled",
+            INFO_RECONCILIATION_DONE,
+            guild_id,
+            progress.messages,
+            progress.added,
+            progress.removed,
+            progress.toggled
+        );
+    }
+
+    Ok(())
+}
+
+async fn reconcile_reaction_roles(
+    ctx: &Context,
+    config: &Config,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    progress: &mut Progress,
+) -> Result<(), KowalskiError> {
+    // Get the messages with reaction-roles
+    let messages: Vec<_> = storage
+        .guild_reaction_roles(guild_id)
+        .await?
+        .iter()
+        .map(|reaction_role| (reaction_role.channel, reaction_role.message))
+        .unique()
+        .collect();
+
+    for (channel_id, message_id) in messages {
+        // The message might have been deleted in the meantime
+        let message = match channel_id.message(&ctx.http, message_id).await {
+            Ok(message) => message,
+            Err(_) => continue,
+        };
+
+        for reaction in &message.reactions {
+            // Get the reaction-roles bound to the emoji
+            let emoji = match storage.find_emoji(&reaction.reaction_type).await? {
+                Some(emoji) => emoji,
+                None => continue,
+            };
+            let reaction_roles = storage
+                .reaction_roles(guild_id, channel_id, message_id, emoji)
+                .await?;
+            if reaction_roles.is_empty() {
+                continue;
+            }
+
+            // Every reaction left by a user is a reaction-role the user did not get yet
+            for user in reaction_users(ctx, &message, &reaction.reaction_type).await? {
+                if user.bot {
+                    continue;
+                }
+
+                // A failing member shouldn't stop the reconciliation of the other members
+                match reconcile_member(
+                    ctx,
+                    storage,
+                    guild_id,
+                    &message,
+                    &reaction.reaction_type,
+                    &reaction_roles,
+                    user.id,
+                )
+                .await
+                {
+                    Ok(true) => progress.toggled += 1,
+                    Ok(false) => {}
+                    Err(why) => error!(
+                        "{} of user {} in guild {}: {}",
+                        ERR_RECONCILIATION, user.id, guild_id, why
+                    ),
+                }
+            }
+        }
+
+        pause(config, progress).await;
+    }
+
+    Ok(())
+}
+
+/// Toggles the reaction-roles a member reacted for and removes the reaction afterwards.
+///
+/// Returns whether the user is still a member whose roles were toggled.
+///
+/// Note: The reaction stays if toggling fails, so the next reconciliation tries again
+async fn reconcile_member(
+    ctx: &Context,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    message: &Message,
+    reaction_type: &ReactionType,
+    reaction_roles: &[ReactionRole],
+    user_id: UserId,
+) -> Result<bool, KowalskiError> {
+    let toggled = match guild_id.member(ctx, user_id).await {
+        Ok(mut member) => {
+            for reaction_role in reaction_roles {
+                toggle(ctx, storage, guild_id, &mut member, reaction_role).await?;
+            }
+
+            true
+        }
+        Err(_) => false,
+    };
+
+    // Remove the reaction
+    message
+        .channel_id
+        .delete_reaction(&ctx.http, message.id, Some(user_id), reaction_type.clone())
+        .await?;
+
+    Ok(toggled)
+}
+
+async fn reconcile_scores(
+    ctx: &Context,
+    config: &Config,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    progress: &mut Progress,
+) -> Result<(), KowalskiError> {
+    let mut messages = Vec::new();
+
+    // Get the recent messages of the channels in which reactions count
+    for channel_id in storage.score_channels(guild_id).await? {
+        if let Ok(recent) = channel_id
+            .messages(&ctx.http, |retriever| {
+                retriever.limit(config.general.reconciliation_messages)
+            })
+            .await
+        {
+            messages.extend(recent);
+        }
+    }
+
+    // Votes on messages from before the current season were reset with it
+    let season_start = storage
+        .season_start(guild_id)
+        .await?
+        .map_or(0, |started| started.timestamp());
+    messages.retain(|message| message.id.created_at().unix_timestamp() >= season_start);
+
+    // Get the registered messages which are not too old to be looked at
+    let cutoff = (Utc::now() - chrono::Duration::days(config.general.reconciliation_days))
+        .timestamp()
+        .max(season_start);
+    let known: HashSet<_> = messages
+        .iter()
+        .map(|message| (message.channel_id, message.id))
+        .collect();
+    let tracked: Vec<(ChannelId, MessageId)> = storage
+        .tracked_messages(guild_id)
+        .await?
+        .into_iter()
+        .filter(|key| !known.contains(key))
+        .filter(|(_, message_id)| message_id.created_at().unix_timestamp() >= cutoff)
+        .collect();
+
+    let total = messages.len() + tracked.len();
+    info!(
+        "{} {}: {} messages to check",
+        INFO_RECONCILIATION_PROGRESS, guild_id, total
+    );
+
+    let mut checked = 0;
+
+    for message in messages {
+        reconcile_message(ctx, storage, guild_id, message, progress).await?;
+
+        checked += 1;
+        log_progress(guild_id, checked, total);
+        pause(config, progress).await;
+    }
+
+    for (channel_id, message_id) in tracked {
+        // The message might have been deleted in the meantime
+        if let Ok(message) = channel_id.message(&ctx.http, message_id).await {
+            reconcile_message(ctx, storage, guild_id, message, progress).await?;
+        }
+
+        checked += 1;
+        log_progress(guild_id, checked, total);
+        pause(config, progress).await;
+    }
+
+    Ok(())
+}
+
+async fn reconcile_message(
+    ctx: &Context,
+    storage: &dyn Storage,
+    guild_id: GuildId,
+    message: Message,
+    progress: &mut Progress,
+) -> Result<(), KowalskiError> {
+    // Get the up- and downvotes which are registered
+    let registered = storage
+        .message_score_reactions(guild_id, message.channel_id, message.id)
+        .await?;
+
+    // Get the votes removed by the bot, they must not be counted again
+    let removed = storage
+        .removed_votes(guild_id, message.channel_id, message.id)
+        .await?;
+
+    // Get the up- and downvotes which are actually on the message
+    let scoring = storage
+        .channel_scoring(guild_id, message.channel_id)
+        .await?;
+    let mut actual = HashSet::new();
+    let mut missed = Vec::new();
+    for reaction in &message.reactions {
+        let emoji = match storage.find_emoji(&reaction.reaction_type).await? {
+            Some(emoji) => emoji,
+            None => continue,
+        };
+        let weight = match storage.score_emoji(guild_id, emoji).await? {
+            Some(weight) => weight as i64 * scoring.multiplier as i64,
+            None => continue,
+        };
+
+        // Reactions bound to reaction-roles don't count as votes
+        let counted = !scoring.excluded
+            && storage
+                .reaction_roles(guild_id, message.channel_id, message.id, emoji)
+                .await?
+                .is_empty();
+
+        for user in reaction_users(ctx, &message, &reaction.reaction_type).await? {
+            // Users can't vote on their own messages
+            if user.id == message.author.id {
+                continue;
+            }
+            actual.insert((user.id, emoji));
+
+            // Only the votes given while the bot was offline were missed
+            let registered_already = registered
+                .iter()
+                .any(|reaction| reaction.user_from == user.id && reaction.emoji == emoji);
+            if counted && !registered_already && !removed.contains(&(user.id, emoji)) {
+                missed.push((user.id, emoji, weight, reaction.reaction_type.clone()));
+            }
+        }
+    }
+
+    let mut affected: HashSet<UserId> = HashSet::new();
+
+    // Insert the reactions added while the bot was offline, if they would have counted
+    for (user_from, emoji, weight, reaction_type) in missed {
+        // The user might have left the guild in the meantime
+        let member = match guild_id.member(ctx, user_from).await {
+            Ok(member) => member,
+            Err(_) => continue,
+        };
+
+        let vote = Vote {
+            guild: guild_id,
+            user_from,
+            user_to: message.author.id,
+            upvote: weight > 0,
+        };
+
+        if !vote_allowed(ctx, storage, &vote, weight, &member.roles).await? {
+            // Remove the reaction, just like when the bot is online
+            message
+                .channel_id
+                .delete_reaction(&ctx.http, message.id, Some(user_from), reaction_type)
+                .await?;
+            continue;
+        }
+
+        storage
+            .add_score_reaction(&ScoreReaction {
+                guild: guild_id,
+                user_from,
+                user_to: message.author.id,
+                channel: message.channel_id,
+                message: message.id,
+                emoji,
+                native: true,
+                // The time the reaction was added at is unknown
+                time: Utc::now(),
+            })
+            .await?;
+
+        affected.insert(message.author.id);
+        progress.added += 1;
+    }
+
+    // Delete the reactions removed while the bot was offline
+    for reaction in &registered {
+        if !actual.contains(&(reaction.user_from, reaction.emoji)) {
+            storage
+                .remove_score_reaction(
+                    guild_id,
+                    reaction.user_from,
+                    message.channel_id,
+                    message.id,
+                    reaction.emoji,
+                )
+                .await?;
+
+            affected.insert(reaction.user_to);
+            progress.removed += 1;
+        }
+    }
+
+    if affected.is_empty() {
+        return Ok(());
+    }
+
+    // Update the roles of the users whose score changed, they might have left the guild
+    for user_id in affected {
+        if let Ok(mut member) = guild_id.member(ctx, user_id).await {
+            update_roles(ctx, storage, &mut member).await?;
+        }
+    }
+
+    // Auto moderate the message if necessary
+    auto_moderate(ctx, storage, guild_id, message).await
+}
+
+/// Gets all users who reacted with a certain emoji, requesting them page by page.
+async fn reaction_users(
+    ctx: &Context,
+    message: &Message,
+    reaction_type: &ReactionType,
+) -> Result<Vec<User>, KowalskiError> {
+    let mut users: Vec<User> = Vec::new();
+
+    loop {
+        let page = message
+            .reaction_users(
+                &ctx.http,
+                reaction_type.clone(),
+                Some(100),
+                users.last().map(|user| user.id),
+            )
+            .await?;
+
+        let done = page.len() < 100;
+        users.extend(page);
+
+        if done {
+            return Ok(users);
+        }
+    }
+}
+
+/// Logs the progress of a guild every few messages.
+fn log_progress(guild_id: GuildId, checked: usize, total: usize) {
+    if checked.is_multiple_of(PROGRESS_INTERVAL) || checked == total {
+        info!(
+            "{} {}: {}/{} messages checked",
+            INFO_RECONCILIATION_PROGRESS, guild_id, checked, total
+        );
+    }
+}
+
+/// Counts a checked message and waits to stay clear of the rate limits.
+async fn pause(config: &Config, progress: &mut Progress) {
+    progress.messages += 1;
+
+    sleep(Duration::from_millis(config.general.reconciliation_delay)).await;
+}
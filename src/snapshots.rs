@@ -0,0 +1,44 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use serenity::client::Context;
+use tokio::time::interval;
+use tracing::error;
+
+use crate::{
+    data,
+    database::{client::Database, storage::Storage},
+    strings::ERR_SCORE_SNAPSHOT,
+};
+
+/// Whether the snapshot loop was started already, the ready event is fired again on reconnects.
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Repeatedly record the current scores, so the score history of the users can be charted.
+///
+/// Note: Only the last snapshot of each day is kept
+pub fn take_snapshots(ctx: Context, period: Duration) {
+    // Only run one snapshot loop
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        // Get database
+        let database = data!(ctx, Database);
+
+        // Create the interval at which we will take the snapshots
+        let mut interval = interval(period);
+
+        loop {
+            // Wait for the next tick
+            interval.tick().await;
+
+            if let Err(why) = database.snapshot_scores().await {
+                error!("{}: {}", ERR_SCORE_SNAPSHOT, why);
+            }
+        }
+    });
+}
@@ -3,9 +3,15 @@ pub const INFO_CMD_GLOBAL: &str = "Global commands registered.";
 pub const INFO_CMD_MODULE: &str = "Module commands registered.";
 pub const INFO_CONNECTED: &str = "Connection to Discord API established!";
 pub const INFO_DB_CONNECTED: &str = "Database connection established.";
+pub const INFO_DB_MIGRATED: &str = "Applied database migration";
+pub const INFO_DB_RECONNECTED: &str = "Database connection re-established.";
 pub const INFO_DB_SETUP: &str = "Database setup complete.";
+pub const INFO_RECONCILIATION_DONE: &str = "Reconciled the reactions of guild";
+pub const INFO_RECONCILIATION_PROGRESS: &str = "Reconciling the reactions of guild";
+pub const INFO_RESYNC_DONE: &str = "Resynchronized the level-up roles of guild";
 // Error messages
 pub const ERR_API_LOAD: &str = "Failed to request information from the REST API";
+pub const ERR_AUTO_MODERATION: &str = "Failed to apply the auto-moderation rule";
 pub const ERR_AUTOCOMPLETE: &str = "Failed to answer the autocomplete request";
 pub const ERR_CALENDAR: &str = "Failed to host the calendar";
 pub const ERR_CLIENT: &str = "Client error";
@@ -22,15 +28,31 @@ pub const ERR_CMD_PERMISSION: &str =
     "A user with insufficient permissions tried to execute the command";
 pub const ERR_CONFIG_PARSE: &str = "Failed to parse config file";
 pub const ERR_CONFIG_READ: &str = "Failed to read config file";
+pub const ERR_COOLDOWN_EVICTION: &str = "Failed to remove the expired cooldowns";
 pub const ERR_DATA_ACCESS: &str = "Failed to access the global data";
+pub const ERR_DB_CLOSED: &str = "Removed closed database connections from the pool";
 pub const ERR_DB_CONNECTION: &str = "Database connection error";
+pub const ERR_DB_MIGRATIONS_ORDER: &str = "Database migrations are not numbered consecutively";
 pub const ERR_DB_QUERY: &str = "Failed to execute the database query";
+pub const ERR_DB_RECONNECT: &str = "Failed to connect to the database";
+pub const ERR_DB_VERSION_NEWER: &str =
+    "The database schema is newer than the schema supported by this version of the bot";
 pub const ERR_ENV_NOT_SET: &str = "Environment variable not set";
+pub const ERR_DROP: &str = "Failed to hand out the dropped score";
+pub const ERR_LEVEL_UP_ANNOUNCEMENT: &str = "Failed to announce the level change";
+pub const ERR_LEVEL_UP_ROLES: &str = "Failed to update the level-up roles";
 pub const ERR_MEMBER_REMOVAL: &str = "Failed to handle the member removal event";
 pub const ERR_MESSAGE_COMPONENT: &str = "Failed to answer the message component request";
 pub const ERR_MODEL_CREATE: &str = "Failed to create the model";
 pub const ERR_REACTION: &str = "Failed to handle the reaction event";
+pub const ERR_REACTION_ROLE_EXPIRATION: &str = "Failed to remove an expired reaction-role";
+pub const ERR_RECONCILIATION: &str = "Failed to reconcile the reactions";
 pub const ERR_REMINDER: &str = "Failed to send the reminder";
+pub const ERR_RESYNC: &str = "Failed to resynchronize the level-up roles";
+pub const ERR_SCORE_SNAPSHOT: &str = "Failed to take the score snapshots";
+pub const ERR_TLS_CERTIFICATE: &str = "No certificates found in file";
+pub const ERR_TLS_KEY: &str = "No private key found in file";
+pub const ERR_TLS_KEY_PAIR: &str = "Client certificate and client key have to be set together";
 // User error messages
 pub const ERR_USER_TITLE: &str = "Looks like something really went wrong here :/";
 pub const ERR_USER_EXECUTION_FAILED: &str =
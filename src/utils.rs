@@ -6,15 +6,16 @@ use itertools::Itertools;
 use linked_hash_map::LinkedHashMap;
 use serde::Deserialize;
 #[cfg(feature = "nlp-model")]
-use serenity::model::id::{ChannelId, UserId};
+use serenity::model::id::UserId;
 use serenity::{
     builder::{
         CreateActionRow, CreateApplicationCommand, CreateApplicationCommandOption, CreateEmbed,
     },
     client::Context,
     model::{
-        channel::ChannelType,
-        id::GuildId,
+        channel::{AttachmentType, ChannelType},
+        guild::Member,
+        id::{ChannelId, GuildId, MessageId},
         interactions::{
             application_command::{
                 ApplicationCommandInteraction, ApplicationCommandInteractionDataOption,
@@ -36,6 +37,9 @@ use crate::{
     strings::{ERR_CMD_ARGS_INVALID, ERR_CMD_CREATION, ERR_CMD_SEND_FAILURE},
 };
 
+/// The amount of members requested per page.
+const MEMBER_PAGE_SIZE: u64 = 1000;
+
 #[macro_export]
 macro_rules! data {
     ( $ctx:expr, ( $( $type:ty ),*) ) => {
@@ -191,6 +195,45 @@ pub async fn send_response_complex<F>(
 where
     F: Fn(&mut CreateEmbed) -> &mut CreateEmbed,
 {
+    let mut embed = create_response_embed(command_config, title, content);
+
+    // Apply changed by the given function
+    update(&mut embed);
+
+    edit_embed(ctx, command, embed, action_rows).await
+}
+
+/// Edit a embed response, given the title, content, a function further editing the embed and a file to attach.
+///
+/// Note: The embed can display the file as its image by referring to it as `attachment://<filename>`
+pub async fn send_response_attachment<F>(
+    ctx: &Context,
+    command: &ApplicationCommandInteraction,
+    command_config: &Command,
+    title: &str,
+    content: &str,
+    update: F,
+    file: AttachmentType<'_>,
+) -> Result<(), KowalskiError>
+where
+    F: Fn(&mut CreateEmbed) -> &mut CreateEmbed,
+{
+    let mut embed = create_response_embed(command_config, title, content);
+
+    // Apply changed by the given function
+    update(&mut embed);
+
+    // Interaction responses can't get files attached, so edit the message itself
+    command
+        .get_interaction_response(&ctx.http)
+        .await?
+        .edit(ctx, |message| message.set_embed(embed).attachment(file))
+        .await?;
+
+    Ok(())
+}
+
+fn create_response_embed(command_config: &Command, title: &str, content: &str) -> CreateEmbed {
     let mut embed = create_embed(title, content);
     embed.color(Colour::from((47, 49, 54)));
 
@@ -199,10 +242,7 @@ where
         embed.footer(|footer| footer.text(format!("Module: {:?}", module)));
     }
 
-    // Apply changed by the given function
-    update(&mut embed);
-
-    edit_embed(ctx, command, embed, action_rows).await
+    embed
 }
 
 /// Send a failure embed response, given the title and content.
@@ -420,6 +460,25 @@ where
     Ok(result)
 }
 
+/// Parse a message given either its link or its id in the given channel.
+pub fn parse_message(
+    message: &str,
+    channel_id: ChannelId,
+) -> Result<(ChannelId, MessageId), KowalskiError> {
+    // Links end with the channel and the message
+    let mut parts = message.trim().rsplit('/');
+    let message_id = parts.next().and_then(|part| part.parse().ok());
+    let channel_id = match parts.next() {
+        Some(part) => part.parse().ok(),
+        None => Some(channel_id.0),
+    };
+
+    match (channel_id, message_id) {
+        (Some(channel_id), Some(message_id)) => Ok((ChannelId(channel_id), MessageId(message_id))),
+        _ => Err(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string())),
+    }
+}
+
 /// Parse a command argument given an index and resolve it.
 pub fn parse_arg_resolved(
     args: &[ApplicationCommandInteractionDataOption],
@@ -432,6 +491,29 @@ pub fn parse_arg_resolved(
         .ok_or(DiscordApiError(ERR_CMD_ARGS_INVALID.to_string()))
 }
 
+/// Get all members of a guild, requesting them page by page.
+pub async fn get_members(ctx: &Context, guild_id: GuildId) -> Result<Vec<Member>, KowalskiError> {
+    let mut members: Vec<Member> = Vec::new();
+    loop {
+        let page = guild_id
+            .members(
+                &ctx.http,
+                Some(MEMBER_PAGE_SIZE),
+                members.last().map(|member| member.user.id),
+            )
+            .await?;
+
+        let done = (page.len() as u64) < MEMBER_PAGE_SIZE;
+        members.extend(page);
+
+        if done {
+            break;
+        }
+    }
+
+    Ok(members)
+}
+
 #[cfg(feature = "nlp-model")]
 /// Get last messages of the current channel which are relevant for analysis
 pub async fn get_relevant_messages(